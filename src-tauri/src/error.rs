@@ -0,0 +1,166 @@
+//! Why a launch failed, in a shape the frontend can act on. Every launch
+//! surface — `launch_agent`, `launch_shell`, `preview_launch` and IDE Mode's
+//! `spawn_pty` — reports failures as a `LaunchError`, so the UI can point at
+//! the offending request field or offer a targeted fix (re-detect the agent
+//! path, pick another terminal) instead of parsing a sentence. `Display`
+//! renders the same sentence the log gets.
+
+use std::fmt;
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum LaunchError {
+    /// A request field was rejected before anything ran. `field` is the
+    /// request's camelCase field name, e.g. "agentPath" or "flags".
+    #[serde(rename_all = "camelCase")]
+    Validation { field: &'static str, reason: String },
+    /// A path the launch needs does not exist. `field` as for `Validation`.
+    #[serde(rename_all = "camelCase")]
+    MissingPath { field: &'static str, path: String },
+    /// The request asks for something this platform cannot do, e.g. a tmux
+    /// launch on Windows. `field` as for `Validation`; `platform` is
+    /// `std::env::consts::OS`.
+    #[serde(rename_all = "camelCase")]
+    Unsupported { field: &'static str, platform: String },
+    /// A secret the request references (see secrets.rs) is not in the store.
+    #[serde(rename_all = "camelCase")]
+    MissingSecret { name: String },
+    /// The OS refused to start `program`. `os_error_kind` is the
+    /// `std::io::ErrorKind` name, e.g. "NotFound" or "PermissionDenied".
+    #[serde(rename_all = "camelCase")]
+    Spawn { program: String, os_error_kind: String, message: String },
+    /// `program` started but exited unsuccessfully before the launch counted
+    /// as done. `code` is None when it was killed by a signal.
+    #[serde(rename_all = "camelCase")]
+    Exited { program: String, code: Option<i32>, stderr: String },
+    /// Launcher-side I/O other than the spawn itself failed, e.g. writing the
+    /// pre-launch script or opening a PTY.
+    #[serde(rename_all = "camelCase")]
+    Io { context: String, message: String },
+    /// Every backend was tried and none started; one attempt per backend, in
+    /// the order tried.
+    #[serde(rename_all = "camelCase")]
+    FallbackExhausted { attempts: Vec<LaunchAttempt> },
+}
+
+/// One backend's failed try, inside `LaunchError::FallbackExhausted`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchAttempt {
+    pub backend: String,
+    pub error: LaunchError,
+}
+
+impl LaunchError {
+    pub(crate) fn validation(field: &'static str, reason: impl Into<String>) -> Self {
+        LaunchError::Validation { field, reason: reason.into() }
+    }
+
+    pub(crate) fn unsupported(field: &'static str) -> Self {
+        LaunchError::Unsupported { field, platform: std::env::consts::OS.to_string() }
+    }
+
+    pub(crate) fn spawn(program: impl Into<String>, e: &std::io::Error) -> Self {
+        LaunchError::Spawn {
+            program: program.into(),
+            os_error_kind: format!("{:?}", e.kind()),
+            message: e.to_string(),
+        }
+    }
+
+    pub(crate) fn io(context: impl Into<String>, e: impl fmt::Display) -> Self {
+        LaunchError::Io { context: context.into(), message: e.to_string() }
+    }
+}
+
+impl fmt::Display for LaunchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LaunchError::Validation { reason, .. } => f.write_str(reason),
+            LaunchError::MissingPath { field: "projectPath", path } => {
+                write!(f, "Project directory does not exist: {}", path)
+            }
+            LaunchError::MissingPath { field: "agentPath", path } => {
+                write!(f, "Agent executable not found: {}", path)
+            }
+            LaunchError::MissingPath { field, path } => write!(f, "{} does not exist: {}", field, path),
+            LaunchError::Unsupported { field, platform } => write!(f, "{} is not supported on {}", field, platform),
+            LaunchError::MissingSecret { name } => write!(f, "Secret not found: {}", name),
+            LaunchError::Spawn { program, message, .. } => write!(f, "{} spawn failed: {}", program, message),
+            LaunchError::Exited { program, code, stderr } => {
+                write!(f, "{} exited with code: {:?}", program, code)?;
+                if !stderr.is_empty() {
+                    write!(f, ": {}", stderr)?;
+                }
+                Ok(())
+            }
+            LaunchError::Io { context, message } => write!(f, "{}: {}", context, message),
+            LaunchError::FallbackExhausted { attempts } => {
+                let tried: Vec<&str> = attempts.iter().map(|a| a.backend.as_str()).collect();
+                write!(f, "No launch method succeeded (tried: {})", tried.join(", "))?;
+                if let Some(last) = attempts.last() {
+                    write!(f, "; last error: {}", last.error)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for LaunchError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The wire shape is what the frontend switches on: a camelCase `kind`
+    /// tag and camelCase fields.
+    #[test]
+    fn serializes_tagged_camel_case() {
+        let e = LaunchError::FallbackExhausted {
+            attempts: vec![LaunchAttempt {
+                backend: "kitty".to_string(),
+                error: LaunchError::spawn(
+                    "kitty",
+                    &std::io::Error::from(std::io::ErrorKind::NotFound),
+                ),
+            }],
+        };
+        let v = serde_json::to_value(&e).unwrap();
+        assert_eq!(v["kind"], "fallbackExhausted");
+        assert_eq!(v["attempts"][0]["backend"], "kitty");
+        assert_eq!(v["attempts"][0]["error"]["kind"], "spawn");
+        assert_eq!(v["attempts"][0]["error"]["osErrorKind"], "NotFound");
+
+        let v = serde_json::to_value(LaunchError::MissingPath {
+            field: "agentPath",
+            path: "/x".to_string(),
+        })
+        .unwrap();
+        assert_eq!(v["kind"], "missingPath");
+        assert_eq!(v["field"], "agentPath");
+    }
+
+    #[test]
+    fn display_matches_log_wording() {
+        let e = LaunchError::Unsupported { field: "multiplexer", platform: "windows".to_string() };
+        assert_eq!(e.to_string(), "multiplexer is not supported on windows");
+        let e = LaunchError::Exited { program: "wt".to_string(), code: Some(1), stderr: String::new() };
+        assert_eq!(e.to_string(), "wt exited with code: Some(1)");
+        let e = LaunchError::FallbackExhausted {
+            attempts: vec![
+                LaunchAttempt { backend: "wt".to_string(), error: e.clone() },
+                LaunchAttempt {
+                    backend: "pwsh".to_string(),
+                    error: LaunchError::io("Failed to write pre-launch script", "denied"),
+                },
+            ],
+        };
+        assert_eq!(
+            e.to_string(),
+            "No launch method succeeded (tried: wt, pwsh); last error: Failed to write pre-launch script: denied"
+        );
+    }
+}
@@ -0,0 +1,420 @@
+//! Launch history: one record per launch or IDE session, kept in the backend
+//! rather than as `Project.lastLaunchedAt` in the frontend store, so the
+//! launcher can answer "how often does this project fail to start" or "how
+//! many agent hours went into it this month".
+//!
+//! The store is an append-only JSON-lines file in the app data dir. A session
+//! is written as it happens — a `start` record, a `transition` for every
+//! complete/waiting ping the IDE listener acts on, an `end` record when the
+//! process exits or the launch fails — and `sessions` folds the records back
+//! together by id. Appending keeps every write a single short line, so a crash
+//! mid-session loses nothing already recorded; a torn last line is skipped.
+//!
+//! Terminal and multiplexer launches are handed off to another process, so
+//! their history ends at the launch: they count towards launches and failures
+//! but not agent hours. Agent hours are the start-to-end time of IDE sessions.
+
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::{DateTime, FixedOffset, Local, SecondsFormat};
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+use crate::launch_log::agent_name;
+use crate::redact::redact;
+use crate::{write_log, LaunchRequest, LogPath};
+
+/// Where a session ran.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LaunchMode {
+    /// A terminal tab or window (Windows Terminal on Windows).
+    Terminal,
+    /// A tmux or screen window.
+    Multiplexer,
+    /// An embedded IDE Mode PTY.
+    Ide,
+}
+
+/// A state the IDE listener reported for a session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Transition {
+    Complete,
+    Waiting,
+}
+
+/// One line of the history file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Record {
+    /// The session id (IDE Mode) or a fresh id per launch (terminal).
+    pub id: String,
+    /// RFC 3339, local offset, millisecond precision.
+    pub at: String,
+    #[serde(flatten)]
+    pub event: RecordEvent,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub(crate) enum RecordEvent {
+    #[serde(rename_all = "camelCase")]
+    Start {
+        project: String,
+        /// Executable name, as in the log.
+        agent: String,
+        /// Redacted the way the log is; a flag can carry a credential.
+        flags: Vec<String>,
+        mode: LaunchMode,
+    },
+    Transition { status: Transition },
+    #[serde(rename_all = "camelCase")]
+    End {
+        exit_code: Option<i32>,
+        /// Why the launch failed, when it did.
+        error: Option<String>,
+        /// Ended by `kill_pty` rather than exiting on its own.
+        #[serde(default)]
+        killed: bool,
+    },
+}
+
+impl Record {
+    fn now(id: &str, event: RecordEvent) -> Self {
+        Record {
+            id: id.to_string(),
+            at: Local::now().to_rfc3339_opts(SecondsFormat::Millis, false),
+            event,
+        }
+    }
+
+    pub(crate) fn start(id: &str, request: &LaunchRequest, mode: LaunchMode) -> Self {
+        Record::now(
+            id,
+            RecordEvent::Start {
+                project: request.project_path.clone(),
+                agent: agent_name(&request.agent_path),
+                flags: request.flags.iter().map(|f| redact(f)).collect(),
+                mode,
+            },
+        )
+    }
+
+    pub(crate) fn transition(id: &str, status: Transition) -> Self {
+        Record::now(id, RecordEvent::Transition { status })
+    }
+
+    pub(crate) fn failed(id: &str, error: &str) -> Self {
+        Record::now(id, RecordEvent::End { exit_code: None, error: Some(redact(error)), killed: false })
+    }
+
+    pub(crate) fn exited(id: &str, exit_code: i32, killed: bool) -> Self {
+        Record::now(id, RecordEvent::End { exit_code: Some(exit_code), error: None, killed })
+    }
+}
+
+/// Managed state: the history file, and a lock so records from the command
+/// handlers, PTY wait threads and the listener never interleave.
+pub struct LaunchHistory {
+    path: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl LaunchHistory {
+    pub fn new(path: PathBuf) -> Self {
+        LaunchHistory { path, lock: Mutex::new(()) }
+    }
+
+    fn append(&self, record: &Record) -> Result<(), String> {
+        let mut line = serde_json::to_string(record).map_err(|e| e.to_string())?;
+        line.push('\n');
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| e.to_string())?;
+        file.write_all(line.as_bytes()).map_err(|e| e.to_string())
+    }
+
+    fn sessions(&self) -> Result<Vec<Session>, String> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        read_sessions(&self.path)
+    }
+}
+
+/// Append `record`. Best-effort: history must never fail a launch, so a write
+/// error is logged and dropped.
+pub(crate) fn record(app: &tauri::AppHandle, record: Record) {
+    let Some(history) = app.try_state::<LaunchHistory>() else {
+        return;
+    };
+    if let Err(e) = history.append(&record) {
+        if let Some(log_path) = app.try_state::<LogPath>().map(|p| p.0.lock().unwrap().clone()) {
+            write_log(&log_path, "WARN", &format!("Failed to record launch history: {}", e));
+        }
+    }
+}
+
+/// A session as folded from its records.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Session {
+    pub id: String,
+    pub project: String,
+    pub agent: String,
+    pub flags: Vec<String>,
+    pub mode: LaunchMode,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+    pub exit_code: Option<i32>,
+    pub error: Option<String>,
+    pub killed: bool,
+    pub completions: u32,
+    pub waits: u32,
+}
+
+impl Session {
+    /// The launch failed, or the process exited non-zero on its own.
+    pub fn failed(&self) -> bool {
+        self.error.is_some() || (!self.killed && self.exit_code.is_some_and(|c| c != 0))
+    }
+
+    /// Start to end, for sessions that ran and have ended.
+    fn hours(&self) -> Option<f64> {
+        if self.error.is_some() {
+            return None;
+        }
+        let start = parse_time(&self.started_at)?;
+        let end = parse_time(self.ended_at.as_deref()?)?;
+        Some((end - start).num_milliseconds().max(0) as f64 / 3_600_000.0)
+    }
+}
+
+fn parse_time(s: &str) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(s).ok()
+}
+
+/// Fold the records in the file at `path` into sessions, oldest first. A
+/// missing file is an empty history. Records for an id with no `start` are
+/// ignored.
+fn read_sessions(path: &Path) -> Result<Vec<Session>, String> {
+    let text = match fs::read_to_string(path) {
+        Ok(t) => t,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.to_string()),
+    };
+    let mut sessions: Vec<Session> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for record in text.lines().filter_map(|l| serde_json::from_str::<Record>(l).ok()) {
+        match record.event {
+            RecordEvent::Start { project, agent, flags, mode } => {
+                index.insert(record.id.clone(), sessions.len());
+                sessions.push(Session {
+                    id: record.id,
+                    project,
+                    agent,
+                    flags,
+                    mode,
+                    started_at: record.at,
+                    ended_at: None,
+                    exit_code: None,
+                    error: None,
+                    killed: false,
+                    completions: 0,
+                    waits: 0,
+                });
+            }
+            RecordEvent::Transition { status } => {
+                if let Some(s) = index.get(&record.id).map(|&i| &mut sessions[i]) {
+                    match status {
+                        Transition::Complete => s.completions += 1,
+                        Transition::Waiting => s.waits += 1,
+                    }
+                }
+            }
+            RecordEvent::End { exit_code, error, killed } => {
+                if let Some(s) = index.get(&record.id).map(|&i| &mut sessions[i]) {
+                    s.ended_at = Some(record.at);
+                    s.exit_code = exit_code;
+                    s.error = error;
+                    s.killed = killed;
+                }
+            }
+        }
+    }
+    Ok(sessions)
+}
+
+/// Totals for one project, or (in `HistoryStats`) for all of them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectStats {
+    pub project: String,
+    pub launches: u32,
+    pub failures: u32,
+    /// `failures / launches`, 0 with no launches.
+    pub failure_rate: f64,
+    pub agent_hours: f64,
+    pub completions: u32,
+    pub waits: u32,
+    pub last_launched_at: Option<String>,
+}
+
+impl ProjectStats {
+    fn add(&mut self, s: &Session) {
+        self.launches += 1;
+        self.failures += s.failed() as u32;
+        self.failure_rate = self.failures as f64 / self.launches as f64;
+        self.agent_hours += s.hours().unwrap_or(0.0);
+        self.completions += s.completions;
+        self.waits += s.waits;
+        // Sessions are folded oldest first.
+        self.last_launched_at = Some(s.started_at.clone());
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryStats {
+    /// Most launched first.
+    pub projects: Vec<ProjectStats>,
+    /// Every project together; `project` is empty.
+    pub total: ProjectStats,
+}
+
+/// Stats over `sessions` started at or after `since`.
+fn stats(sessions: &[Session], since: Option<DateTime<FixedOffset>>) -> HistoryStats {
+    let mut by_project: HashMap<&str, ProjectStats> = HashMap::new();
+    let mut total = ProjectStats::default();
+    for s in sessions {
+        if since.is_some_and(|since| parse_time(&s.started_at).is_none_or(|t| t < since)) {
+            continue;
+        }
+        by_project
+            .entry(s.project.as_str())
+            .or_insert_with(|| ProjectStats { project: s.project.clone(), ..Default::default() })
+            .add(s);
+        total.add(s);
+    }
+    let mut projects: Vec<ProjectStats> = by_project.into_values().collect();
+    projects.sort_by(|a, b| b.launches.cmp(&a.launches).then_with(|| a.project.cmp(&b.project)));
+    HistoryStats { projects, total }
+}
+
+fn parse_since(since: Option<&str>) -> Result<Option<DateTime<FixedOffset>>, String> {
+    since
+        .map(|s| parse_time(s).ok_or_else(|| format!("Invalid timestamp: {}", s)))
+        .transpose()
+}
+
+/// Past sessions, newest first, optionally for one project only.
+#[tauri::command]
+pub async fn launch_history(
+    app: tauri::AppHandle,
+    project: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<Session>, String> {
+    let handle = app.clone();
+    let sessions = tauri::async_runtime::spawn_blocking(move || handle.state::<LaunchHistory>().sessions())
+        .await
+        .map_err(|e| e.to_string())??;
+    Ok(sessions
+        .into_iter()
+        .rev()
+        .filter(|s| project.as_ref().is_none_or(|p| &s.project == p))
+        .take(limit.unwrap_or(usize::MAX))
+        .collect())
+}
+
+/// Per-project launch counts, agent hours, failure rates and complete/waiting
+/// counts, over sessions started at or after `since` (RFC 3339) if given.
+#[tauri::command]
+pub async fn launch_stats(app: tauri::AppHandle, since: Option<String>) -> Result<HistoryStats, String> {
+    let since = parse_since(since.as_deref())?;
+    let handle = app.clone();
+    let sessions = tauri::async_runtime::spawn_blocking(move || handle.state::<LaunchHistory>().sessions())
+        .await
+        .map_err(|e| e.to_string())??;
+    Ok(stats(&sessions, since))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(id: &str, time: &str, event: RecordEvent) -> Record {
+        Record { id: id.to_string(), at: time.to_string(), event }
+    }
+
+    fn start(id: &str, time: &str, project: &str, mode: LaunchMode) -> Record {
+        let event = RecordEvent::Start { project: project.to_string(), agent: "claude".into(), flags: vec![], mode };
+        at(id, time, event)
+    }
+
+    #[test]
+    fn records_fold_into_sessions_and_stats() {
+        let dir = std::env::temp_dir().join(format!("claude-launcher-history-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let history = LaunchHistory::new(dir.join("launch-history.jsonl"));
+
+        let complete = |id: &str, time: &str| at(id, time, RecordEvent::Transition { status: Transition::Complete });
+        let waiting = |id: &str, time: &str| at(id, time, RecordEvent::Transition { status: Transition::Waiting });
+        let end = |id: &str, time: &str, code: i32, killed: bool| {
+            at(id, time, RecordEvent::End { exit_code: Some(code), error: None, killed })
+        };
+        for r in [
+            // Two hours, clean exit.
+            start("a", "2026-03-01T10:00:00.000+00:00", "/p/one", LaunchMode::Ide),
+            complete("a", "2026-03-01T10:30:00.000+00:00"),
+            waiting("a", "2026-03-01T11:00:00.000+00:00"),
+            complete("a", "2026-03-01T11:30:00.000+00:00"),
+            end("a", "2026-03-01T12:00:00.000+00:00", 0, false),
+            // Half an hour, killed: not a failure.
+            start("b", "2026-03-02T10:00:00.000+00:00", "/p/one", LaunchMode::Ide),
+            end("b", "2026-03-02T10:30:00.000+00:00", 1, true),
+            // A terminal launch that failed.
+            start("c", "2026-03-03T10:00:00.000+00:00", "/p/two", LaunchMode::Terminal),
+            at("c", "2026-03-03T10:00:01.000+00:00", RecordEvent::End {
+                exit_code: None,
+                error: Some("wt.exe not found".into()),
+                killed: false,
+            }),
+            // A transition for a session with no start is ignored.
+            complete("zzz", "2026-03-03T11:00:00.000+00:00"),
+        ] {
+            history.append(&r).unwrap();
+        }
+        // A torn line from a crash mid-write is skipped.
+        OpenOptions::new().append(true).open(&history.path).unwrap().write_all(b"{\"id\":\"d\",\"at").unwrap();
+
+        let sessions = history.sessions().unwrap();
+        assert_eq!(sessions.len(), 3);
+        assert_eq!((sessions[0].completions, sessions[0].waits), (2, 1));
+        assert!(!sessions[1].failed());
+        assert!(sessions[2].failed());
+
+        let all = stats(&sessions, None);
+        assert_eq!(all.total.launches, 3);
+        assert_eq!(all.total.failures, 1);
+        assert!((all.total.agent_hours - 2.5).abs() < 1e-9);
+        assert_eq!(all.projects[0].project, "/p/one");
+        assert_eq!(all.projects[0].launches, 2);
+        assert_eq!(all.projects[0].failure_rate, 0.0);
+        assert_eq!(all.projects[0].last_launched_at.as_deref(), Some("2026-03-02T10:00:00.000+00:00"));
+        assert_eq!(all.projects[1].failure_rate, 1.0);
+
+        let recent = stats(&sessions, parse_since(Some("2026-03-02T00:00:00+00:00")).unwrap());
+        assert_eq!(recent.total.launches, 2);
+        assert_eq!(recent.total.completions, 0);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
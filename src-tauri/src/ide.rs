@@ -1,22 +1,30 @@
-//! IDE Mode backend: embedded Claude PTY sessions, the loopback attention
-//! listener that turns hook pings into session-state events, and read-only
-//! file-tree / diff helpers for the on-demand files drawer.
+//! IDE Mode backend: embedded Claude PTY sessions and read-only file-tree /
+//! diff helpers for the on-demand files drawer. The loopback attention
+//! listener that turns hook pings into session-state events is listener.rs.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::io::{Read, Write};
-use std::net::TcpListener;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::Instant;
 
+use chrono::{DateTime, Local, SecondsFormat};
 use portable_pty::{native_pty_system, ChildKiller, CommandBuilder, MasterPty, PtySize};
 use serde::Serialize;
 use tauri::{Emitter, Manager};
 
+use crate::error::LaunchError;
+use crate::listener::{IdeListener, IdeToken};
+use crate::history::{self, LaunchMode, Record};
+use crate::launch_log::{self, EventKind, LogEntry, Outcome};
+use crate::recording::{self, Recorder, RecordingDir, RecordingOptions};
+use crate::redact::describe_env;
+use crate::secrets;
 use crate::{
-    build_agent_pwsh_cmd, codex_notify_config_arg, is_safe_flag, is_safe_path, is_safe_subcommand,
-    LaunchRequest, FULL_REPAINT_ENV,
+    codex_notify_config_arg, is_safe_path, resolve_agent_request,
+    write_log, LaunchRequest, LogPath, FULL_REPAINT_ENV,
 };
 
 /// Live PTYs keyed by session id.
@@ -28,85 +36,310 @@ impl Default for PtySessions {
     }
 }
 
+impl PtySessions {
+    pub(crate) fn contains(&self, session_id: &str) -> bool {
+        self.0.lock().unwrap().contains_key(session_id)
+    }
+
+    /// Every live session, oldest first.
+    pub(crate) fn list(&self) -> Vec<PtySessionInfo> {
+        let map = self.0.lock().unwrap();
+        let mut sessions: Vec<PtySessionInfo> = map
+            .values()
+            .map(|h| PtySessionInfo {
+                last_output_at: h.output.lock().unwrap().last_output.map(rfc3339),
+                ..h.info.clone()
+            })
+            .collect();
+        sessions.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+        sessions
+    }
+
+    /// `session_id` is live and writing a recording.
+    pub(crate) fn is_recording(&self, session_id: &str) -> bool {
+        self.0
+            .lock()
+            .unwrap()
+            .get(session_id)
+            .is_some_and(|h| h.output.lock().unwrap().recorder.is_some())
+    }
+
+    /// `session_id`'s kept output, with its current size and cwd, for
+    /// transcripts of sessions that aren't being recorded.
+    pub(crate) fn scrollback(&self, session_id: &str) -> Option<(Vec<u8>, (u16, u16), String)> {
+        let sessions = self.0.lock().unwrap();
+        let handle = sessions.get(session_id)?;
+        let bytes = handle.output.lock().unwrap().scrollback.contents();
+        Some((bytes, (handle.info.cols, handle.info.rows), handle.info.cwd.clone()))
+    }
+}
+
 /// The port this app instance's attention listener is bound to (0 if it failed
 /// to bind). Stamped onto every spawned session as `CLAUDE_LAUNCHER_PORT` so the
-/// session's Stop/Notification hook reaches THIS instance directly, instead of
-/// trusting the shared `ide-port` file — which any second instance (a dev build,
-/// a double-launch) overwrites and none restores on exit, silently breaking
-/// every other instance's status routing.
+/// session's Stop/Notification hook reaches THIS instance directly. (A shared
+/// `ide-port` file used to serve as a fallback; any second instance — a dev
+/// build, a double-launch — overwrote it, silently breaking every other
+/// instance's status routing, and it could never carry the token.)
 pub struct IdePort(pub std::sync::atomic::AtomicU16);
 
+/// Where a session's hooks report to: this instance's listener port, socket
+/// and token.
+pub(crate) struct HookEndpoint {
+    pub port: u16,
+    pub socket: Option<PathBuf>,
+    pub token: String,
+}
+
+impl HookEndpoint {
+    pub(crate) fn of(app: &tauri::AppHandle) -> Self {
+        HookEndpoint {
+            port: app
+                .try_state::<IdePort>()
+                .map(|p| p.0.load(std::sync::atomic::Ordering::Relaxed))
+                .unwrap_or(0),
+            socket: app.try_state::<IdeListener>().and_then(|l| l.socket.clone()),
+            token: app.try_state::<IdeToken>().map(|t| t.0.clone()).unwrap_or_default(),
+        }
+    }
+}
+
 pub struct PtyHandle {
     writer: Box<dyn Write + Send>,
     master: Box<dyn MasterPty + Send>,
     killer: Box<dyn ChildKiller + Send + Sync>,
+    /// Shared with the reader thread, which outlives the handle.
+    output: Arc<Mutex<PtyOutput>>,
+    /// What `list_pty_sessions` reports, less the last-output time.
+    info: PtySessionInfo,
 }
 
-#[derive(Clone, Serialize)]
+/// A live PTY session as `list_pty_sessions` and `pty-spawned` report it.
+#[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
-struct ExitPayload {
+pub struct PtySessionInfo {
     session_id: String,
-    code: i32,
+    /// None where the platform doesn't report one.
+    pid: Option<u32>,
+    cwd: String,
+    agent_path: String,
+    /// As spawned: launch profile applied.
+    flags: Vec<String>,
+    /// RFC 3339, local offset, millisecond precision, like the log.
+    started_at: String,
+    cols: u16,
+    rows: u16,
+    last_output_at: Option<String>,
+}
+
+fn rfc3339(t: DateTime<Local>) -> String {
+    t.to_rfc3339_opts(SecondsFormat::Millis, false)
+}
+
+/// Most output kept per session for `attach_pty` to replay.
+const SCROLLBACK_BYTES: usize = 1024 * 1024;
+
+/// The last `cap` bytes a session wrote.
+struct Scrollback {
+    buf: VecDeque<u8>,
+    cap: usize,
+    /// Bytes have been dropped off the front.
+    truncated: bool,
+}
+
+impl Scrollback {
+    fn new(cap: usize) -> Self {
+        Scrollback { buf: VecDeque::new(), cap, truncated: false }
+    }
+
+    fn push(&mut self, bytes: &[u8]) {
+        let bytes = &bytes[bytes.len().saturating_sub(self.cap)..];
+        let overflow = (self.buf.len() + bytes.len()).saturating_sub(self.cap);
+        if overflow > 0 {
+            self.buf.drain(..overflow);
+            self.truncated = true;
+        }
+        self.buf.extend(bytes);
+    }
+
+    /// Everything kept. Once the front has been dropped it starts after the
+    /// first newline, so a replay never opens mid-line or mid-escape sequence.
+    fn contents(&self) -> Vec<u8> {
+        let skip = if self.truncated {
+            self.buf.iter().position(|&b| b == b'\n').map_or(0, |i| i + 1)
+        } else {
+            0
+        };
+        self.buf.iter().skip(skip).copied().collect()
+    }
+}
+
+/// A session's scrollback and the channel its output currently goes to, if
+/// any. One lock for both, so `attach_pty`'s replay and the switch to live
+/// output happen with no chunk lost or sent twice in between.
+struct PtyOutput {
+    scrollback: Scrollback,
+    channel: Option<tauri::ipc::Channel<Vec<u8>>>,
+    last_output: Option<DateTime<Local>>,
+    /// Set when the session is being recorded (see recording.rs).
+    recorder: Option<Recorder>,
 }
 
 #[derive(Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
-struct StatePayload {
+struct ExitPayload {
     session_id: String,
-    status: String,
+    code: i32,
+}
+
+/// The env a launcher-owned session runs with, as (set, remove). Shared by the
+/// embedded PTY and the tmux/screen launch target so both look the same to
+/// the agent and to the global hooks.
+pub(crate) fn session_env(
+    request: &LaunchRequest,
+    session_id: &str,
+    endpoint: &HookEndpoint,
+) -> (BTreeMap<String, String>, Vec<String>) {
+    let mut set = BTreeMap::new();
+    let mut remove = Vec::new();
+    set.insert("CLAUDE_LAUNCHER_SESSION".to_string(), session_id.to_string());
+    // Where there is one, the listener's Unix socket: only this user can
+    // connect to it, which the loopback port can't promise.
+    if let Some(socket) = &endpoint.socket {
+        set.insert("CLAUDE_LAUNCHER_SOCKET".to_string(), socket.to_string_lossy().into_owned());
+    }
+    // Stamp THIS instance's listener port so the session's Stop/Notification
+    // hook POSTs status straight back to us, whatever other instances run.
+    // The token goes with either: the listener turns away pings without it.
+    if endpoint.port != 0 {
+        set.insert("CLAUDE_LAUNCHER_PORT".to_string(), endpoint.port.to_string());
+    }
+    if endpoint.port != 0 || endpoint.socket.is_some() {
+        set.insert("CLAUDE_LAUNCHER_TOKEN".to_string(), endpoint.token.clone());
+    }
+    if request.claude_features {
+        // Match the wt path: prevent Claude's nested-session detection.
+        remove.push("CLAUDECODE".to_string());
+        // Renderer choice (IDE mode only). The embedded xterm.js terminal can run
+        // Claude's fullscreen alt-screen TUI; "classic" forces the scrollback
+        // renderer for users who prefer it. Default (unset) is fullscreen. We pin
+        // whichever is chosen so an inherited env var can't flip it the other way.
+        if request.ide_renderer.as_deref() == Some("classic") {
+            set.insert("CLAUDE_CODE_DISABLE_ALTERNATE_SCREEN".to_string(), "1".to_string());
+            remove.push("CLAUDE_CODE_NO_FLICKER".to_string());
+        } else {
+            set.insert("CLAUDE_CODE_NO_FLICKER".to_string(), "1".to_string());
+            remove.push("CLAUDE_CODE_DISABLE_ALTERNATE_SCREEN".to_string());
+        }
+    } else {
+        // Another agent: hand it a clean env rather than Claude Code's renderer
+        // knobs. They would be inert, but leaking them makes future debugging
+        // read as though the launcher were configuring an agent it isn't.
+        remove.push("CLAUDE_CODE_NO_FLICKER".to_string());
+        remove.push("CLAUDE_CODE_DISABLE_ALTERNATE_SCREEN".to_string());
+    }
+    // Do NOT let ALT_SCREEN_FULL_REPAINT reach embedded sessions. The launcher
+    // persists it machine-wide (HKCU) to fix Windows Terminal's stale-glyph bug
+    // (anthropics/claude-code#69619), and this app's process env inherits it —
+    // but that bug is WT's, not xterm's. In here it forces Claude to redraw the
+    // whole screen every frame, multiplying xterm's rendering load (and, under
+    // the WebGL renderer, the glyph-atlas churn behind the corruption the
+    // repaint controls were added for). xterm's stale-glyph issues are addressed
+    // at the renderer level instead (DOM renderer default + sideloaded ConPTY).
+    remove.push(FULL_REPAINT_ENV.to_string());
+    (set, remove)
 }
 
 /// Spawn `claude` (optionally behind a pre-launch command) inside a real
-/// Windows ConPTY. Output streams back over `on_output`; an exit emits
-/// `pty-exit`. The session id is exported as `CLAUDE_LAUNCHER_SESSION` so the
-/// global Stop/Notification hooks can correlate their pings to this session.
+/// Windows ConPTY. Output streams back over `on_output` and into the
+/// session's scrollback (see `attach_pty`); a successful spawn emits
+/// `pty-spawned` and an exit emits `pty-exit`. With `recording`, the session
+/// is also written to an asciicast file (see recording.rs). The session id is
+/// exported as `CLAUDE_LAUNCHER_SESSION` so the global Stop/Notification hooks
+/// can correlate their pings to this session. Async so that resolving the
+/// request's secrets, which may wait on the OS keyring, never blocks the UI.
 #[tauri::command]
-pub fn spawn_pty(
+pub async fn spawn_pty(
     app: tauri::AppHandle,
-    state: tauri::State<PtySessions>,
     session_id: String,
     request: LaunchRequest,
     cols: u16,
     rows: u16,
     on_output: tauri::ipc::Channel<Vec<u8>>,
-) -> Result<(), String> {
+    recording: Option<RecordingOptions>,
+) -> Result<(), LaunchError> {
+    let log_path = app.state::<LogPath>().0.lock().unwrap().clone();
     // Reuse the exact validation the wt launch path uses — IDE mode must not
     // be a weaker-guarded launch surface.
-    if !is_safe_path(&request.agent_path) {
-        return Err("Agent path contains invalid characters".into());
-    }
-    if !is_safe_path(&request.project_path) {
-        return Err("Project path contains invalid characters".into());
-    }
-    for flag in &request.flags {
-        if !is_safe_flag(flag) {
-            return Err(format!("Invalid flag rejected: {}", flag));
-        }
-    }
-    if let Some(sub) = request.subcommand.as_deref() {
-        if !is_safe_subcommand(sub) {
-            return Err(format!("Invalid subcommand rejected: {}", sub));
-        }
+    let resolved = resolve_agent_request(&request);
+    // Recorded first: the wait thread may record the exit before start_pty
+    // returns. As the session will run, profile flags included, when it resolved.
+    history::record(&app, Record::start(&session_id, resolved.as_ref().unwrap_or(&request), LaunchMode::Ide));
+    let result = match resolved {
+        Ok(resolved) => match prepare_pty_request(&app, &log_path, &session_id, resolved).await {
+            Ok(prepared) => start_pty(&app, &session_id, &prepared, (cols, rows), on_output, recording.as_ref()),
+            Err(e) => Err(e),
+        },
+        Err(e) => Err(e),
+    };
+    if let Err(e) = &result {
+        history::record(&app, Record::failed(&session_id, &e.to_string()));
     }
-    if !std::path::Path::new(&request.project_path).exists() {
-        return Err(format!("Project directory does not exist: {}", request.project_path));
+    let entry = match &result {
+        Ok(()) => LogEntry::new("INFO", EventKind::PtySpawn, "PTY session started").outcome(Outcome::Success),
+        Err(e) => LogEntry::new("ERROR", EventKind::PtySpawn, format!("PTY session failed to start: {}", e))
+            .outcome(Outcome::Failure),
+    };
+    launch_log::append(
+        &log_path,
+        &entry.project(&request.project_path).session(&session_id).agent(&request.agent_path),
+    );
+    result
+}
+
+/// The resolved request as the session will run it: with its secrets
+/// resolved off the async runtime's workers (see `secrets::inject_secrets`).
+async fn prepare_pty_request(
+    app: &tauri::AppHandle,
+    log_path: &Path,
+    session_id: &str,
+    request: LaunchRequest,
+) -> Result<LaunchRequest, LaunchError> {
+    if !request.env.is_empty() {
+        write_log(
+            log_path,
+            "INFO",
+            &format!("Agent env for session {}: {}", session_id, describe_env(&request.env)),
+        );
     }
+    secrets::inject_secrets(app, log_path, &request).await
+}
+
+/// `spawn_pty` minus the outcome entry, which it logs around this. `request`
+/// comes from `prepare_pty_request`.
+fn start_pty(
+    app: &tauri::AppHandle,
+    session_id: &str,
+    request: &LaunchRequest,
+    (cols, rows): (u16, u16),
+    on_output: tauri::ipc::Channel<Vec<u8>>,
+    recording: Option<&RecordingOptions>,
+) -> Result<(), LaunchError> {
+    let log_path = app.state::<LogPath>().0.lock().unwrap().clone();
 
     // Spawn Claude directly (CommandBuilder seeds the full parent env via
     // get_base_env, so PATH/APPDATA/etc are inherited). A pre-launch command
-    // still needs a shell, so route that case through pwsh.
-    let has_pre_launch = request
+    // still needs a shell, so route that case through one.
+    let pre_launch = request
         .pre_launch_command
-        .as_ref()
-        .is_some_and(|c| !c.is_empty());
-
-    let mut cmd = if has_pre_launch {
-        let agent_cmd = build_agent_pwsh_cmd(&request);
-        let pre = request.pre_launch_command.clone().unwrap_or_default();
-        let mut c = CommandBuilder::new("pwsh");
-        c.arg("-NoLogo");
-        c.arg("-Command");
-        c.arg(format!("{}; {}", pre, agent_cmd));
+        .as_deref()
+        .filter(|c| !c.is_empty())
+        .map(|pre| pre_launch_shell(request, pre));
+
+    let mut cmd = if let Some((shell, args)) = &pre_launch {
+        let mut c = CommandBuilder::new(shell);
+        for arg in args {
+            c.arg(arg);
+        }
         c
     } else {
         let mut c = CommandBuilder::new(&request.agent_path);
@@ -121,55 +354,26 @@ pub fn spawn_pty(
         // writes. A failure must not block the launch — the session simply
         // falls back to the output-idle heuristic for its status.
         if request.notify_hook {
-            match codex_notify_config_arg() {
-                Ok(arg) => c.arg(arg),
-                Err(_) => {}
+            if let Ok(arg) = codex_notify_config_arg() {
+                c.arg(arg);
             }
         }
         c
     };
 
     cmd.cwd(&request.project_path);
-    cmd.env("CLAUDE_LAUNCHER_SESSION", &session_id);
-    // Stamp THIS instance's listener port so the session's Stop/Notification
-    // hook POSTs status straight back to us, regardless of what the shared
-    // ide-port file currently holds (another instance may have overwritten it).
-    if let Some(p) = app.try_state::<IdePort>() {
-        let port = p.0.load(std::sync::atomic::Ordering::Relaxed);
-        if port != 0 {
-            cmd.env("CLAUDE_LAUNCHER_PORT", port.to_string());
-        }
+    let endpoint = HookEndpoint::of(app);
+    // The request's own env first, so the session's cannot be overridden.
+    for (key, value) in &request.env {
+        cmd.env(key, value);
     }
-    if request.claude_features {
-        // Match the wt path: prevent Claude's nested-session detection.
-        cmd.env_remove("CLAUDECODE");
-        // Renderer choice (IDE mode only). The embedded xterm.js terminal can run
-        // Claude's fullscreen alt-screen TUI; "classic" forces the scrollback
-        // renderer for users who prefer it. Default (unset) is fullscreen. We pin
-        // whichever is chosen so an inherited env var can't flip it the other way.
-        if request.ide_renderer.as_deref() == Some("classic") {
-            cmd.env("CLAUDE_CODE_DISABLE_ALTERNATE_SCREEN", "1");
-            cmd.env_remove("CLAUDE_CODE_NO_FLICKER");
-        } else {
-            cmd.env("CLAUDE_CODE_NO_FLICKER", "1");
-            cmd.env_remove("CLAUDE_CODE_DISABLE_ALTERNATE_SCREEN");
-        }
-    } else {
-        // Another agent: hand it a clean env rather than Claude Code's renderer
-        // knobs. They would be inert, but leaking them makes future debugging
-        // read as though the launcher were configuring an agent it isn't.
-        cmd.env_remove("CLAUDE_CODE_NO_FLICKER");
-        cmd.env_remove("CLAUDE_CODE_DISABLE_ALTERNATE_SCREEN");
+    let (env_set, env_remove) = session_env(request, session_id, &endpoint);
+    for key in &env_remove {
+        cmd.env_remove(key);
+    }
+    for (key, value) in &env_set {
+        cmd.env(key, value);
     }
-    // Do NOT let ALT_SCREEN_FULL_REPAINT reach embedded sessions. The launcher
-    // persists it machine-wide (HKCU) to fix Windows Terminal's stale-glyph bug
-    // (anthropics/claude-code#69619), and this app's process env inherits it —
-    // but that bug is WT's, not xterm's. In here it forces Claude to redraw the
-    // whole screen every frame, multiplying xterm's rendering load (and, under
-    // the WebGL renderer, the glyph-atlas churn behind the corruption the
-    // repaint controls were added for). xterm's stale-glyph issues are addressed
-    // at the renderer level instead (DOM renderer default + sideloaded ConPTY).
-    cmd.env_remove(FULL_REPAINT_ENV);
 
     let pair = native_pty_system()
         .openpty(PtySize {
@@ -178,36 +382,78 @@ pub fn spawn_pty(
             pixel_width: 0,
             pixel_height: 0,
         })
-        .map_err(|e| format!("openpty failed: {}", e))?;
+        .map_err(|e| LaunchError::io("openpty failed", e))?;
 
+    let program = pre_launch.as_ref().map_or(request.agent_path.as_str(), |(shell, _)| *shell);
     let mut child = pair
         .slave
         .spawn_command(cmd)
-        .map_err(|e| format!("spawn failed: {}", e))?;
+        .map_err(|e| match e.downcast_ref::<std::io::Error>() {
+            Some(io) => LaunchError::spawn(program, io),
+            None => LaunchError::Spawn {
+                program: program.to_string(),
+                os_error_kind: "Other".to_string(),
+                message: e.to_string(),
+            },
+        })?;
     // The parent does not need the slave handle once the child holds it.
     drop(pair.slave);
 
     let mut reader = pair
         .master
         .try_clone_reader()
-        .map_err(|e| format!("clone reader failed: {}", e))?;
+        .map_err(|e| LaunchError::io("clone reader failed", e))?;
     let writer = pair
         .master
         .take_writer()
-        .map_err(|e| format!("take writer failed: {}", e))?;
+        .map_err(|e| LaunchError::io("take writer failed", e))?;
     let killer = child.clone_killer();
+    let info = PtySessionInfo {
+        session_id: session_id.to_string(),
+        pid: child.process_id(),
+        cwd: request.project_path.clone(),
+        agent_path: request.agent_path.clone(),
+        flags: request.flags.clone(),
+        started_at: rfc3339(Local::now()),
+        cols,
+        rows,
+        last_output_at: None,
+    };
 
-    // Reader thread: stream PTY output to the frontend.
+    let output = Arc::new(Mutex::new(PtyOutput {
+        scrollback: Scrollback::new(SCROLLBACK_BYTES),
+        channel: Some(on_output),
+        last_output: None,
+        recorder: recording.and_then(|options| start_recording(app, session_id, request, (cols, rows), options)),
+    }));
+
+    // Reader thread: keep PTY output in the scrollback and stream it to the
+    // frontend while one is attached. A closed channel (the webview reloaded)
+    // detaches it; reading carries on so the child never blocks on a full PTY
+    // and `attach_pty` can pick the session up again.
     {
-        let channel = on_output.clone();
+        let output = output.clone();
+        let log_path = log_path.clone();
+        let sid = session_id.to_string();
         thread::spawn(move || {
             let mut buf = [0u8; 4096];
             loop {
                 match reader.read(&mut buf) {
                     Ok(0) | Err(_) => break,
                     Ok(n) => {
-                        if channel.send(buf[..n].to_vec()).is_err() {
-                            break;
+                        let mut out = output.lock().unwrap();
+                        out.scrollback.push(&buf[..n]);
+                        out.last_output = Some(Local::now());
+                        if let Some(rec) = out.recorder.as_mut() {
+                            rec.output(&buf[..n]);
+                        }
+                        if out.channel.as_ref().is_some_and(|c| c.send(buf[..n].to_vec()).is_err()) {
+                            out.channel = None;
+                            launch_log::append(
+                                &log_path,
+                                &LogEntry::new("WARN", EventKind::Message, "PTY output channel closed; buffering output")
+                                    .session(&sid),
+                            );
                         }
                     }
                 }
@@ -215,30 +461,101 @@ pub fn spawn_pty(
         });
     }
 
+    // Registered and announced before the wait thread exists: it tells a kill
+    // from an exit by the handle being gone, so a child that dies at once must
+    // find it, and its `pty-exit` must come after this `pty-spawned`.
+    app.state::<PtySessions>().0.lock().unwrap().insert(
+        session_id.to_string(),
+        PtyHandle {
+            writer,
+            master: pair.master,
+            killer,
+            output,
+            info: info.clone(),
+        },
+    );
+    let _ = app.emit("pty-spawned", info);
+
     // Wait thread: detect exit, clean up, notify the frontend.
     {
         let app = app.clone();
-        let sid = session_id.clone();
+        let sid = session_id.to_string();
+        let project = request.project_path.clone();
+        let started = Instant::now();
         thread::spawn(move || {
             let code = child
                 .wait()
                 .map(|s| s.exit_code() as i32)
                 .unwrap_or(-1);
-            if let Some(state) = app.try_state::<PtySessions>() {
-                state.0.lock().unwrap().remove(&sid);
-            }
+            // Gone from the map already means kill_pty ended it.
+            let killed = app
+                .try_state::<PtySessions>()
+                .is_some_and(|state| state.0.lock().unwrap().remove(&sid).is_none());
+            history::record(&app, Record::exited(&sid, code, killed));
+            let log_path = app.state::<LogPath>().0.lock().unwrap().clone();
+            let (level, outcome) = if code == 0 { ("INFO", Outcome::Success) } else { ("WARN", Outcome::Failure) };
+            launch_log::append(
+                &log_path,
+                &LogEntry::new(level, EventKind::PtyExit, format!("PTY session exited with code {}", code))
+                    .project(&project)
+                    .session(&sid)
+                    .duration(started.elapsed())
+                    .outcome(outcome),
+            );
             let _ = app.emit("pty-exit", ExitPayload { session_id: sid, code });
         });
     }
 
-    state.0.lock().unwrap().insert(
-        session_id,
-        PtyHandle {
-            writer,
-            master: pair.master,
-            killer,
-        },
-    );
+    Ok(())
+}
+
+/// Open `session_id`'s cast. A failure is logged and the session runs
+/// unrecorded.
+fn start_recording(
+    app: &tauri::AppHandle,
+    session_id: &str,
+    request: &LaunchRequest,
+    (cols, rows): (u16, u16),
+    options: &RecordingOptions,
+) -> Option<Recorder> {
+    let log_path = app.state::<LogPath>().0.lock().unwrap().clone();
+    let title = request.tab_title.as_deref().unwrap_or(&request.project_path);
+    let created = recording::cast_path(&app.state::<RecordingDir>().0, session_id)
+        .and_then(|path| Recorder::create(&path, cols, rows, title, options).map_err(|e| e.to_string()));
+    match created {
+        Ok(rec) => {
+            write_log(&log_path, "INFO", &format!("Recording session {}", session_id));
+            Some(rec)
+        }
+        Err(e) => {
+            write_log(&log_path, "WARN", &format!("Recording for session {} not started: {}", session_id, e));
+            None
+        }
+    }
+}
+
+/// Attach `on_output` to a running session, e.g. after the webview reloaded:
+/// the scrollback is replayed as one chunk, then live output follows. Replaces
+/// whichever channel was attached before.
+#[tauri::command]
+pub fn attach_pty(
+    state: tauri::State<PtySessions>,
+    session_id: String,
+    on_output: tauri::ipc::Channel<Vec<u8>>,
+) -> Result<(), String> {
+    let output = state
+        .0
+        .lock()
+        .unwrap()
+        .get(&session_id)
+        .map(|h| h.output.clone())
+        .ok_or_else(|| "No such session".to_string())?;
+    let mut out = output.lock().unwrap();
+    let replay = out.scrollback.contents();
+    if !replay.is_empty() {
+        on_output.send(replay).map_err(|e| e.to_string())?;
+    }
+    out.channel = Some(on_output);
     Ok(())
 }
 
@@ -256,7 +573,11 @@ pub fn write_pty(
         .writer
         .write_all(data.as_bytes())
         .map_err(|e| e.to_string())?;
-    handle.writer.flush().map_err(|e| e.to_string())
+    handle.writer.flush().map_err(|e| e.to_string())?;
+    if let Some(rec) = handle.output.lock().unwrap().recorder.as_mut() {
+        rec.input(&data);
+    }
+    Ok(())
 }
 
 #[tauri::command]
@@ -266,9 +587,9 @@ pub fn resize_pty(
     cols: u16,
     rows: u16,
 ) -> Result<(), String> {
-    let map = state.0.lock().unwrap();
+    let mut map = state.0.lock().unwrap();
     let handle = map
-        .get(&session_id)
+        .get_mut(&session_id)
         .ok_or_else(|| "No such session".to_string())?;
     handle
         .master
@@ -278,7 +599,21 @@ pub fn resize_pty(
             pixel_width: 0,
             pixel_height: 0,
         })
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    handle.info.cols = cols;
+    handle.info.rows = rows;
+    if let Some(rec) = handle.output.lock().unwrap().recorder.as_mut() {
+        rec.resize(cols, rows);
+    }
+    Ok(())
+}
+
+/// Every live PTY session, oldest first. What the backend actually has
+/// running, for the frontend to reconcile its own list against (e.g. after a
+/// reload; see `attach_pty`).
+#[tauri::command]
+pub fn list_pty_sessions(state: tauri::State<PtySessions>) -> Vec<PtySessionInfo> {
+    state.list()
 }
 
 #[tauri::command]
@@ -289,133 +624,6 @@ pub fn kill_pty(state: tauri::State<PtySessions>, session_id: String) -> Result<
     Ok(())
 }
 
-/// Path of the file the app writes its loopback port to, so the hook command
-/// (which has no other channel to the app) can find it.
-fn ide_port_file() -> Option<PathBuf> {
-    std::env::var_os("USERPROFILE")
-        .map(|h| PathBuf::from(h).join(".claude-launcher").join("ide-port"))
-}
-
-/// Start the loopback attention listener. Binds 127.0.0.1:<random>, writes the
-/// port to a well-known file, and turns `{session,event}` POSTs from the
-/// Stop/Notification hooks into `session-state` events. Pings for sessions we
-/// don't own (e.g. external Launcher-Mode sessions firing the same global hook)
-/// are ignored.
-pub fn start_ide_listener(app: tauri::AppHandle) -> u16 {
-    // Bind synchronously so the caller can stamp the real port onto sessions
-    // before any are spawned. Returns 0 if binding failed (status routing then
-    // falls back to the shared port file, written below).
-    let listener = match TcpListener::bind("127.0.0.1:0") {
-        Ok(l) => l,
-        Err(_) => return 0,
-    };
-    let port = listener.local_addr().map(|a| a.port()).unwrap_or(0);
-    if port != 0 {
-        if let Some(file) = ide_port_file() {
-            if let Some(parent) = file.parent() {
-                let _ = std::fs::create_dir_all(parent);
-            }
-            let _ = std::fs::write(&file, port.to_string());
-        }
-    }
-
-    thread::spawn(move || {
-        for stream in listener.incoming() {
-            let mut stream = match stream {
-                Ok(s) => s,
-                Err(_) => continue,
-            };
-            // Never let a stalled client wedge this single-threaded loop.
-            let _ = stream.set_read_timeout(Some(std::time::Duration::from_millis(1500)));
-
-            // Minimal HTTP read. We must consume the full request body, which
-            // means handling the `Expect: 100-continue` handshake that Windows
-            // PowerShell's Invoke-RestMethod uses: it sends only the headers and
-            // waits for a "100 Continue" before sending the body. If we answered
-            // with a final 200 first (as a naive single-read server does), the
-            // body would never arrive, the session id would be empty, and the
-            // session would stay stuck on "Working".
-            let mut data: Vec<u8> = Vec::with_capacity(2048);
-            let mut tmp = [0u8; 2048];
-            let mut header_end: Option<usize> = None;
-            while header_end.is_none() && data.len() < 64 * 1024 {
-                match stream.read(&mut tmp) {
-                    Ok(0) | Err(_) => break,
-                    Ok(n) => {
-                        data.extend_from_slice(&tmp[..n]);
-                        header_end = data.windows(4).position(|w| w == b"\r\n\r\n");
-                    }
-                }
-            }
-            let hdr_end = match header_end {
-                Some(e) => e,
-                None => {
-                    let _ = stream.write_all(b"HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\n\r\n");
-                    continue;
-                }
-            };
-            let headers_lc = String::from_utf8_lossy(&data[..hdr_end]).to_ascii_lowercase();
-
-            if headers_lc.contains("expect:") && headers_lc.contains("100-continue") {
-                let _ = stream.write_all(b"HTTP/1.1 100 Continue\r\n\r\n");
-                let _ = stream.flush();
-            }
-
-            let content_len = headers_lc
-                .lines()
-                .find_map(|l| l.strip_prefix("content-length:"))
-                .and_then(|v| v.trim().parse::<usize>().ok())
-                .unwrap_or(0);
-            let body_start = hdr_end + 4;
-            while data.len() < body_start + content_len {
-                match stream.read(&mut tmp) {
-                    Ok(0) | Err(_) => break,
-                    Ok(n) => data.extend_from_slice(&tmp[..n]),
-                }
-            }
-
-            // Always answer so the hook's HTTP client doesn't hang.
-            let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n");
-            let _ = stream.flush();
-
-            let body = if data.len() > body_start {
-                String::from_utf8_lossy(&data[body_start..]).to_string()
-            } else {
-                String::new()
-            };
-            if let Ok(v) = serde_json::from_str::<serde_json::Value>(body.trim()) {
-                let session = v.get("session").and_then(|s| s.as_str()).unwrap_or("");
-                let event = v.get("event").and_then(|s| s.as_str()).unwrap_or("");
-                if session.is_empty() {
-                    continue;
-                }
-                // Only act on sessions we own.
-                let owned = app
-                    .try_state::<PtySessions>()
-                    .map(|st| st.0.lock().unwrap().contains_key(session))
-                    .unwrap_or(false);
-                if !owned {
-                    continue;
-                }
-                let status = match event {
-                    "stop" => "complete",
-                    "notification" => "waiting",
-                    _ => continue,
-                };
-                let _ = app.emit(
-                    "session-state",
-                    StatePayload {
-                        session_id: session.to_string(),
-                        status: status.to_string(),
-                    },
-                );
-            }
-        }
-    });
-
-    port
-}
-
 // ---------------------------------------------------------------------------
 // Read-only files drawer
 // ---------------------------------------------------------------------------
@@ -494,9 +702,8 @@ pub fn git_status(cwd: String) -> Result<Vec<GitStatusEntry>, String> {
             "A"
         } else if code.contains('D') {
             "D"
-        } else if code.contains('M') || code.contains('R') {
-            "M"
         } else {
+            // M, R and anything else all read as "modified".
             "M"
         };
         entries.push(GitStatusEntry {
@@ -540,3 +747,58 @@ pub fn git_diff(cwd: String, file: String) -> Result<String, String> {
         Err(_) => Ok("(no diff available)".to_string()),
     }
 }
+
+/// The shell and arguments that run `pre` and then the agent: pwsh on
+/// Windows, as the wt and pwsh backends do.
+#[cfg(not(unix))]
+fn pre_launch_shell(request: &LaunchRequest, pre: &str) -> (&'static str, Vec<String>) {
+    let command = format!("{}; {}", pre, crate::build_agent_pwsh_cmd(request));
+    ("pwsh", vec!["-NoLogo".to_string(), "-Command".to_string(), command])
+}
+
+/// The same under `sh -c`, as `terminal::PosixShell` does: the agent is
+/// `exec`ed once `pre` has run.
+#[cfg(unix)]
+fn pre_launch_shell(request: &LaunchRequest, pre: &str) -> (&'static str, Vec<String>) {
+    let command = format!("{}\n{}", pre, crate::build_agent_sh_cmd(request));
+    ("sh", vec!["-c".to_string(), command])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn pre_launch_runs_under_sh_off_windows() {
+        let request = LaunchRequest {
+            agent_path: "/usr/local/bin/claude".to_string(),
+            flags: vec!["--continue".to_string()],
+            ..Default::default()
+        };
+        let (shell, args) = pre_launch_shell(&request, "source ~/.venv/bin/activate");
+        assert_eq!(shell, "sh");
+        assert_eq!(
+            args,
+            ["-c", "source ~/.venv/bin/activate\nexec '/usr/local/bin/claude' '--continue'"]
+        );
+    }
+
+    #[test]
+    fn scrollback_keeps_the_tail_from_a_line_start() {
+        let mut sb = Scrollback::new(16);
+        sb.push(b"one\ntwo\n");
+        assert_eq!(sb.contents(), b"one\ntwo\n");
+
+        // 8 + 10 bytes: "on" drops off, and the replay skips the rest of the
+        // cut line.
+        sb.push(b"three\nfour");
+        assert_eq!(sb.buf.len(), 16);
+        assert_eq!(sb.contents(), b"two\nthree\nfour");
+
+        // A single chunk bigger than the buffer keeps only its tail.
+        sb.push(&[b'x'; 40]);
+        assert_eq!(sb.buf.len(), 16);
+        assert!(sb.contents().iter().all(|&b| b == b'x'));
+    }
+}
@@ -0,0 +1,413 @@
+//! The launch log as structured JSON lines, one `LogEntry` per line, and the
+//! `query_log` command the Logs tab filters it with.
+//!
+//! `write_log` still takes a level and a free-form message; those become
+//! `message` entries. The events worth asking questions about — a launch and
+//! its outcome, a PTY session starting and exiting, a session changing state —
+//! are written with `append` and carry the project, session, agent, duration
+//! and outcome as fields, so "why did launches of project X fail last week" is
+//! a filter rather than a grep. Lines in the old `[timestamp] [LEVEL] message`
+//! format, from before the switch, are still read back as `message` entries.
+
+use std::path::Path;
+use std::time::Duration;
+
+use chrono::{DateTime, FixedOffset, Local, NaiveDateTime, SecondsFormat, TimeZone};
+use serde::{Deserialize, Serialize};
+
+use crate::log_file;
+use crate::logger;
+use crate::redact::redact;
+
+/// What a log entry records.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EventKind {
+    /// Free-form, from `write_log`.
+    #[default]
+    Message,
+    /// A `launch_agent` call and how it ended.
+    Launch,
+    /// An IDE Mode PTY session started, or failed to.
+    PtySpawn,
+    /// An IDE Mode PTY session's process exited.
+    PtyExit,
+    /// A hook reported a session complete or waiting.
+    SessionState,
+    /// A hook ping the listener ignored: malformed, or for a session this
+    /// instance doesn't own.
+    RejectedPing,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Outcome {
+    Success,
+    Failure,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntry {
+    /// RFC 3339, local offset, millisecond precision.
+    pub timestamp: String,
+    pub level: String,
+    #[serde(default)]
+    pub event: EventKind,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// The agent executable's name, e.g. "claude" or "codex".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<Outcome>,
+}
+
+impl LogEntry {
+    /// An entry stamped with the current time.
+    pub(crate) fn new(level: &str, event: EventKind, message: impl Into<String>) -> Self {
+        LogEntry {
+            timestamp: Local::now().to_rfc3339_opts(SecondsFormat::Millis, false),
+            level: level.to_string(),
+            event,
+            message: message.into(),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn project(mut self, path: &str) -> Self {
+        self.project = Some(path.to_string());
+        self
+    }
+
+    pub(crate) fn session(mut self, id: &str) -> Self {
+        self.session_id = Some(id.to_string());
+        self
+    }
+
+    /// Records the agent by executable name rather than full path.
+    pub(crate) fn agent(mut self, agent_path: &str) -> Self {
+        self.agent = Some(agent_name(agent_path));
+        self
+    }
+
+    pub(crate) fn duration(mut self, d: Duration) -> Self {
+        self.duration_ms = Some(d.as_millis() as u64);
+        self
+    }
+
+    pub(crate) fn outcome(mut self, outcome: Outcome) -> Self {
+        self.outcome = Some(outcome);
+        self
+    }
+
+    fn time(&self) -> Option<DateTime<FixedOffset>> {
+        DateTime::parse_from_rfc3339(&self.timestamp).ok()
+    }
+
+    /// The entry as one line of text, in the old log format plus whichever
+    /// fields are set. What `read_log` returns.
+    pub(crate) fn to_text(&self) -> String {
+        let ts = self
+            .time()
+            .map(|t| t.format("%Y-%m-%d %H:%M:%S%.3f").to_string())
+            .unwrap_or_else(|| self.timestamp.clone());
+        let mut line = format!("[{}] [{}] {}", ts, self.level, self.message);
+        let mut context = Vec::new();
+        if let Some(p) = &self.project {
+            context.push(format!("project={}", p));
+        }
+        if let Some(s) = &self.session_id {
+            context.push(format!("session={}", s));
+        }
+        if let Some(a) = &self.agent {
+            context.push(format!("agent={}", a));
+        }
+        if let Some(ms) = self.duration_ms {
+            context.push(format!("{} ms", ms));
+        }
+        if !context.is_empty() {
+            line.push_str(&format!(" ({})", context.join(", ")));
+        }
+        line
+    }
+}
+
+/// "claude" for `C:\...\claude.exe` or `/usr/bin/claude`.
+pub(crate) fn agent_name(agent_path: &str) -> String {
+    let file = agent_path.rsplit(['/', '\\']).next().unwrap_or(agent_path);
+    let stem = file.strip_suffix(".exe").or_else(|| file.strip_suffix(".cmd")).unwrap_or(file);
+    stem.to_ascii_lowercase()
+}
+
+/// Queue `entry` for the log at `log_path`, redacted (see redact.rs). Returns
+/// straight away; the logger thread writes it (see logger.rs).
+pub(crate) fn append(log_path: &Path, entry: &LogEntry) {
+    let mut entry = entry.clone();
+    entry.message = redact(&entry.message);
+    entry.project = entry.project.as_deref().map(redact);
+
+    logger::submit(log_path, entry);
+}
+
+/// One line of the log file, in either format. None for lines that are
+/// neither (e.g. the tail of a multi-line message in the old format).
+pub(crate) fn parse_line(line: &str) -> Option<LogEntry> {
+    if line.starts_with('{') {
+        return serde_json::from_str(line).ok();
+    }
+    let rest = line.strip_prefix('[')?;
+    let (ts, rest) = rest.split_once("] [")?;
+    let (level, message) = rest.split_once("] ")?;
+    let naive = NaiveDateTime::parse_from_str(ts, "%Y-%m-%d %H:%M:%S%.3f").ok()?;
+    let local = Local.from_local_datetime(&naive).earliest()?;
+    Some(LogEntry {
+        timestamp: local.to_rfc3339_opts(SecondsFormat::Millis, false),
+        level: level.to_string(),
+        message: message.to_string(),
+        ..Default::default()
+    })
+}
+
+/// Every entry in the log, rotated segments included, oldest first. NotFound
+/// when there is no log at all yet.
+pub(crate) fn read_entries(log_path: &Path) -> std::io::Result<Vec<LogEntry>> {
+    let files = log_file::all_files(log_path);
+    if files.is_empty() {
+        return Err(std::io::ErrorKind::NotFound.into());
+    }
+    let mut entries = Vec::new();
+    for file in files {
+        entries.extend(log_file::read_segment(&file)?.lines().filter_map(parse_line));
+    }
+    Ok(entries)
+}
+
+/// The last `n` entries, oldest first, reading back through rotated segments
+/// only as far as needed. NotFound when there is no log at all yet.
+pub(crate) fn read_tail(log_path: &Path, n: usize) -> std::io::Result<Vec<LogEntry>> {
+    let files = log_file::all_files(log_path);
+    if files.is_empty() {
+        return Err(std::io::ErrorKind::NotFound.into());
+    }
+    let mut tail: Vec<LogEntry> = Vec::new();
+    for file in files.iter().rev() {
+        if tail.len() >= n {
+            break;
+        }
+        let mut older: Vec<LogEntry> = log_file::read_segment(file)?.lines().filter_map(parse_line).collect();
+        older.append(&mut tail);
+        tail = older;
+    }
+    let start = tail.len().saturating_sub(n);
+    Ok(tail.split_off(start))
+}
+
+/// `query_log`'s filters. Every field is optional; empty lists match all.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LogQuery {
+    /// Levels to include, case-insensitive.
+    pub levels: Vec<String>,
+    pub events: Vec<EventKind>,
+    /// Only entries for this project directory.
+    pub project: Option<String>,
+    /// RFC 3339 bounds: `since` inclusive, `until` exclusive.
+    pub since: Option<String>,
+    pub until: Option<String>,
+    /// Entries to skip, newest first.
+    pub offset: usize,
+    /// Page size; defaults to `DEFAULT_PAGE` and is capped at `MAX_PAGE`.
+    pub limit: Option<usize>,
+}
+
+const DEFAULT_PAGE: usize = 200;
+const MAX_PAGE: usize = 1000;
+
+/// One page of `query_log` results, newest first. `total` counts every
+/// matching entry, not just this page.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogPage {
+    pub entries: Vec<LogEntry>,
+    pub total: usize,
+}
+
+fn parse_bound(field: &str, value: Option<&str>) -> Result<Option<DateTime<FixedOffset>>, String> {
+    value
+        .filter(|v| !v.is_empty())
+        .map(|v| DateTime::parse_from_rfc3339(v).map_err(|e| format!("Invalid {}: {}", field, e)))
+        .transpose()
+}
+
+fn same_project(a: &str, b: &str) -> bool {
+    let trim = |p: &str| p.trim_end_matches(['/', '\\']).to_string();
+    if cfg!(windows) {
+        trim(a).eq_ignore_ascii_case(&trim(b))
+    } else {
+        trim(a) == trim(b)
+    }
+}
+
+/// Filter and page `entries` (oldest first, as read) per `query`.
+pub(crate) fn run_query(entries: Vec<LogEntry>, query: &LogQuery) -> Result<LogPage, String> {
+    let since = parse_bound("since", query.since.as_deref())?;
+    let until = parse_bound("until", query.until.as_deref())?;
+    // Stored project paths went through redaction (e.g. home → `~`), so the
+    // one asked for has to as well to match them.
+    let project = query.project.as_deref().map(redact);
+    let matching: Vec<LogEntry> = entries
+        .into_iter()
+        .rev()
+        .filter(|e| query.levels.is_empty() || query.levels.iter().any(|l| l.eq_ignore_ascii_case(&e.level)))
+        .filter(|e| query.events.is_empty() || query.events.contains(&e.event))
+        .filter(|e| match (&project, &e.project) {
+            (None, _) => true,
+            (Some(want), Some(have)) => same_project(want, have),
+            (Some(_), None) => false,
+        })
+        .filter(|e| {
+            if since.is_none() && until.is_none() {
+                return true;
+            }
+            let Some(t) = e.time() else {
+                return false;
+            };
+            since.is_none_or(|s| t >= s) && until.is_none_or(|u| t < u)
+        })
+        .collect();
+    let total = matching.len();
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE).min(MAX_PAGE);
+    let entries = matching.into_iter().skip(query.offset).take(limit).collect();
+    Ok(LogPage { entries, total })
+}
+
+/// Push each entry to `on_entry` as it is written, at `min_level` or above
+/// when given ("INFO", "WARN", "ERROR"). Returns the id for `unsubscribe_log`.
+/// A subscription also ends by itself once the channel is dropped.
+#[tauri::command]
+pub async fn subscribe_log(min_level: Option<String>, on_entry: tauri::ipc::Channel<LogEntry>) -> u64 {
+    logger::subscribe(min_level.as_deref(), Box::new(move |entry| on_entry.send(entry.clone()).is_ok()))
+}
+
+#[tauri::command]
+pub async fn unsubscribe_log(subscription: u64) {
+    logger::unsubscribe(subscription);
+}
+
+/// Structured log entries matching `query`, newest first.
+#[tauri::command]
+pub async fn query_log(app: tauri::AppHandle, query: LogQuery) -> Result<LogPage, String> {
+    let log_path = crate::checked_log_path(&app)?;
+    // Waiting on the logger and reading every segment both block.
+    let read = tauri::async_runtime::spawn_blocking(move || {
+        logger::flush();
+        read_entries(&log_path)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    let entries = match read {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(format!("Failed to read log: {}", e)),
+    };
+    run_query(entries, &query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(ts: &str, level: &str, event: EventKind, project: Option<&str>) -> LogEntry {
+        LogEntry {
+            timestamp: ts.to_string(),
+            level: level.to_string(),
+            event,
+            message: String::new(),
+            project: project.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn round_trips_and_reads_old_format() {
+        let e = LogEntry::new("ERROR", EventKind::Launch, "Launch failed: boom")
+            .project("/srv/proj")
+            .agent(r"C:\Tools\Claude.exe")
+            .duration(Duration::from_millis(1500))
+            .outcome(Outcome::Failure);
+        let line = serde_json::to_string(&e).unwrap();
+        assert!(line.contains(r#""event":"launch""#));
+        assert!(line.contains(r#""agent":"claude""#));
+        assert!(line.contains(r#""durationMs":1500"#));
+        assert!(!line.contains("sessionId"));
+        assert_eq!(parse_line(&line), Some(e));
+
+        let old = parse_line("[2026-01-02 03:04:05.678] [WARN] Falling back to xterm").unwrap();
+        assert_eq!(old.level, "WARN");
+        assert_eq!(old.event, EventKind::Message);
+        assert_eq!(old.message, "Falling back to xterm");
+        assert!(old.to_text().starts_with("[2026-01-02 03:04:05.678] [WARN] Falling back"));
+        assert_eq!(parse_line("  continuation of a multi-line message"), None);
+    }
+
+    #[test]
+    fn query_filters_and_pages_newest_first() {
+        let entries = vec![
+            entry("2026-03-01T10:00:00.000+00:00", "ERROR", EventKind::Launch, Some("/srv/a")),
+            entry("2026-03-05T10:00:00.000+00:00", "INFO", EventKind::Launch, Some("/srv/a/")),
+            entry("2026-03-06T10:00:00.000+00:00", "ERROR", EventKind::Launch, Some("/srv/a")),
+            entry("2026-03-07T10:00:00.000+00:00", "ERROR", EventKind::Launch, Some("/srv/b")),
+            entry("2026-03-08T10:00:00.000+00:00", "ERROR", EventKind::Message, None),
+        ];
+        // Failed launches of /srv/a in the week from 2026-03-02.
+        let q = LogQuery {
+            levels: vec!["error".to_string()],
+            events: vec![EventKind::Launch],
+            project: Some("/srv/a".to_string()),
+            since: Some("2026-03-02T00:00:00Z".to_string()),
+            until: Some("2026-03-09T00:00:00Z".to_string()),
+            ..Default::default()
+        };
+        let page = run_query(entries.clone(), &q).unwrap();
+        assert_eq!(page.total, 1);
+        assert_eq!(page.entries[0].timestamp, "2026-03-06T10:00:00.000+00:00");
+
+        let q = LogQuery { offset: 1, limit: Some(2), ..Default::default() };
+        let page = run_query(entries.clone(), &q).unwrap();
+        assert_eq!(page.total, 5);
+        let got: Vec<&str> = page.entries.iter().map(|e| &e.timestamp[..10]).collect();
+        assert_eq!(got, ["2026-03-07", "2026-03-06"]);
+
+        let q = LogQuery { since: Some("last week".to_string()), ..Default::default() };
+        assert!(run_query(entries, &q).is_err());
+    }
+
+    #[test]
+    fn tail_spans_rotated_segments() {
+        let dir = std::env::temp_dir().join(format!("claude-launcher-tail-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let log = dir.join("claude-launcher.log");
+        let mut writer = log_file::LogWriter::new(log_file::RotationPolicy {
+            max_bytes: 300,
+            ..Default::default()
+        });
+        for i in 0..8 {
+            let mut line = serde_json::to_string(&LogEntry::new("INFO", EventKind::Message, format!("entry {}", i))).unwrap();
+            line.push('\n');
+            writer.append(&log, &line);
+        }
+        assert!(!log_file::segments(&log).is_empty());
+
+        let tail = read_tail(&log, 5).unwrap();
+        let got: Vec<&str> = tail.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(got, ["entry 3", "entry 4", "entry 5", "entry 6", "entry 7"]);
+        assert_eq!(read_entries(&log).unwrap().len(), 8);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,14 +1,34 @@
 use serde::{Deserialize, Serialize};
-use std::fs::{self, OpenOptions};
-use std::io::Write;
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::sync::Mutex;
+use std::time::Instant;
 use tauri::Manager;
 
-mod ide;
+use error::LaunchError;
+use history::{LaunchMode, Record};
+use launch_log::{EventKind, LogEntry, Outcome};
 
-#[derive(Debug, Serialize, Deserialize)]
+mod error;
+mod history;
+mod ide;
+mod launch_log;
+mod listener;
+mod log_file;
+mod logger;
+mod prelaunch;
+mod profile;
+mod recording;
+mod replay;
+mod redact;
+mod secrets;
+mod terminal;
+mod tmux;
+mod transcript;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LaunchRequest {
     /// Executable for whichever agent CLI this project runs. The frontend
@@ -47,13 +67,40 @@ pub struct LaunchRequest {
     /// (CLAUDE_CODE_DISABLE_ALTERNATE_SCREEN); anything else (incl. unset)
     /// uses the fullscreen alt-screen TUI. Ignored by the wt launch path.
     pub ide_renderer: Option<String>,
+    /// Non-Windows only: the terminal emulators to try, in order, by name
+    /// (see `terminal::LinuxTerminal`). Empty uses the built-in order.
+    /// Ignored on Windows, where the wt path always applies.
+    #[serde(default)]
+    pub terminal_order: Vec<String>,
+    /// "tmux" or "screen" to start the agent in a window of a multiplexer
+    /// session instead of a terminal tab (see tmux.rs). None uses the
+    /// terminal backends.
+    #[serde(default)]
+    pub multiplexer: Option<String>,
+    /// Multiplexer session to create or reuse. None uses
+    /// `tmux::DEFAULT_SESSION`.
+    #[serde(default)]
+    pub multiplexer_session: Option<String>,
+    /// Built-in launch profile to apply on top of the request (see
+    /// profile.rs), or None.
+    #[serde(default)]
+    pub launch_profile: Option<String>,
+    /// Extra env for the agent (API base URLs, model, proxy settings). Names
+    /// and values are validated (see `is_safe_env_name`/`is_safe_env_value`);
+    /// the launch profile's env is merged in under these.
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Env vars whose values come from the secret store: env var name →
+    /// secret name (see secrets.rs). Resolved only at spawn time.
+    #[serde(default)]
+    pub secret_env: BTreeMap<String, String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize)]
 pub struct LaunchResult {
     pub success: bool,
     pub command: String,
-    pub error: Option<String>,
+    pub error: Option<LaunchError>,
 }
 
 // Managed state for log file path
@@ -62,17 +109,10 @@ pub struct LogPath(pub Mutex<PathBuf>);
 // Managed state for the app data directory (used to restrict log path changes)
 pub struct AppDataDir(pub PathBuf);
 
-fn write_log(log_path: &PathBuf, level: &str, message: &str) {
-    let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f");
-    let line = format!("[{}] [{}] {}\n", timestamp, level, message);
-
-    if let Some(parent) = log_path.parent() {
-        let _ = fs::create_dir_all(parent);
-    }
-
-    if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(log_path) {
-        let _ = file.write_all(line.as_bytes());
-    }
+/// Log a free-form message. Written as a `message` entry of the structured
+/// log (see launch_log.rs), redacted on the way (see redact.rs).
+fn write_log(log_path: &Path, level: &str, message: &str) {
+    launch_log::append(log_path, &LogEntry::new(level, EventKind::Message, message));
 }
 
 /// Shell metacharacters that must not appear in user-supplied values
@@ -124,6 +164,27 @@ pub(crate) fn is_safe_path(path: &str) -> bool {
     !path.contains(PATH_METACHARACTERS.as_ref())
 }
 
+/// Validate an env var name: ASCII letter or `_`, then letters, digits and
+/// `_` — the portable POSIX set, which also keeps `=` and NUL out.
+pub(crate) fn is_safe_env_name(name: &str) -> bool {
+    name.len() <= 128
+        && name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Validate an env var value. Values are written into the wt pre-launch script
+/// and the tmux `env` argv as well as set directly, so shell metacharacters
+/// are rejected like in flag values, along with NUL.
+pub(crate) fn is_safe_env_value(value: &str) -> bool {
+    !value.contains(SHELL_METACHARACTERS.as_ref()) && !value.contains('\0')
+}
+
+/// Env the launcher sets (or strips) itself for its sessions; a request may
+/// not supply these.
+fn is_reserved_env_name(name: &str) -> bool {
+    name.starts_with("CLAUDE_LAUNCHER_") || name == "CLAUDECODE"
+}
+
 /// Env var that fixes intermittently garbled fullscreen-TUI output on Windows
 /// Terminal (stale glyphs from the previous frame left in the leading columns)
 /// by making Claude Code repaint the whole screen each frame instead of
@@ -166,15 +227,46 @@ pub(crate) fn build_agent_pwsh_cmd(request: &LaunchRequest) -> String {
     cmd_parts.join(" ")
 }
 
+/// Build the POSIX `sh` command string to exec the agent with flags — the
+/// non-Windows counterpart of `build_agent_pwsh_cmd`. Single-quoted so nothing
+/// in a path or flag value is expanded; an embedded `'` becomes `'\''`.
+/// Returns something like: exec '/usr/bin/claude' '--flag1' '--flag2'
+pub(crate) fn build_agent_sh_cmd(request: &LaunchRequest) -> String {
+    let quote = |s: &str| format!("'{}'", s.replace('\'', "'\\''"));
+    let mut cmd_parts: Vec<String> = vec!["exec".to_string(), quote(&request.agent_path)];
+    if let Some(sub) = request.subcommand.as_deref() {
+        if is_safe_subcommand(sub) {
+            cmd_parts.push(quote(sub));
+        }
+    }
+    for flag in &request.flags {
+        cmd_parts.push(quote(flag));
+    }
+    cmd_parts.join(" ")
+}
+
+/// The user's home directory: `USERPROFILE` on Windows, `HOME` elsewhere,
+/// each falling back to the other. Off Windows `HOME` must win: a Linux
+/// session can inherit a `USERPROFILE` (WSL interop, wine) naming some other
+/// machine's home.
+pub(crate) fn home_dir() -> Option<PathBuf> {
+    let order = if cfg!(windows) { ["USERPROFILE", "HOME"] } else { ["HOME", "USERPROFILE"] };
+    order
+        .into_iter()
+        .filter_map(std::env::var_os)
+        .find(|h| !h.is_empty())
+        .map(PathBuf::from)
+}
+
 /// Validate that a color is a strict `#rrggbb` hex string.
-fn is_safe_color(color: &str) -> bool {
+pub(crate) fn is_safe_color(color: &str) -> bool {
     let bytes = color.as_bytes();
     bytes.len() == 7 && bytes[0] == b'#' && bytes[1..].iter().all(|b| b.is_ascii_hexdigit())
 }
 
 /// Validate that a tab title is safe: non-empty, bounded length, no shell
 /// metacharacters. `;` matters most — wt treats it as a subcommand delimiter.
-fn is_safe_title(title: &str) -> bool {
+pub(crate) fn is_safe_title(title: &str) -> bool {
     !title.is_empty() && title.len() <= 128 && !title.contains(SHELL_METACHARACTERS.as_ref())
 }
 
@@ -186,90 +278,220 @@ fn is_safe_profile(profile: &str) -> bool {
             .all(|c| c.is_ascii_alphanumeric() || c == ' ' || c == '-' || c == '_')
 }
 
-#[tauri::command]
-async fn launch_agent(
-    app: tauri::AppHandle,
-    request: LaunchRequest,
-) -> Result<LaunchResult, String> {
-    let log_path = app.state::<LogPath>().0.lock().unwrap().clone();
-    write_log(&log_path, "INFO", &format!("Launch requested for: {}", request.project_path));
+/// The request as it will actually run: its profile folded in, then the
+/// checks of `validate_agent_command`. The starting point of every launch
+/// surface.
+pub(crate) fn resolve_agent_request(request: &LaunchRequest) -> Result<LaunchRequest, LaunchError> {
+    let request = profile::apply_profile(request)?;
+    validate_agent_command(&request)?;
+    Ok(request)
+}
 
-    // --- Input validation ---
+/// The checks every surface that runs an agent makes — `launch_agent`,
+/// `preview_launch` and IDE Mode's `spawn_pty` — so none of them is a
+/// weaker-guarded way in: the argv pieces, the env, then the project directory.
+pub(crate) fn validate_agent_command(request: &LaunchRequest) -> Result<(), LaunchError> {
     if !is_safe_path(&request.agent_path) {
-        let msg = "Agent path contains invalid characters".to_string();
-        write_log(&log_path, "ERROR", &msg);
-        return Ok(LaunchResult { success: false, command: String::new(), error: Some(msg) });
+        return Err(LaunchError::validation("agentPath", "Agent path contains invalid characters"));
     }
 
     if !is_safe_path(&request.project_path) {
-        let msg = "Project path contains invalid characters".to_string();
-        write_log(&log_path, "ERROR", &msg);
-        return Ok(LaunchResult { success: false, command: String::new(), error: Some(msg) });
-    }
-
-    if !is_safe_profile(&request.terminal_profile) {
-        let msg = "Terminal profile contains invalid characters".to_string();
-        write_log(&log_path, "ERROR", &msg);
-        return Ok(LaunchResult { success: false, command: String::new(), error: Some(msg) });
+        return Err(LaunchError::validation("projectPath", "Project path contains invalid characters"));
     }
 
     for flag in &request.flags {
         if !is_safe_flag(flag) {
-            let msg = format!("Invalid flag rejected: {}", flag);
-            write_log(&log_path, "ERROR", &msg);
-            return Ok(LaunchResult { success: false, command: String::new(), error: Some(msg) });
+            return Err(LaunchError::validation("flags", format!("Invalid flag rejected: {}", flag)));
         }
     }
 
     if let Some(sub) = request.subcommand.as_deref() {
         if !is_safe_subcommand(sub) {
-            let msg = format!("Invalid subcommand rejected: {}", sub);
-            write_log(&log_path, "ERROR", &msg);
-            return Ok(LaunchResult { success: false, command: String::new(), error: Some(msg) });
+            return Err(LaunchError::validation(
+                "subcommand",
+                format!("Invalid subcommand rejected: {}", sub),
+            ));
+        }
+    }
+
+    for (name, value) in &request.env {
+        if !is_safe_env_name(name) || is_reserved_env_name(name) {
+            return Err(LaunchError::validation("env", format!("Invalid env var name rejected: {}", name)));
+        }
+        // The value is not echoed: it may be a secret.
+        if !is_safe_env_value(value) {
+            return Err(LaunchError::validation("env", format!("Invalid value rejected for env var {}", name)));
         }
     }
 
+    for (name, secret) in &request.secret_env {
+        if !is_safe_env_name(name) || is_reserved_env_name(name) {
+            return Err(LaunchError::validation(
+                "secretEnv",
+                format!("Invalid env var name rejected: {}", name),
+            ));
+        }
+        if !secrets::is_safe_secret_name(secret) {
+            return Err(LaunchError::validation(
+                "secretEnv",
+                format!("Invalid secret name rejected: {}", secret),
+            ));
+        }
+    }
+
+    if !std::path::Path::new(&request.project_path).exists() {
+        return Err(LaunchError::MissingPath {
+            field: "projectPath",
+            path: request.project_path.clone(),
+        });
+    }
+
+    Ok(())
+}
+
+/// What validation resolved from a request, for the launch (or its preview)
+/// to act on.
+struct ValidatedLaunch {
+    /// The request with its profile applied; see `resolve_agent_request`.
+    request: LaunchRequest,
+    terminal_order: Vec<terminal::LinuxTerminal>,
+    multiplexer: Option<tmux::Multiplexer>,
+    multiplexer_session: String,
+}
+
+/// Every check `launch_agent` makes before it changes anything. Shared with
+/// `preview_launch`, so a preview that passes is a launch that would.
+fn validate_launch(request: &LaunchRequest) -> Result<ValidatedLaunch, LaunchError> {
+    let request = resolve_agent_request(request)?;
+
+    if !is_safe_profile(&request.terminal_profile) {
+        return Err(LaunchError::validation(
+            "terminalProfile",
+            "Terminal profile contains invalid characters",
+        ));
+    }
+
     // Reject a malformed tab color rather than passing it to wt.
     if let Some(color) = &request.tab_color {
         if !color.is_empty() && !is_safe_color(color) {
-            let msg = format!("Invalid tab color rejected: {}", color);
-            write_log(&log_path, "ERROR", &msg);
-            return Ok(LaunchResult { success: false, command: String::new(), error: Some(msg) });
+            return Err(LaunchError::validation("tabColor", format!("Invalid tab color rejected: {}", color)));
         }
     }
 
     // Reject a malformed tab title rather than passing it to wt.
     if let Some(title) = &request.tab_title {
         if !title.is_empty() && !is_safe_title(title) {
-            let msg = format!("Invalid tab title rejected: {}", title);
-            write_log(&log_path, "ERROR", &msg);
-            return Ok(LaunchResult { success: false, command: String::new(), error: Some(msg) });
+            return Err(LaunchError::validation("tabTitle", format!("Invalid tab title rejected: {}", title)));
         }
     }
 
-    // Validate project path exists
-    if !std::path::Path::new(&request.project_path).exists() {
-        let msg = format!("Project directory does not exist: {}", request.project_path);
-        write_log(&log_path, "ERROR", &msg);
-        return Ok(LaunchResult {
-            success: false,
-            command: String::new(),
-            error: Some(msg),
-        });
+    // Off Windows, every named terminal must be one we know how to drive.
+    let terminal_order = terminal::resolve_terminal_order(&request.terminal_order)?;
+
+    let multiplexer = match request.multiplexer.as_deref() {
+        None | Some("") => None,
+        Some(name) => Some(tmux::Multiplexer::from_name(name).ok_or_else(|| {
+            LaunchError::validation("multiplexer", format!("Unknown multiplexer rejected: {}", name))
+        })?),
+    };
+    // The window command is a POSIX one, and there is no tmux or screen to run it.
+    if multiplexer.is_some() && cfg!(windows) {
+        return Err(LaunchError::unsupported("multiplexer"));
+    }
+    let multiplexer_session = request
+        .multiplexer_session
+        .as_deref()
+        .filter(|s| !s.is_empty())
+        .unwrap_or(tmux::DEFAULT_SESSION)
+        .to_string();
+    if !tmux::is_safe_session_name(&multiplexer_session) {
+        return Err(LaunchError::validation(
+            "multiplexerSession",
+            format!("Invalid multiplexer session rejected: {}", multiplexer_session),
+        ));
     }
 
     // Validate agent_path points to an existing file
-    let agent_path = std::path::Path::new(&request.agent_path);
-    if !agent_path.exists() {
-        let msg = format!("Agent executable not found: {}", request.agent_path);
-        write_log(&log_path, "ERROR", &msg);
-        return Ok(LaunchResult {
-            success: false,
-            command: String::new(),
-            error: Some(msg),
+    if !std::path::Path::new(&request.agent_path).exists() {
+        return Err(LaunchError::MissingPath {
+            field: "agentPath",
+            path: request.agent_path.clone(),
         });
     }
 
+    Ok(ValidatedLaunch { request, terminal_order, multiplexer, multiplexer_session })
+}
+
+#[tauri::command]
+async fn launch_agent(
+    app: tauri::AppHandle,
+    request: LaunchRequest,
+) -> Result<LaunchResult, String> {
+    let log_path = app.state::<LogPath>().0.lock().unwrap().clone();
+    write_log(&log_path, "INFO", &format!("Launch requested for: {}", request.project_path));
+
+    let started = Instant::now();
+    let validated = validate_launch(&request);
+    // As validation reads the request: `multiplexer: ""` means none. A request
+    // that fails it never reached a multiplexer either.
+    let mode = match &validated {
+        Ok(v) if v.multiplexer.is_some() => LaunchMode::Multiplexer,
+        _ => LaunchMode::Terminal,
+    };
+    // Also the id a multiplexer window registers and pings under, so its
+    // transitions land on this record. Recorded before the launch, as the
+    // request will run when it validated: profile flags included.
+    let history_id = uuid::Uuid::new_v4().to_string();
+    let recorded = validated.as_ref().map_or(&request, |v| &v.request);
+    history::record(&app, Record::start(&history_id, recorded, mode));
+    let result = run_launch(&app, &log_path, validated, &history_id).await;
+
+    if let Some(e) = &result.error {
+        history::record(&app, Record::failed(&history_id, &e.to_string()));
+    }
+
+    let entry = match &result.error {
+        None => LogEntry::new("INFO", EventKind::Launch, "Launch succeeded").outcome(Outcome::Success),
+        Some(e) => LogEntry::new("ERROR", EventKind::Launch, format!("Launch failed: {}", e)).outcome(Outcome::Failure),
+    };
+    launch_log::append(
+        &log_path,
+        &entry
+            .project(&request.project_path)
+            .agent(&request.agent_path)
+            .duration(started.elapsed()),
+    );
+    Ok(result)
+}
+
+/// `launch_agent` minus validation and the outcome entry, which it does
+/// around this. `session_id` is the launch's history id.
+async fn run_launch(
+    app: &tauri::AppHandle,
+    log_path: &Path,
+    validated: Result<ValidatedLaunch, LaunchError>,
+    session_id: &str,
+) -> LaunchResult {
+    let validated = match validated {
+        Ok(v) => v,
+        Err(e) => {
+            write_log(log_path, "ERROR", &e.to_string());
+            return LaunchResult { success: false, command: String::new(), error: Some(e) };
+        }
+    };
+    // From here on, the request as it will run: profile applied.
+    let request = &validated.request;
+    if !request.env.is_empty() {
+        write_log(log_path, "INFO", &format!("Agent env: {}", redact::describe_env(&request.env)));
+    }
+    let request = &match secrets::inject_secrets(app, log_path, request).await {
+        Ok(r) => r,
+        Err(e) => {
+            write_log(log_path, "ERROR", &e.to_string());
+            return LaunchResult { success: false, command: String::new(), error: Some(e) };
+        }
+    };
+
     // Best-effort: make sure the session about to start (and every other
     // Claude session) picks up the fullscreen-repaint fix. The write completes
     // before the spawn below, so this launch already benefits. A failure here
@@ -278,47 +500,9 @@ async fn launch_agent(
     // writing someone else's config for no reason.
     if request.claude_features {
         match ensure_full_repaint_env() {
-            Ok(true) => write_log(&log_path, "INFO", "Installed full-repaint env into HKCU\\Environment"),
+            Ok(true) => write_log(log_path, "INFO", "Installed full-repaint env into HKCU\\Environment"),
             Ok(false) => {}
-            Err(e) => write_log(&log_path, "WARN", &format!("full-repaint env install failed: {}", e)),
-        }
-    }
-
-    // Build wt arguments.
-    // Without pre-launch: wt new-tab --profile "PowerShell" -d "path" -- claude --flags
-    // With pre-launch:    wt new-tab --profile "PowerShell" -d "path" -- pwsh -NoExit -Command "pre_cmd; & 'claude' '--flags'"
-    let has_pre_launch = request.pre_launch_command.as_ref().is_some_and(|c| !c.is_empty());
-
-    let mut args: Vec<String> = vec![
-        "new-tab".to_string(),
-        "--profile".to_string(),
-        request.terminal_profile.clone(),
-        "-d".to_string(),
-        request.project_path.clone(),
-    ];
-
-    // Color the terminal tab per-project. This is a wt new-tab option, so it
-    // must come before the `--` command separator.
-    if let Some(color) = &request.tab_color {
-        if is_safe_color(color) {
-            args.push("--tabColor".to_string());
-            args.push(color.clone());
-        }
-    }
-
-    // Title the terminal tab per-project. --suppressApplicationTitle keeps it
-    // fixed; without it Claude Code's own OSC title updates (and the installed
-    // statusLine's "<name> — <model>" title) replace it. Both `dynamic_title`
-    // and `model_in_title` need the title left un-suppressed.
-    let live_title = request.dynamic_title.unwrap_or(false)
-        || request.model_in_title.unwrap_or(false);
-    if let Some(title) = &request.tab_title {
-        if is_safe_title(title) {
-            args.push("--title".to_string());
-            args.push(title.clone());
-            if !live_title {
-                args.push("--suppressApplicationTitle".to_string());
-            }
+            Err(e) => write_log(log_path, "WARN", &format!("full-repaint env install failed: {}", e)),
         }
     }
 
@@ -328,176 +512,145 @@ async fn launch_agent(
     // not block the launch.
     // Claude Code only: the map is read by the installed statusLine script,
     // which is a Claude Code concept with no analogue elsewhere.
-    if request.claude_features && request.model_in_title.unwrap_or(false) {
-        if let Some(title) = &request.tab_title {
-            if is_safe_title(title) {
-                if let Err(e) = upsert_tab_name(&request.project_path, title) {
-                    write_log(&log_path, "WARN", &format!("Failed to update tab-name map: {}", e));
-                }
-            }
+    if let Some(title) = tab_name_map_entry(request) {
+        if let Err(e) = upsert_tab_name(&request.project_path, title) {
+            write_log(log_path, "WARN", &format!("Failed to update tab-name map: {}", e));
         }
     }
 
-    args.push("--".to_string());
-
-    if has_pre_launch {
-        let agent_cmd = build_agent_pwsh_cmd(&request);
-        let pre_cmd = request.pre_launch_command.as_ref().unwrap();
-
-        // Write both commands to a temp PowerShell script to avoid wt
-        // semicolon parsing issues. wt treats ';' as a subcommand delimiter
-        // (opening separate tabs) even within quoted arguments, and \; escaping
-        // is unreliable when args are passed through Rust's Command API.
-        let temp_dir = std::env::temp_dir();
-        let script_path = temp_dir.join("claude-launcher-prelaunch.ps1");
-        let script_content = format!("{}\n{}", pre_cmd, agent_cmd);
-
-        if let Err(e) = std::fs::write(&script_path, &script_content) {
-            let msg = format!("Failed to write pre-launch script: {}", e);
-            write_log(&log_path, "ERROR", &msg);
-            return Ok(LaunchResult { success: false, command: String::new(), error: Some(msg) });
-        }
-
-        args.push("pwsh".to_string());
-        args.push("-NoExit".to_string());
-        args.push("-ExecutionPolicy".to_string());
-        args.push("Bypass".to_string());
-        args.push("-File".to_string());
-        args.push(script_path.to_string_lossy().to_string());
+    // Turn-completion callback. Best-effort: a failure to write the script
+    // must not stop the launch. The callback is a PowerShell script, so it is
+    // not offered off Windows. Only the direct (no pre-launch) plans use it.
+    let notify_arg = if !request.notify_hook {
+        None
+    } else if !cfg!(windows) {
+        write_log(log_path, "WARN", "notify hook skipped: not supported on this platform");
+        None
     } else {
-        args.push(request.agent_path.clone());
-        if let Some(sub) = request.subcommand.as_deref() {
-            args.push(sub.to_string());
-        }
-        for flag in &request.flags {
-            args.push(flag.clone());
-        }
-        // Turn-completion callback. In a wt tab there's no session id, so the
-        // script chimes and skips the status POST. Best-effort: a failure to
-        // write the script must not stop the launch.
-        if request.notify_hook {
-            match codex_notify_config_arg() {
-                Ok(arg) => args.push(arg),
-                Err(e) => write_log(&log_path, "WARN", &format!("notify hook skipped: {}", e)),
+        match codex_notify_config_arg() {
+            Ok(arg) => Some(arg),
+            Err(e) => {
+                write_log(log_path, "WARN", &format!("notify hook skipped: {}", e));
+                None
             }
         }
+    };
+
+    // Keys this launch's pre-launch script, so concurrent launches never
+    // share one (see prelaunch.rs).
+    let launch_id = uuid::Uuid::new_v4().to_string();
+    let ctx = terminal::PlanContext {
+        notify_arg,
+        prelaunch_script: prelaunch::script_path(&launch_id),
+    };
+    if let Some(m) = validated.multiplexer {
+        return tmux::launch_in_multiplexer(app, log_path, request, m, &validated.multiplexer_session, session_id, &ctx)
+            .await;
     }
+    let backends = terminal::backends_for_host(&validated.terminal_order);
+    terminal::launch_with_backends(log_path, request, &backends, &ctx).await
+}
 
-    let full_command = format!("wt {}", args.join(" "));
-    write_log(&log_path, "INFO", &format!("Executing: {}", full_command));
+/// The tab title to record in the statusLine's path→name map, when this
+/// launch would record one: Claude Code with live model-in-title and a valid
+/// title.
+fn tab_name_map_entry(request: &LaunchRequest) -> Option<&str> {
+    if !request.claude_features || !request.model_in_title.unwrap_or(false) {
+        return None;
+    }
+    request.tab_title.as_deref().filter(|t| is_safe_title(t))
+}
 
-    // Try wt first, then fall back to starting cmd/pwsh directly
-    let mut wt_cmd = Command::new("wt");
-    wt_cmd.args(&args);
-    if request.claude_features {
-        // Prevent Claude's nested-session detection.
-        wt_cmd.env_remove("CLAUDECODE");
-        // Belt-and-suspenders for the repaint fix: when this spawn starts a fresh
-        // wt.exe (no existing window services the request), the new wt — and the
-        // claude tab under it — inherit our env directly, so the fix applies even
-        // before the persisted HKCU var has propagated to a new shell session.
-        wt_cmd.env(FULL_REPAINT_ENV, "1");
+/// A write `launch_agent` makes outside of the spawn itself.
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+enum LaunchSideEffect {
+    /// Persist a user-level Windows env var (HKCU\Environment).
+    #[serde(rename_all = "camelCase")]
+    UserEnvVar { name: String, value: String },
+    /// Upsert `key → name` into the statusLine's tab-name map.
+    #[serde(rename_all = "camelCase")]
+    TabNameMap { path: PathBuf, key: String, name: String },
+    /// (Re)write the Codex notify script.
+    #[serde(rename_all = "camelCase")]
+    NotifyScript { path: PathBuf },
+}
+
+/// What `launch_agent` would do for a request.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchPreview {
+    /// Terminal launches: the plans tried in order until one starts.
+    /// Multiplexer launches: the invocations run, in order, when the session
+    /// does not exist yet.
+    plans: Vec<terminal::LaunchPlan>,
+    /// Multiplexer launches only: the invocations run instead of `plans` when
+    /// the session already exists. Which set applies is decided at launch time.
+    reuse_plans: Vec<terminal::LaunchPlan>,
+    side_effects: Vec<LaunchSideEffect>,
+}
+
+/// Dry run of `launch_agent`: the same validation, then the fully resolved
+/// plans and the side effects a launch would have — without spawning anything
+/// or writing to disk (or the registry). A validation failure is returned as
+/// the error, exactly as the launch would report it.
+#[tauri::command]
+async fn preview_launch(app: tauri::AppHandle, request: LaunchRequest) -> Result<LaunchPreview, LaunchError> {
+    let validated = validate_launch(&request)?;
+    // Secrets are shown by reference, never resolved here.
+    let mut request = validated.request.clone();
+    for (var, secret) in &validated.request.secret_env {
+        request.env.insert(var.clone(), format!("<secret:{}>", secret));
     }
-    match wt_cmd.spawn() {
-        Ok(mut child) => {
-            // Wait briefly to see if it exits immediately with error
-            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
-            match child.try_wait() {
-                Ok(Some(status)) if !status.success() => {
-                    let msg = format!("wt exited with code: {:?}", status.code());
-                    write_log(&log_path, "WARN", &msg);
-                    // Fall back to pwsh directly
-                    write_log(&log_path, "INFO", "Falling back to pwsh direct launch");
-                    launch_with_pwsh(&log_path, &request)
-                }
-                Ok(_) => {
-                    write_log(&log_path, "INFO", "Launch successful via wt");
-                    Ok(LaunchResult {
-                        success: true,
-                        command: full_command,
-                        error: None,
-                    })
-                }
-                Err(e) => {
-                    let msg = format!("Error checking wt status: {}", e);
-                    write_log(&log_path, "WARN", &msg);
-                    Ok(LaunchResult {
-                        success: true,
-                        command: full_command,
-                        error: None,
-                    })
-                }
-            }
+    let request = &request;
+
+    let mut side_effects = Vec::new();
+    if request.claude_features && full_repaint_env_would_write() {
+        side_effects.push(LaunchSideEffect::UserEnvVar {
+            name: FULL_REPAINT_ENV.to_string(),
+            value: "1".to_string(),
+        });
+    }
+    if let Some(title) = tab_name_map_entry(request) {
+        if let Some(home) = home_dir() {
+            side_effects.push(LaunchSideEffect::TabNameMap {
+                path: home.join(".claude").join("launcher-tab-names.json"),
+                key: normalize_path_key(&request.project_path),
+                name: title.to_string(),
+            });
         }
-        Err(e) => {
-            let msg = format!("wt spawn failed: {}", e);
-            write_log(&log_path, "WARN", &msg);
-            write_log(&log_path, "INFO", "Falling back to pwsh direct launch");
-            launch_with_pwsh(&log_path, &request)
+    }
+    let mut notify_arg = None;
+    if request.notify_hook && cfg!(windows) {
+        if let Ok(path) = codex_notify_script_path() {
+            notify_arg = Some(codex_notify_arg_for(&path));
+            side_effects.push(LaunchSideEffect::NotifyScript { path });
         }
     }
-}
 
-fn launch_with_pwsh(log_path: &PathBuf, request: &LaunchRequest) -> Result<LaunchResult, String> {
-    // Launch the agent as a direct process via pwsh, passing the executable
-    // and flags as separate arguments to avoid shell interpretation.
-    // Using -NoExit keeps the terminal open; -Command with & (call operator)
-    // and individually quoted args prevents injection.
-    let agent_cmd = build_agent_pwsh_cmd(request);
-    let agent_cmd = match &request.pre_launch_command {
-        Some(pre_cmd) if !pre_cmd.is_empty() => format!("{}; {}", pre_cmd, agent_cmd),
-        _ => agent_cmd,
+    // The real id is minted at launch time.
+    let ctx = terminal::PlanContext {
+        notify_arg,
+        prelaunch_script: prelaunch::script_path("<launch-id>"),
     };
-    // No --suppressApplicationTitle equivalent here, so the agent may still
-    // retitle the window later; set the initial title at least.
-    let agent_cmd = match &request.tab_title {
-        Some(title) if is_safe_title(title) => format!(
-            "$Host.UI.RawUI.WindowTitle = '{}'; {}",
-            title.replace('\'', "''"),
-            agent_cmd
+    let (plans, reuse_plans) = match validated.multiplexer {
+        Some(m) => {
+            let endpoint = ide::HookEndpoint { token: "<token>".to_string(), ..ide::HookEndpoint::of(&app) };
+            (
+                tmux::preview_plans(request, m, &validated.multiplexer_session, false, &ctx, &endpoint),
+                tmux::preview_plans(request, m, &validated.multiplexer_session, true, &ctx, &endpoint),
+            )
+        }
+        None => (
+            terminal::backends_for_host(&validated.terminal_order)
+                .iter()
+                .map(|b| b.plan(request, &ctx))
+                .collect(),
+            Vec::new(),
         ),
-        _ => agent_cmd,
     };
 
-    let full_command = format!(
-        "pwsh -NoExit -WorkingDirectory \"{}\" -Command \"{}\"",
-        request.project_path, agent_cmd
-    );
-    write_log(log_path, "INFO", &format!("Executing fallback: {}", full_command));
-
-    let mut pwsh_cmd = Command::new("pwsh");
-    pwsh_cmd.args([
-        "-NoExit",
-        "-WorkingDirectory",
-        &request.project_path,
-        "-Command",
-        &agent_cmd,
-    ]);
-    if request.claude_features {
-        pwsh_cmd.env_remove("CLAUDECODE");
-        // Direct child of this pwsh spawn, so the env propagates reliably
-        // (unlike the wt path, which sets it inside the launch script).
-        pwsh_cmd.env(FULL_REPAINT_ENV, "1");
-    }
-    match pwsh_cmd.spawn() {
-        Ok(_) => {
-            write_log(log_path, "INFO", "Launch successful via pwsh fallback");
-            Ok(LaunchResult {
-                success: true,
-                command: full_command,
-                error: None,
-            })
-        }
-        Err(e) => {
-            let msg = format!("pwsh fallback also failed: {}", e);
-            write_log(log_path, "ERROR", &msg);
-            Ok(LaunchResult {
-                success: false,
-                command: full_command,
-                error: Some(msg),
-            })
-        }
-    }
+    Ok(LaunchPreview { plans, reuse_plans, side_effects })
 }
 
 /// Launch a plain Command Prompt or PowerShell window (no Claude), opened in
@@ -513,13 +666,13 @@ async fn launch_shell(app: tauri::AppHandle, shell: String) -> Result<LaunchResu
         "cmd" => ("cmd.exe", "Command Prompt", &["/k"]),
         "pwsh" => ("pwsh.exe", "PowerShell", &["-NoExit"]),
         other => {
-            let msg = format!("Unsupported shell requested: {}", other);
-            write_log(&log_path, "ERROR", &msg);
-            return Ok(LaunchResult { success: false, command: String::new(), error: Some(msg) });
+            let e = LaunchError::validation("shell", format!("Unsupported shell requested: {}", other));
+            write_log(&log_path, "ERROR", &e.to_string());
+            return Ok(LaunchResult { success: false, command: String::new(), error: Some(e) });
         }
     };
 
-    let home = std::env::var("USERPROFILE").unwrap_or_default();
+    let home = home_dir().map(|h| h.to_string_lossy().to_string()).unwrap_or_default();
     write_log(&log_path, "INFO", &format!("Shell launch requested: {} in {}", exe, home));
 
     // Try Windows Terminal: wt new-tab --profile "<profile>" -d <home> -- <exe> <extra>
@@ -547,7 +700,7 @@ async fn launch_shell(app: tauri::AppHandle, shell: String) -> Result<LaunchResu
             match child.try_wait() {
                 Ok(Some(status)) if !status.success() => {
                     write_log(&log_path, "WARN", &format!("wt exited with code: {:?}", status.code()));
-                    launch_shell_direct(&log_path, exe, extra_args, &home)
+                    Ok(launch_shell_direct(&log_path, exe, extra_args, &home))
                 }
                 _ => {
                     write_log(&log_path, "INFO", "Shell launch successful via wt");
@@ -557,25 +710,28 @@ async fn launch_shell(app: tauri::AppHandle, shell: String) -> Result<LaunchResu
         }
         Err(e) => {
             write_log(&log_path, "WARN", &format!("wt spawn failed: {}", e));
-            launch_shell_direct(&log_path, exe, extra_args, &home)
+            Ok(launch_shell_direct(&log_path, exe, extra_args, &home))
         }
     }
 }
 
 /// Fallback: spawn the shell directly in its own new console window.
 fn launch_shell_direct(
-    log_path: &PathBuf,
+    log_path: &Path,
     exe: &str,
     extra_args: &[&str],
     home: &str,
-) -> Result<LaunchResult, String> {
+) -> LaunchResult {
     // CREATE_NEW_CONSOLE so the shell gets its own visible window rather than
     // attaching (invisibly) to this GUI process.
-    const CREATE_NEW_CONSOLE: u32 = 0x0000_0010;
-    use std::os::windows::process::CommandExt;
-
     let mut cmd = Command::new(exe);
-    cmd.args(extra_args).env_remove("CLAUDECODE").creation_flags(CREATE_NEW_CONSOLE);
+    cmd.args(extra_args).env_remove("CLAUDECODE");
+    #[cfg(windows)]
+    {
+        const CREATE_NEW_CONSOLE: u32 = 0x0000_0010;
+        use std::os::windows::process::CommandExt;
+        cmd.creation_flags(CREATE_NEW_CONSOLE);
+    }
     if !home.is_empty() {
         cmd.current_dir(home);
     }
@@ -583,12 +739,12 @@ fn launch_shell_direct(
     match cmd.spawn() {
         Ok(_) => {
             write_log(log_path, "INFO", &format!("Shell launch successful via direct {} spawn", exe));
-            Ok(LaunchResult { success: true, command: exe.to_string(), error: None })
+            LaunchResult { success: true, command: exe.to_string(), error: None }
         }
         Err(e) => {
-            let msg = format!("Direct {} spawn failed: {}", exe, e);
-            write_log(log_path, "ERROR", &msg);
-            Ok(LaunchResult { success: false, command: exe.to_string(), error: Some(msg) })
+            let err = LaunchError::spawn(exe, &e);
+            write_log(log_path, "ERROR", &format!("Direct {}", err));
+            LaunchResult { success: false, command: exe.to_string(), error: Some(err) }
         }
     }
 }
@@ -718,33 +874,110 @@ fn upsert_hook(
 /// shell (Git Bash) which expands every `$var` to empty BEFORE powershell sees
 /// it — mangling an inline `...$port...` command into a parse error. Here the
 /// `$vars` live inside the script, never on the command line, so nothing can
-/// strip them. Reads the loopback port + session id and POSTs {session,event}.
+/// strip them. Reads the loopback port, token and session id and POSTs
+/// {session,event,tool,payload} with the token in its `X-Claude-Launcher-Token`
+/// header; `payload` is the hook's stdin JSON, left out above `MAX_PAYLOAD`.
+/// Prints nothing: a UserPromptSubmit or SessionStart hook's output is added
+/// to the agent's context.
+#[cfg(any(not(unix), test))]
 const IDE_EVENT_TEMPLATE: &str = r#"param([string]$Event)
 # Auto-generated by Claude Launcher (IDE Mode). Notifies the running app so its
 # session rail can blink and end the Working state. No-ops when the app isn't
 # running or the session is external.
 $ErrorActionPreference = 'SilentlyContinue'
-# Prefer the port the spawning app instance stamped onto this session's env: it
-# points at exactly the instance that owns the session, even when several apps
-# (or a dev build) are running and the shared ide-port file has been overwritten
-# by whichever launched last. Fall back to the file for older sessions.
+# The port and token the spawning app instance stamped onto this session's env:
+# they point at exactly the instance that owns the session, even when several
+# apps (or a dev build) are running, and the listener turns away any ping
+# without the token. A session without them isn't one of ours to report on.
 $port = $env:CLAUDE_LAUNCHER_PORT
-if (-not $port) {
-  $portFile = Join-Path $env:USERPROFILE '.claude-launcher\ide-port'
-  if (Test-Path $portFile) { $port = (Get-Content -Raw $portFile).Trim() }
-}
+$token = $env:CLAUDE_LAUNCHER_TOKEN
 $sid = $env:CLAUDE_LAUNCHER_SESSION
-if (-not $port -or -not $sid) { return }
+if (-not $port -or -not $token -or -not $sid) { return }
 # Disable the Expect: 100-continue handshake so the body is sent with the
 # headers in one shot — the app's tiny loopback listener answers immediately,
 # and waiting for a 100 Continue would otherwise drop the body.
 [System.Net.ServicePointManager]::Expect100Continue = $false
 try {
-  $body = @{ session = $sid; event = $Event } | ConvertTo-Json -Compress
-  Invoke-RestMethod -Uri ("http://127.0.0.1:$port/event") -Method Post -TimeoutSec 1 -ContentType 'application/json' -Body $body | Out-Null
+  $fields = @{ session = $sid; event = $Event }
+  # The hook's own JSON arrives on stdin. It goes along whole unless it is large
+  # (a PreToolUse for a big Write, say); the app keeps only a checked subset.
+  $raw = [Console]::In.ReadToEnd()
+  $payload = $null
+  try { $payload = $raw | ConvertFrom-Json } catch { }
+  if ($payload) {
+    if ($payload.tool_name) { $fields.tool = [string]$payload.tool_name }
+    if ([System.Text.Encoding]::UTF8.GetByteCount($raw) -le 32768) { $fields.payload = $payload }
+  }
+  $body = $fields | ConvertTo-Json -Compress -Depth 8
+  Invoke-RestMethod -Uri ("http://127.0.0.1:$port/event") -Method Post -TimeoutSec 1 -ContentType 'application/json' -Headers @{ 'X-Claude-Launcher-Token' = $token } -Body $body | Out-Null
 } catch { }
 "#;
 
+/// `IDE_EVENT_TEMPLATE` for Linux and macOS, run by `sh`. Prefers the
+/// listener's Unix socket, which only this user can connect to, over the
+/// loopback port. Needs curl (7.40+ for `--unix-socket`); without it the hook
+/// is a no-op, as it is when the app isn't running or the session is external.
+/// Like the PowerShell script it never prints, and curl's one-second cap bounds
+/// how long a PreToolUse hook can hold up the tool call.
+const IDE_EVENT_SH_TEMPLATE: &str = r#"#!/bin/sh
+# Auto-generated by Claude Launcher (IDE Mode). Notifies the running app so its
+# session rail can blink and end the Working state. No-ops when the app isn't
+# running or the session is external.
+event="$1"
+case "$event" in
+  stop|notification|pre-tool-use|post-tool-use|user-prompt-submit|session-start|session-end|subagent-stop|pre-compact) ;;
+  *) exit 0 ;;
+esac
+if [ -z "$CLAUDE_LAUNCHER_SESSION" ] || [ -z "$CLAUDE_LAUNCHER_TOKEN" ]; then exit 0; fi
+command -v curl >/dev/null 2>&1 || exit 0
+# The hook's own JSON arrives on stdin. It goes along whole unless it is large
+# (a PreToolUse for a big Write, say); the app keeps only a checked subset.
+# One byte past the cap is read to tell the two apart.
+input=$(head -c 32769)
+payload=
+if [ "$(printf '%s' "$input" | wc -c)" -le 32768 ]; then
+  case "$input" in "{"*"}") payload="$input" ;; esac
+fi
+# Without the payload the tool's name is all that's sent on, and only when it
+# needs no escaping. The app checks it again regardless.
+tool=
+case "$event" in
+  pre-tool-use|post-tool-use)
+    tool=$(printf '%s' "$input" | grep -o '"tool_name"[[:space:]]*:[[:space:]]*"[^"]*"' | head -n 1 | sed 's/.*"\([^"]*\)"$/\1/')
+    case "$tool" in *[!A-Za-z0-9_.:-]*) tool= ;; esac ;;
+esac
+# The session id is a UUID, so it needs no JSON escaping.
+ping=$(printf '{"session":"%s","event":"%s"' "$CLAUDE_LAUNCHER_SESSION" "$event")
+if [ -n "$tool" ]; then ping="$ping,\"tool\":\"$tool\""; fi
+# POST a body; prints the HTTP status.
+send() {
+  if [ -n "$CLAUDE_LAUNCHER_SOCKET" ] && [ -S "$CLAUDE_LAUNCHER_SOCKET" ]; then
+    curl -s -o /dev/null -w '%{http_code}' -m 1 --unix-socket "$CLAUDE_LAUNCHER_SOCKET" \
+      -H "X-Claude-Launcher-Token: $CLAUDE_LAUNCHER_TOKEN" -H 'Content-Type: application/json' \
+      -d "$1" http://localhost/event
+  elif [ -n "$CLAUDE_LAUNCHER_PORT" ]; then
+    curl -s -o /dev/null -w '%{http_code}' -m 1 \
+      -H "X-Claude-Launcher-Token: $CLAUDE_LAUNCHER_TOKEN" -H 'Content-Type: application/json' \
+      -d "$1" "http://127.0.0.1:$CLAUDE_LAUNCHER_PORT/event"
+  fi
+}
+# The payload is spliced in unchecked, so a malformed one spoils the whole
+# body. The app answers that with a 400, and the ping goes again without it.
+if [ -n "$payload" ]; then
+  status=$(send "$ping,\"payload\":$payload}")
+  if [ "$status" = 400 ]; then send "$ping}" >/dev/null; fi
+else
+  send "$ping}" >/dev/null
+fi
+exit 0
+"#;
+
+/// The IDE event script for this platform: its file name and contents.
+#[cfg(unix)]
+const IDE_EVENT_SCRIPT: (&str, &str) = ("launcher-ide-event.sh", IDE_EVENT_SH_TEMPLATE);
+#[cfg(not(unix))]
+const IDE_EVENT_SCRIPT: (&str, &str) = ("launcher-ide-event.ps1", IDE_EVENT_TEMPLATE);
+
 /// Codex's `notify` callback. Codex invokes the configured program with the
 /// event payload as a single JSON argument; `agent-turn-complete` is the only
 /// type it emits today, which is why Codex sessions can reach "complete" but
@@ -756,6 +989,7 @@ try {
 ///   2. `--config=notify=[...]` is honoured, and the payload type is exactly
 ///      `agent-turn-complete`, matching the guard below. The relay POST reached
 ///      a stand-in listener as {"session":..,"event":"stop"}.
+///
 /// Still unproven: the same for an *interactive* TUI session. The payload's
 /// `client` field reads "codex_exec" there, so Codex does distinguish the two.
 /// If Codex sessions never leave "working", check the launcher log for any
@@ -766,10 +1000,7 @@ const CODEX_NOTIFY_TEMPLATE: &str = r#"param([string]$Payload)
 # the app isn't running or the session wasn't launched by it.
 $ErrorActionPreference = 'SilentlyContinue'
 $port = $env:CLAUDE_LAUNCHER_PORT
-if (-not $port) {
-  $portFile = Join-Path $env:USERPROFILE '.claude-launcher\ide-port'
-  if (Test-Path $portFile) { $port = (Get-Content -Raw $portFile).Trim() }
-}
+$token = $env:CLAUDE_LAUNCHER_TOKEN
 $sid = $env:CLAUDE_LAUNCHER_SESSION
 # NOTE: no early return on a missing session/port here — a Windows Terminal tab
 # has neither and must still get its chime below.
@@ -788,11 +1019,11 @@ foreach ($w in @((Join-Path $PSScriptRoot 'computer-chirp.wav'),
 }
 # Status relay. Only meaningful for sessions this app spawned; a Windows
 # Terminal tab has no session id and stops here, having still chimed.
-if (-not $port -or -not $sid) { return }
+if (-not $port -or -not $token -or -not $sid) { return }
 [System.Net.ServicePointManager]::Expect100Continue = $false
 try {
   $body = @{ session = $sid; event = 'stop' } | ConvertTo-Json -Compress
-  Invoke-RestMethod -Uri ("http://127.0.0.1:$port/event") -Method Post -TimeoutSec 1 -ContentType 'application/json' -Body $body | Out-Null
+  Invoke-RestMethod -Uri ("http://127.0.0.1:$port/event") -Method Post -TimeoutSec 1 -ContentType 'application/json' -Headers @{ 'X-Claude-Launcher-Token' = $token } -Body $body | Out-Null
 } catch { }
 "#;
 
@@ -803,13 +1034,24 @@ try {
 /// corrupt anything. Forward slashes keep the value free of backslash escaping,
 /// and the whole value is free of the shell metacharacters is_safe_flag rejects.
 pub(crate) fn codex_notify_config_arg() -> Result<String, String> {
-    let home = std::env::var("USERPROFILE").map_err(|_| "USERPROFILE not set".to_string())?;
-    let dir = PathBuf::from(home).join(".codex").join("scripts");
-    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
-    let script = dir.join("launcher-codex-notify.ps1");
+    let script = codex_notify_script_path()?;
+    if let Some(dir) = script.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
     // Rewrite every time so a stale or truncated script self-heals.
     fs::write(&script, CODEX_NOTIFY_TEMPLATE)
         .map_err(|e| format!("Failed to write Codex notify script: {}", e))?;
+    Ok(codex_notify_arg_for(&script))
+}
+
+/// Where `codex_notify_config_arg` writes the notify script.
+fn codex_notify_script_path() -> Result<PathBuf, String> {
+    let home = home_dir().ok_or_else(|| "Could not resolve home directory".to_string())?;
+    Ok(home.join(".codex").join("scripts").join("launcher-codex-notify.ps1"))
+}
+
+/// The `--config` argument pointing Codex at the notify script at `script`.
+fn codex_notify_arg_for(script: &std::path::Path) -> String {
     let p = script.to_string_lossy().replace('\\', "/");
     // TOML *literal* strings (single-quoted) rather than basic strings, because
     // this arg has to survive `wt.exe`. Rust escapes the arg correctly for wt,
@@ -821,10 +1063,10 @@ pub(crate) fn codex_notify_config_arg() -> Result<String, String> {
     // Single quotes are inert to wt's parser, to CreateProcessW, and to the
     // pwsh fallback (build_agent_pwsh_cmd doubles them), and TOML treats
     // '...' as a literal string — no escaping needed for a Windows path.
-    Ok(format!(
+    format!(
         "--config=notify=['powershell','-NoProfile','-ExecutionPolicy','Bypass','-File','{}']",
         p
-    ))
+    )
 }
 
 /// Install the Codex turn-completion assets: the notify script plus a local
@@ -838,9 +1080,7 @@ async fn install_codex_notify(app: tauri::AppHandle) -> Result<String, String> {
     // Writes (or refreshes) the script and gives back the arg we would pass.
     codex_notify_config_arg()?;
 
-    let home = std::env::var_os("USERPROFILE")
-        .map(PathBuf::from)
-        .ok_or_else(|| "Could not resolve USERPROFILE".to_string())?;
+    let home = home_dir().ok_or_else(|| "Could not resolve the home directory".to_string())?;
     let scripts_dir = home.join(".codex").join("scripts");
 
     // Copy the chime next to the script so a Codex-only user doesn't depend on
@@ -870,10 +1110,40 @@ async fn install_codex_notify(app: tauri::AppHandle) -> Result<String, String> {
     Ok(msg)
 }
 
-/// The Stop/Notification hook command that runs the IDE event script. The line
+/// The Stop and Notification chime hook commands: a single chirp of `normal`
+/// when Claude finishes a turn, and a faster double-chirp of `fast` when it
+/// pauses for input/permission. Both name their wav, which is what
+/// `upsert_hook` dedups on.
+#[cfg(not(unix))]
+fn chime_commands(normal: &Path, fast: &Path) -> (String, String) {
+    let stop = format!(
+        "powershell -Command \"(New-Object Media.SoundPlayer '{}').PlaySync()\"",
+        normal.display()
+    );
+    let notification = format!(
+        "powershell -Command \"$p = New-Object Media.SoundPlayer '{}'; $p.PlaySync(); Start-Sleep -Milliseconds 120; $p.PlaySync()\"",
+        fast.display()
+    );
+    (stop, notification)
+}
+
+/// The same through whichever player the system has: PulseAudio/PipeWire's
+/// `paplay`, ALSA's `aplay`, or macOS's `afplay`. Hook commands run through
+/// `sh -c`, so the paths are single-quoted; a missing player stays silent.
+#[cfg(unix)]
+fn chime_commands(normal: &Path, fast: &Path) -> (String, String) {
+    let play = |wav: &Path| {
+        let wav = format!("'{}'", wav.to_string_lossy().replace('\'', "'\\''"));
+        format!("{{ paplay {0} || aplay -q {0} || afplay {0} || true; }} >/dev/null 2>&1", wav)
+    };
+    (play(normal), format!("{0}; sleep 0.12; {0}", play(fast)))
+}
+
+/// The hook command that runs the IDE event script for `event`. The line
 /// carries zero `$`/paren/brace tokens so a wrapping POSIX shell can't damage
 /// it, and uses forward slashes so backslash-escaping is moot. The trailing
 /// `#cl-ide-event` is the dedup marker (a harmless extra arg / shell comment).
+#[cfg(not(unix))]
 fn ide_event_command(script_path: &std::path::Path, event: &str) -> String {
     let path = script_path.to_string_lossy().replace('\\', "/");
     format!(
@@ -882,6 +1152,14 @@ fn ide_event_command(script_path: &std::path::Path, event: &str) -> String {
     )
 }
 
+/// The same for the POSIX script. Hook commands run through `sh -c`, so the
+/// path is single-quoted.
+#[cfg(unix)]
+fn ide_event_command(script_path: &std::path::Path, event: &str) -> String {
+    let path = script_path.to_string_lossy().replace('\'', "'\\''");
+    format!("sh '{}' {} #cl-ide-event", path, event)
+}
+
 /// Persist the fullscreen-repaint fix as a **user-level Windows environment
 /// variable** (HKCU\Environment → CLAUDE_CODE_ALT_SCREEN_FULL_REPAINT=1) so every
 /// future terminal — and thus every Claude Code session, however launched —
@@ -904,15 +1182,9 @@ fn ide_event_command(script_path: &std::path::Path, event: &str) -> String {
 /// Already-open Windows Terminal windows still only pick it up after they
 /// restart, since a running process's environment block is fixed at spawn.
 /// Returns Ok(true) when it wrote, Ok(false) when the var was already set.
+#[cfg(windows)]
 fn ensure_full_repaint_env() -> Result<bool, String> {
-    use winreg::enums::HKEY_CURRENT_USER;
-    use winreg::RegKey;
-
-    let env = RegKey::predef(HKEY_CURRENT_USER)
-        .open_subkey("Environment")
-        .map_err(|e| format!("open HKCU\\Environment: {}", e))?;
-    let current: Option<String> = env.get_value(FULL_REPAINT_ENV).ok();
-
+    let current = read_full_repaint_env()?;
     if !full_repaint_needs_write(current.as_deref()) {
         return Ok(false);
     }
@@ -938,10 +1210,43 @@ fn ensure_full_repaint_env() -> Result<bool, String> {
     Ok(true)
 }
 
+/// No HKCU off Windows, and the bug the var works around is Windows Terminal's.
+#[cfg(not(windows))]
+fn ensure_full_repaint_env() -> Result<bool, String> {
+    Ok(false)
+}
+
+/// The repaint var's current HKCU value, if any.
+#[cfg(windows)]
+fn read_full_repaint_env() -> Result<Option<String>, String> {
+    use winreg::enums::HKEY_CURRENT_USER;
+    use winreg::RegKey;
+
+    let env = RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey("Environment")
+        .map_err(|e| format!("open HKCU\\Environment: {}", e))?;
+    Ok(env.get_value(FULL_REPAINT_ENV).ok())
+}
+
+/// Whether `ensure_full_repaint_env` would write, decided by a registry read
+/// alone. An unreadable key counts as "would try".
+#[cfg(windows)]
+fn full_repaint_env_would_write() -> bool {
+    read_full_repaint_env()
+        .map(|current| full_repaint_needs_write(current.as_deref()))
+        .unwrap_or(true)
+}
+
+#[cfg(not(windows))]
+fn full_repaint_env_would_write() -> bool {
+    false
+}
+
 /// Decide whether the repaint var needs writing: only when it is currently unset
 /// or empty. Any explicit value (e.g. a user's deliberate "0") is preserved.
 /// Split out from the registry/PowerShell I/O so it is unit-testable without
 /// touching the real HKCU hive.
+#[cfg(any(windows, test))]
 fn full_repaint_needs_write(current: Option<&str>) -> bool {
     matches!(current, None | Some(""))
 }
@@ -951,6 +1256,7 @@ fn full_repaint_needs_write(current: Option<&str>) -> bool {
 /// same way VS Code does — it passes the real build from the pty host process.
 /// Read from the registry (cheap, spawn-free); 0 on failure, which the frontend
 /// treats as "don't set windowsPty".
+#[cfg(windows)]
 #[tauri::command]
 fn get_os_build() -> u32 {
     use winreg::enums::HKEY_LOCAL_MACHINE;
@@ -964,14 +1270,20 @@ fn get_os_build() -> u32 {
         .unwrap_or(0)
 }
 
+/// Not Windows: no ConPTY, so the frontend leaves `windowsPty` unset.
+#[cfg(not(windows))]
+#[tauri::command]
+fn get_os_build() -> u32 {
+    0
+}
+
 /// Ensure the IDE-mode attention hooks are present in ~/.claude/settings.json,
 /// WITHOUT touching chimes or anything else. Idempotent; called when the user
-/// enters IDE Mode so the rail's blink / Working-end state works out of the box.
+/// enters IDE Mode so the rail's blink / Working-end state, and what each agent
+/// is doing, work out of the box.
 #[tauri::command]
 async fn ensure_ide_hooks() -> Result<String, String> {
-    let home = std::env::var_os("USERPROFILE")
-        .map(PathBuf::from)
-        .ok_or_else(|| "Could not resolve USERPROFILE".to_string())?;
+    let home = home_dir().ok_or_else(|| "Could not resolve the home directory".to_string())?;
     let claude_dir = home.join(".claude");
     let scripts_dir = claude_dir.join("scripts");
     fs::create_dir_all(&scripts_dir).map_err(|e| e.to_string())?;
@@ -979,8 +1291,9 @@ async fn ensure_ide_hooks() -> Result<String, String> {
 
     // Always (re)write the event script so a missing or previously-broken one
     // self-heals on the next entry into IDE Mode.
-    let script_path = scripts_dir.join("launcher-ide-event.ps1");
-    fs::write(&script_path, IDE_EVENT_TEMPLATE)
+    let (script_name, script) = IDE_EVENT_SCRIPT;
+    let script_path = scripts_dir.join(script_name);
+    fs::write(&script_path, script)
         .map_err(|e| format!("Failed to write IDE event script: {}", e))?;
 
     let original = if settings_path.exists() {
@@ -1004,15 +1317,11 @@ async fn ensure_ide_hooks() -> Result<String, String> {
         .ok_or_else(|| "settings.json 'hooks' is not an object".to_string())?;
 
     // upsert_hook strips any prior hook carrying the marker before adding ours,
-    // so re-running heals the old broken inline command in place.
-    upsert_hook(hooks_obj, "Stop", &ide_event_command(&script_path, "stop"), "cl-ide-event", 5);
-    upsert_hook(
-        hooks_obj,
-        "Notification",
-        &ide_event_command(&script_path, "notification"),
-        "cl-ide-event",
-        5,
-    );
+    // so re-running heals the old broken inline command in place. No matcher
+    // on the tool hooks: they report every tool.
+    for (hook, event) in listener::HOOK_EVENTS {
+        upsert_hook(hooks_obj, hook, &ide_event_command(&script_path, event), "cl-ide-event", 5);
+    }
 
     let serialized =
         serde_json::to_string_pretty(&root).map_err(|e| e.to_string())?;
@@ -1035,9 +1344,7 @@ async fn ensure_ide_hooks() -> Result<String, String> {
 async fn install_chime_hooks(app: tauri::AppHandle) -> Result<String, String> {
     let log_path = app.state::<LogPath>().0.lock().unwrap().clone();
 
-    let home = std::env::var_os("USERPROFILE")
-        .map(PathBuf::from)
-        .ok_or_else(|| "Could not resolve USERPROFILE".to_string())?;
+    let home = home_dir().ok_or_else(|| "Could not resolve the home directory".to_string())?;
     let claude_dir = home.join(".claude");
     let sounds_dir = claude_dir.join("sounds");
     fs::create_dir_all(&sounds_dir)
@@ -1064,20 +1371,12 @@ async fn install_chime_hooks(app: tauri::AppHandle) -> Result<String, String> {
     let scripts_dir = claude_dir.join("scripts");
     fs::create_dir_all(&scripts_dir)
         .map_err(|e| format!("Failed to create {}: {}", scripts_dir.display(), e))?;
-    let ide_script_path = scripts_dir.join("launcher-ide-event.ps1");
-    fs::write(&ide_script_path, IDE_EVENT_TEMPLATE)
+    let (ide_script_name, ide_script) = IDE_EVENT_SCRIPT;
+    let ide_script_path = scripts_dir.join(ide_script_name);
+    fs::write(&ide_script_path, ide_script)
         .map_err(|e| format!("Failed to write IDE event script: {}", e))?;
 
-    // Stop: single chirp when Claude finishes a turn.
-    let stop_cmd = format!(
-        "powershell -Command \"(New-Object Media.SoundPlayer '{}').PlaySync()\"",
-        normal.display()
-    );
-    // Notification: faster double-chirp when Claude pauses for input/permission.
-    let notif_cmd = format!(
-        "powershell -Command \"$p = New-Object Media.SoundPlayer '{}'; $p.PlaySync(); Start-Sleep -Milliseconds 120; $p.PlaySync()\"",
-        fast.display()
-    );
+    let (stop_cmd, notif_cmd) = chime_commands(&normal, &fast);
 
     let settings_path = claude_dir.join("settings.json");
     let mut root: serde_json::Value = if settings_path.exists() {
@@ -1139,9 +1438,7 @@ fn normalize_path_key(path: &str) -> String {
 /// The installed statusLine reads this map by the cwd it receives to render
 /// "<name> — <model>". Best-effort; callers log and continue on error.
 fn upsert_tab_name(project_path: &str, name: &str) -> Result<(), String> {
-    let home = std::env::var_os("USERPROFILE")
-        .map(PathBuf::from)
-        .ok_or_else(|| "Could not resolve USERPROFILE".to_string())?;
+    let home = home_dir().ok_or_else(|| "Could not resolve home directory".to_string())?;
     let claude_dir = home.join(".claude");
     fs::create_dir_all(&claude_dir).map_err(|e| e.to_string())?;
     let map_path = claude_dir.join("launcher-tab-names.json");
@@ -1236,9 +1533,7 @@ elseif ($model) { Write-Output $model }
 async fn install_model_title_statusline(app: tauri::AppHandle) -> Result<String, String> {
     let log_path = app.state::<LogPath>().0.lock().unwrap().clone();
 
-    let home = std::env::var_os("USERPROFILE")
-        .map(PathBuf::from)
-        .ok_or_else(|| "Could not resolve USERPROFILE".to_string())?;
+    let home = home_dir().ok_or_else(|| "Could not resolve the home directory".to_string())?;
     let claude_dir = home.join(".claude");
     let scripts_dir = claude_dir.join("scripts");
     fs::create_dir_all(&scripts_dir)
@@ -1329,8 +1624,7 @@ async fn detect_agent_path(agent_id: Option<String>) -> Result<String, String> {
 
     let mut candidates: Vec<PathBuf> = Vec::new();
 
-    if let Some(home) = std::env::var_os("USERPROFILE") {
-        let home_path = PathBuf::from(home);
+    if let Some(home_path) = home_dir() {
         candidates.push(home_path.join(".local").join("bin").join(format!("{}.exe", exe_stem)));
         candidates.push(home_path.join(".local").join("bin").join(exe_stem));
         candidates.push(
@@ -1367,24 +1661,36 @@ async fn get_log_path(app: tauri::AppHandle) -> Result<String, String> {
     Ok(log_path.to_string_lossy().to_string())
 }
 
-#[tauri::command]
-async fn read_log(app: tauri::AppHandle, tail_lines: Option<usize>) -> Result<String, String> {
+/// The log path, provided it is still inside the app data directory.
+pub(crate) fn checked_log_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
     let log_path = app.state::<LogPath>().0.lock().unwrap().clone();
-
-    // Ensure log path is within the app data directory
     let app_data = app.state::<AppDataDir>().0.clone();
     let canonical_log = log_path.canonicalize().unwrap_or(log_path.clone());
     let canonical_app = app_data.canonicalize().unwrap_or(app_data.clone());
     if !canonical_log.starts_with(&canonical_app) {
         return Err("Log path is outside app data directory".to_string());
     }
+    Ok(log_path)
+}
 
-    match fs::read_to_string(&log_path) {
-        Ok(content) => {
-            let lines: Vec<&str> = content.lines().collect();
-            let n = tail_lines.unwrap_or(100);
-            let start = if lines.len() > n { lines.len() - n } else { 0 };
-            Ok(lines[start..].join("\n"))
+/// The last `tail_lines` log entries as text, one per line.
+#[tauri::command]
+async fn read_log(app: tauri::AppHandle, tail_lines: Option<usize>) -> Result<String, String> {
+    let log_path = checked_log_path(&app)?;
+
+    // Tails across rotated segments, so a rotation just before the call
+    // doesn't cut the view short. Waiting on the logger and reading both
+    // block, so neither happens on a worker.
+    let tail = tauri::async_runtime::spawn_blocking(move || {
+        logger::flush();
+        launch_log::read_tail(&log_path, tail_lines.unwrap_or(100))
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    match tail {
+        Ok(entries) => {
+            let lines: Vec<String> = entries.iter().map(LogEntry::to_text).collect();
+            Ok(lines.join("\n"))
         }
         Err(e) => {
             if e.kind() == std::io::ErrorKind::NotFound {
@@ -1398,18 +1704,14 @@ async fn read_log(app: tauri::AppHandle, tail_lines: Option<usize>) -> Result<St
 
 #[tauri::command]
 async fn open_log_folder(app: tauri::AppHandle) -> Result<(), String> {
-    let log_path = app.state::<LogPath>().0.lock().unwrap().clone();
-
-    // Ensure log path is within the app data directory
-    let app_data = app.state::<AppDataDir>().0.clone();
-    let canonical_log = log_path.canonicalize().unwrap_or(log_path.clone());
-    let canonical_app = app_data.canonicalize().unwrap_or(app_data.clone());
-    if !canonical_log.starts_with(&canonical_app) {
-        return Err("Log path is outside app data directory".to_string());
-    }
+    let log_path = checked_log_path(&app)?;
 
+    // The folder holds the rotated segments too; it exists even when the
+    // active file has just been rotated away.
     if let Some(parent) = log_path.parent() {
-        let _ = Command::new("explorer").arg(parent).spawn();
+        let _ = fs::create_dir_all(parent);
+        let opener = if cfg!(windows) { "explorer" } else { "xdg-open" };
+        let _ = Command::new(opener).arg(parent).spawn();
     }
     Ok(())
 }
@@ -1426,20 +1728,38 @@ pub fn run() {
         .setup(|app| {
             // Default log path: app data dir / logs / claude-launcher.log
             let app_data = app.path().app_data_dir().unwrap_or_else(|_| {
-                PathBuf::from(std::env::var("USERPROFILE").unwrap_or_default())
-                    .join(".claude-launcher")
+                home_dir().unwrap_or_default().join(".claude-launcher")
             });
             let log_path = app_data.join("logs").join("claude-launcher.log");
+            // The saved retention period, not the default, before anything is
+            // written: the first write may rotate, which enforces it too.
+            let expired = logger::retain(&log_path, Some(log_file::saved_retention(&app_data)));
             write_log(&log_path, "INFO", "Claude Launcher started");
+            if expired > 0 {
+                write_log(&log_path, "INFO", &format!("Removed {} expired log segment(s)", expired));
+            }
+            let swept = prelaunch::sweep(&prelaunch::script_dir(), prelaunch::SCRIPT_TTL);
+            if swept > 0 {
+                write_log(&log_path, "INFO", &format!("Removed {} stale pre-launch script(s)", swept));
+            }
+            app.manage(secrets::SecretStore::new(app_data.join("secrets")));
+            app.manage(history::LaunchHistory::new(app_data.join("launch-history.jsonl")));
+            app.manage(recording::RecordingDir(app_data.join("recordings")));
+            app.manage(replay::Replays::default());
             app.manage(AppDataDir(app_data));
             app.manage(LogPath(Mutex::new(log_path)));
             app.manage(ide::PtySessions::default());
-            let ide_port = ide::start_ide_listener(app.handle().clone());
-            app.manage(ide::IdePort(std::sync::atomic::AtomicU16::new(ide_port)));
+            app.manage(tmux::MultiplexerSessions::default());
+            let ide_token = listener::new_token();
+            let ide_listener = listener::start_ide_listener(app.handle().clone(), ide_token.clone());
+            app.manage(ide::IdePort(std::sync::atomic::AtomicU16::new(ide_listener.port)));
+            app.manage(listener::IdeToken(ide_token));
+            app.manage(ide_listener);
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             launch_agent,
+            preview_launch,
             launch_shell,
             detect_agent_path,
             install_chime_hooks,
@@ -1450,17 +1770,49 @@ pub fn run() {
             get_log_path,
             read_log,
             open_log_folder,
+            launch_log::query_log,
+            launch_log::subscribe_log,
+            launch_log::unsubscribe_log,
+            log_file::set_log_retention,
+            history::launch_history,
+            history::launch_stats,
             ide::spawn_pty,
+            ide::attach_pty,
             ide::write_pty,
             ide::resize_pty,
             ide::kill_pty,
+            ide::list_pty_sessions,
+            recording::list_recordings,
+            recording::export_recording,
+            recording::delete_recording,
+            replay::start_replay,
+            replay::control_replay,
+            transcript::export_transcript,
             ide::read_dir_entries,
             ide::git_status,
             ide::git_diff,
+            tmux::list_tmux_windows,
+            tmux::focus_tmux_window,
+            profile::list_launch_profiles,
+            secrets::list_secrets,
+            secrets::set_secret,
+            secrets::delete_secret,
+            redact::set_log_redaction,
             get_os_build,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // Entries are written by the logger thread; don't exit with any
+            // still queued. The listener stops taking pings first, so none
+            // logs after the flush.
+            if let tauri::RunEvent::Exit = event {
+                if let Some(listener) = app.try_state::<listener::IdeListener>() {
+                    listener.shutdown();
+                }
+                logger::flush();
+            }
+        });
 }
 
 #[cfg(test)]
@@ -1520,6 +1872,67 @@ mod tests {
         assert!(!is_safe_subcommand(&"a".repeat(33)));  // length cap
     }
 
+    /// Validation runs before anything touches the system, and a request that
+    /// passes it still has to point at real paths. The preview shares this,
+    /// so both report identical wording.
+    #[test]
+    fn launch_validation() {
+        let dir = std::env::temp_dir().to_string_lossy().to_string();
+        let agent = std::env::current_exe().unwrap().to_string_lossy().to_string();
+        let ok = LaunchRequest {
+            agent_path: agent.clone(),
+            project_path: dir.clone(),
+            terminal_profile: "PowerShell".to_string(),
+            flags: vec!["--verbose".to_string()],
+            ..Default::default()
+        };
+        assert!(validate_launch(&ok).is_ok());
+
+        let bad_flag = LaunchRequest { flags: vec!["--x=$(id)".to_string()], ..ok.clone() };
+        let e = validate_launch(&bad_flag).err().unwrap();
+        assert!(matches!(e, LaunchError::Validation { field: "flags", .. }));
+        assert_eq!(e.to_string(), "Invalid flag rejected: --x=$(id)");
+
+        let bad_mux = LaunchRequest { multiplexer: Some("byobu".to_string()), ..ok.clone() };
+        assert!(matches!(
+            validate_launch(&bad_mux).err().unwrap(),
+            LaunchError::Validation { field: "multiplexer", .. }
+        ));
+        let tmux = LaunchRequest { multiplexer: Some("tmux".to_string()), ..ok.clone() };
+        if cfg!(windows) {
+            assert!(matches!(
+                validate_launch(&tmux).err().unwrap(),
+                LaunchError::Unsupported { field: "multiplexer", .. }
+            ));
+        } else {
+            assert!(validate_launch(&tmux).is_ok());
+        }
+
+        let missing = LaunchRequest {
+            project_path: format!("{}/definitely-not-here-4c1e", dir),
+            ..ok.clone()
+        };
+        let e = validate_launch(&missing).err().unwrap();
+        assert!(matches!(e, LaunchError::MissingPath { field: "projectPath", .. }));
+        assert!(e.to_string().starts_with("Project directory does not exist"));
+
+        let env = |k: &str, v: &str| BTreeMap::from([(k.to_string(), v.to_string())]);
+        let good_env = LaunchRequest { env: env("ANTHROPIC_BASE_URL", "https://proxy.local/v1"), ..ok.clone() };
+        assert!(validate_launch(&good_env).is_ok());
+        for (k, v) in [("BAD-NAME", "1"), ("1ST", "1"), ("CLAUDE_LAUNCHER_PORT", "1"), ("OK", "$(id)"), ("OK", "a;b")] {
+            let bad = LaunchRequest { env: env(k, v), ..ok.clone() };
+            let e = validate_launch(&bad).err().unwrap();
+            assert!(matches!(e, LaunchError::Validation { field: "env", .. }), "{}={}", k, v);
+            assert!(!e.to_string().contains("$(id)"), "value must not be echoed");
+        }
+
+        let no_agent = LaunchRequest { agent_path: format!("{}/no-such-agent-4c1e", dir), ..ok.clone() };
+        assert!(matches!(
+            validate_launch(&no_agent).err().unwrap(),
+            LaunchError::MissingPath { field: "agentPath", .. }
+        ));
+    }
+
     /// The write decision must fire only when the var is unset or empty, and must
     /// preserve any explicit value the user chose (including a deliberate "0"
     /// opt-out). Pure — never touches the real HKCU hive.
@@ -1530,4 +1943,110 @@ mod tests {
         assert!(!full_repaint_needs_write(Some("1"))); // already on → skip
         assert!(!full_repaint_needs_write(Some("0"))); // deliberate opt-out → respect
     }
+
+    /// Both event scripts send the instance token, and the POSIX one parses,
+    /// knows every installed hook and is run with its path quoted.
+    /// The POSIX chimes play through the first player that works, with the
+    /// wav path intact, and never fail the hook.
+    #[cfg(unix)]
+    #[test]
+    fn posix_chimes_use_an_available_player() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = std::env::temp_dir().join(format!("claude-launcher-chime-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let played = dir.join("played");
+        let aplay = dir.join("aplay");
+        fs::write(&aplay, format!("#!/bin/sh\necho \"$*\" >> '{}'\n", played.display())).unwrap();
+        fs::set_permissions(&aplay, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let (stop, notification) = chime_commands(Path::new("/snd/it's.wav"), Path::new("/snd/fast.wav"));
+        let run = |cmd: &str| {
+            Command::new("/bin/sh").arg("-c").arg(cmd).env("PATH", &dir).status().unwrap()
+        };
+        assert!(run(&stop).success());
+        assert!(run(&notification).success());
+        assert_eq!(
+            fs::read_to_string(&played).unwrap(),
+            "-q /snd/it's.wav\n-q /snd/fast.wav\n-q /snd/fast.wav\n"
+        );
+        // No player at all: silent, and still a success.
+        fs::remove_file(&aplay).unwrap();
+        assert!(run(&stop).success());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn ide_event_scripts_send_the_token() {
+        for template in [IDE_EVENT_TEMPLATE, IDE_EVENT_SH_TEMPLATE, CODEX_NOTIFY_TEMPLATE] {
+            assert!(template.contains("$env:CLAUDE_LAUNCHER_TOKEN") || template.contains("$CLAUDE_LAUNCHER_TOKEN"));
+            assert!(template.contains("X-Claude-Launcher-Token"));
+        }
+        assert!(IDE_EVENT_SH_TEMPLATE.contains("--unix-socket \"$CLAUDE_LAUNCHER_SOCKET\""));
+        // Both cap the payload they forward at the listener's limit.
+        for template in [IDE_EVENT_TEMPLATE, IDE_EVENT_SH_TEMPLATE] {
+            assert!(template.contains(&listener::MAX_PAYLOAD.to_string()));
+        }
+        // The POSIX script drops any event it doesn't list.
+        for (_, event) in listener::HOOK_EVENTS {
+            assert!(IDE_EVENT_SH_TEMPLATE.contains(event));
+        }
+
+        #[cfg(unix)]
+        {
+            let script = std::env::temp_dir().join(format!("launcher-ide-event-test-{}.sh", std::process::id()));
+            fs::write(&script, IDE_EVENT_SH_TEMPLATE).unwrap();
+            let status = Command::new("sh").arg("-n").arg(&script).status().unwrap();
+            let _ = fs::remove_file(&script);
+            assert!(status.success());
+
+            // A payload the listener can't parse (400) costs the payload, not
+            // the ping: the bare ping goes again. A stand-in curl records each
+            // body and answers 400 to any that carries a payload.
+            use std::os::unix::fs::PermissionsExt;
+            let dir = std::env::temp_dir().join(format!("launcher-ide-event-retry-{}", std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            let (script, sent, curl) = (dir.join("event.sh"), dir.join("sent"), dir.join("curl"));
+            fs::write(&script, IDE_EVENT_SH_TEMPLATE).unwrap();
+            fs::write(
+                &curl,
+                format!(
+                    "#!/bin/sh\nwhile [ \"$1\" != -d ]; do shift; done\nprintf '%s\\n' \"$2\" >> '{}'\n\
+                     case \"$2\" in *payload*) printf 400 ;; *) printf 200 ;; esac\n",
+                    sent.display()
+                ),
+            )
+            .unwrap();
+            fs::set_permissions(&curl, fs::Permissions::from_mode(0o755)).unwrap();
+            let run = |stdin: &str| {
+                let mut child = Command::new("sh")
+                    .arg(&script)
+                    .arg("stop")
+                    .env("PATH", format!("{}:/usr/bin:/bin", dir.display()))
+                    .env("CLAUDE_LAUNCHER_SESSION", "s1")
+                    .env("CLAUDE_LAUNCHER_TOKEN", "t")
+                    .env("CLAUDE_LAUNCHER_PORT", "1")
+                    .env_remove("CLAUDE_LAUNCHER_SOCKET")
+                    .stdin(std::process::Stdio::piped())
+                    .spawn()
+                    .unwrap();
+                use std::io::Write;
+                child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();
+                assert!(child.wait().unwrap().success());
+            };
+            run(r#"{"session_id": oops}"#);
+            run("not json");
+            assert_eq!(
+                fs::read_to_string(&sent).unwrap(),
+                "{\"session\":\"s1\",\"event\":\"stop\",\"payload\":{\"session_id\": oops}}\n\
+                 {\"session\":\"s1\",\"event\":\"stop\"}\n\
+                 {\"session\":\"s1\",\"event\":\"stop\"}\n"
+            );
+            let _ = fs::remove_dir_all(&dir);
+
+            let cmd = ide_event_command(std::path::Path::new("/home/o'neil/.claude/scripts/launcher-ide-event.sh"), "stop");
+            assert_eq!(cmd, "sh '/home/o'\\''neil/.claude/scripts/launcher-ide-event.sh' stop #cl-ide-event");
+        }
+    }
 }
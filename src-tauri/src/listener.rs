@@ -0,0 +1,794 @@
+//! The loopback attention listener: a small HTTP server on 127.0.0.1 that
+//! turns Claude Code's hook pings into `session-state` and `session-event`s.
+//!
+//! On Linux and macOS the same server also listens on a Unix socket in a
+//! directory only this user can enter, itself mode 0600, so nothing else on
+//! the machine can even connect. Hook scripts prefer it when a session has
+//! `CLAUDE_LAUNCHER_SOCKET`, and use TCP otherwise.
+//!
+//! Routes:
+//! - `POST /event` with `{"session": "<id>", "event": "<hook>", "tool": "<name>",
+//!   "payload": {...}}`, the wire format every installed hook script sends.
+//!   `event` is one of `HOOK_EVENTS`' names, `tool` is only there for the tool
+//!   hooks, and `payload` is the hook's own stdin JSON when it is small enough
+//!   (`MAX_PAYLOAD`). `stop` and `notification` end a turn (`session-state`);
+//!   every event, those two included, is also forwarded as a typed
+//!   `session-event`, with the `HookDetails` the payload yields, so the rail can
+//!   show what an agent is doing right now;
+//! - `GET /health`, for checking the app is up;
+//! - `GET /sessions`, the live sessions as `list_pty_sessions` reports them.
+//!
+//! Everything but `/health` needs this instance's token in the
+//! `X-Claude-Launcher-Token` header (see `IdeToken`). Bodies are capped at
+//! `MAX_BODY`. Connections are served concurrently on the async runtime, so a
+//! slow client holds up nobody else, and the server stops accepting when the
+//! app exits (`IdeListener::shutdown`).
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use axum::body::Bytes;
+use axum::extract::{DefaultBodyLimit, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::{Local, SecondsFormat};
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Manager};
+use tokio::sync::watch;
+
+use crate::history::{self, Record, Transition};
+use crate::ide::{PtySessionInfo, PtySessions};
+use crate::launch_log::{self, EventKind, LogEntry};
+use crate::tmux::MultiplexerSessions;
+use crate::{home_dir, write_log, LogPath};
+
+/// Largest hook payload the scripts forward; a bigger one (a PreToolUse for a
+/// large Write, say) is left out of the ping.
+pub(crate) const MAX_PAYLOAD: usize = 32 * 1024;
+
+/// Largest request body accepted: a few dozen bytes of ping around the
+/// payload, with room to spare for PowerShell re-encoding it.
+const MAX_BODY: usize = 2 * MAX_PAYLOAD;
+
+/// Caps on the free text `HookDetails` passes on, in characters.
+const MAX_MESSAGE: usize = 300;
+const MAX_TARGET: usize = 160;
+
+/// The header the hook scripts send the token in.
+const TOKEN_HEADER: &str = "X-Claude-Launcher-Token";
+
+/// This app instance's listener token: random per run, stamped onto every
+/// spawned session as `CLAUDE_LAUNCHER_TOKEN`, and required on every ping. The
+/// port alone is no secret, and neither is a session id once it's in a log, so
+/// without it any local process could flip sessions to "complete"/"waiting".
+pub struct IdeToken(pub String);
+
+pub(crate) fn new_token() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+/// Managed state: where the listener is, and its off switch.
+pub struct IdeListener {
+    /// 0 when it could not bind.
+    pub port: u16,
+    /// The Unix socket, where there is one. Stamped onto every spawned session
+    /// as `CLAUDE_LAUNCHER_SOCKET`.
+    pub socket: Option<PathBuf>,
+    shutdown: watch::Sender<bool>,
+}
+
+impl IdeListener {
+    /// Stop accepting connections; requests already in flight finish.
+    pub fn shutdown(&self) {
+        let _ = self.shutdown.send(true);
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StatePayload {
+    session_id: String,
+    status: String,
+}
+
+/// A `POST /event` body. Missing fields read as empty, so they are reported
+/// as such rather than as unparseable.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Ping {
+    session: String,
+    event: String,
+    tool: Option<String>,
+    payload: Option<serde_json::Value>,
+}
+
+/// The Claude Code hooks `ensure_ide_hooks` installs, and the event name each
+/// one's script sends.
+pub(crate) const HOOK_EVENTS: &[(&str, &str)] = &[
+    ("Stop", "stop"),
+    ("Notification", "notification"),
+    ("PreToolUse", "pre-tool-use"),
+    ("PostToolUse", "post-tool-use"),
+    ("UserPromptSubmit", "user-prompt-submit"),
+    ("SessionStart", "session-start"),
+    ("SessionEnd", "session-end"),
+    ("SubagentStop", "subagent-stop"),
+    ("PreCompact", "pre-compact"),
+];
+
+/// A hook event, as `session-event` carries it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub(crate) enum SessionEvent {
+    /// The turn is over (Stop).
+    Stopped,
+    /// The agent wants the user: a permission prompt, or idle input (Notification).
+    Notification,
+    ToolStarted { tool: Option<String> },
+    ToolFinished { tool: Option<String> },
+    PromptSubmitted,
+    SessionStarted,
+    SessionEnded,
+    SubagentStopped,
+    CompactionStarted,
+}
+
+impl SessionEvent {
+    /// `event` as a hook script names it, with the tool the hook reported.
+    fn parse(event: &str, tool: Option<String>) -> Option<Self> {
+        // Only ever shown, but it comes from whatever sent the ping.
+        let tool = tool.filter(|t| is_tool_name(t));
+        Some(match event {
+            "stop" => SessionEvent::Stopped,
+            "notification" => SessionEvent::Notification,
+            "pre-tool-use" => SessionEvent::ToolStarted { tool },
+            "post-tool-use" => SessionEvent::ToolFinished { tool },
+            "user-prompt-submit" => SessionEvent::PromptSubmitted,
+            "session-start" => SessionEvent::SessionStarted,
+            "session-end" => SessionEvent::SessionEnded,
+            "subagent-stop" => SessionEvent::SubagentStopped,
+            "pre-compact" => SessionEvent::CompactionStarted,
+            _ => return None,
+        })
+    }
+
+    /// The status change the event means, for the two that end a turn.
+    fn transition(&self) -> Option<Transition> {
+        match self {
+            SessionEvent::Stopped => Some(Transition::Complete),
+            SessionEvent::Notification => Some(Transition::Waiting),
+            _ => None,
+        }
+    }
+}
+
+/// Built-in tools are words; MCP tools are `mcp__<server>__<tool>`.
+fn is_tool_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 128
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | ':'))
+}
+
+/// The part of a hook's stdin payload the UI gets. Each field is checked
+/// against what Claude Code sends, and left out when it doesn't fit; free text
+/// is flattened to one line and clipped.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HookDetails {
+    /// The agent's own JSONL transcript of the conversation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transcript_path: Option<String>,
+    /// Claude Code's id for the conversation. Not ours: it changes on /clear.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent_session_id: Option<String>,
+    /// What a Notification says, e.g. "Claude needs your permission to use Bash".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+    /// What the tool call is aimed at: its command, file, pattern or URL.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_target: Option<String>,
+}
+
+impl HookDetails {
+    /// The checked subset of `payload`, or None when nothing in it passes.
+    /// `transcripts` is the directory transcript paths must resolve into.
+    fn from_payload(payload: &serde_json::Value, transcripts: Option<&Path>) -> Option<Self> {
+        let fields = payload.as_object()?;
+        let text = |key: &str| fields.get(key).and_then(serde_json::Value::as_str);
+        let details = HookDetails {
+            transcript_path: text("transcript_path")
+                .filter(|p| transcripts.is_some_and(|root| is_transcript_path(p, root)))
+                .map(str::to_string),
+            agent_session_id: text("session_id").filter(|id| is_agent_session_id(id)).map(str::to_string),
+            message: text("message").map(|m| clip(m, MAX_MESSAGE)).filter(|m| !m.is_empty()),
+            tool_name: text("tool_name").filter(|t| is_tool_name(t)).map(str::to_string),
+            tool_target: fields.get("tool_input").and_then(tool_target),
+        };
+        (details != HookDetails::default()).then_some(details)
+    }
+}
+
+/// Where Claude Code keeps its transcripts: `~/.claude/projects`.
+fn transcripts_dir() -> Option<PathBuf> {
+    home_dir().map(|home| home.join(".claude").join("projects"))
+}
+
+/// Transcripts are existing `.jsonl` files under `root`; anything else the UI
+/// might be asked to open is refused. Both sides are canonicalised, so `..`
+/// and symlinks can't lead out of `root`.
+fn is_transcript_path(path: &str, root: &Path) -> bool {
+    if path.len() > 4096 || !path.ends_with(".jsonl") || path.chars().any(char::is_control) {
+        return false;
+    }
+    let path = Path::new(path);
+    if !path.is_absolute() {
+        return false;
+    }
+    match (path.canonicalize(), root.canonicalize()) {
+        (Ok(path), Ok(root)) => path.starts_with(&root) && path.is_file(),
+        _ => false,
+    }
+}
+
+/// Claude Code's conversation ids are UUIDs.
+fn is_agent_session_id(id: &str) -> bool {
+    !id.is_empty() && id.len() <= 64 && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// The first of the fields the built-in tools name their target in.
+fn tool_target(input: &serde_json::Value) -> Option<String> {
+    let input = input.as_object()?;
+    ["command", "file_path", "notebook_path", "path", "pattern", "url", "query", "description"]
+        .iter()
+        .find_map(|key| input.get(*key).and_then(serde_json::Value::as_str))
+        .map(|target| clip(target, MAX_TARGET))
+        .filter(|target| !target.is_empty())
+}
+
+/// `text` on one line, runs of whitespace and control characters collapsed to
+/// a space, cut to `max` characters with an ellipsis.
+fn clip(text: &str, max: usize) -> String {
+    let line = text
+        .split(|c: char| c.is_whitespace() || c.is_control())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    match line.char_indices().nth(max) {
+        Some((end, _)) => format!("{}…", &line[..end]),
+        None => line,
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SessionEventPayload<'a> {
+    session_id: &'a str,
+    /// RFC 3339, local offset, millisecond precision, like the log.
+    at: String,
+    #[serde(flatten)]
+    event: &'a SessionEvent,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    details: Option<&'a HookDetails>,
+}
+
+/// What the listener acts on. The app's is `AppSink`; tests bring their own.
+pub(crate) trait Sink: Send + Sync + 'static {
+    /// `session` is one of this instance's: a live PTY, or a multiplexer
+    /// window it launched.
+    fn owns(&self, session: &str) -> bool;
+    fn event(&self, session: &str, event: &SessionEvent, details: Option<&HookDetails>);
+    /// A request the listener did not act on, and why.
+    fn rejected(&self, session: Option<&str>, reason: &str);
+    fn sessions(&self) -> Vec<PtySessionInfo>;
+}
+
+struct AppSink(tauri::AppHandle);
+
+impl Sink for AppSink {
+    fn owns(&self, session: &str) -> bool {
+        self.0.try_state::<PtySessions>().is_some_and(|s| s.contains(session))
+            || self.0.try_state::<MultiplexerSessions>().is_some_and(|s| s.contains(session))
+    }
+
+    fn event(&self, session: &str, event: &SessionEvent, details: Option<&HookDetails>) {
+        if let Some(transition) = event.transition() {
+            let status = match transition {
+                Transition::Complete => "complete",
+                Transition::Waiting => "waiting",
+            };
+            if let Some(log_path) = self.0.try_state::<LogPath>().map(|p| p.0.lock().unwrap().clone()) {
+                launch_log::append(
+                    &log_path,
+                    &LogEntry::new("INFO", EventKind::SessionState, format!("Session {}", status)).session(session),
+                );
+            }
+            history::record(&self.0, Record::transition(session, transition));
+            let _ = self.0.emit(
+                "session-state",
+                StatePayload {
+                    session_id: session.to_string(),
+                    status: status.to_string(),
+                },
+            );
+        }
+        let _ = self.0.emit(
+            "session-event",
+            SessionEventPayload {
+                session_id: session,
+                at: Local::now().to_rfc3339_opts(SecondsFormat::Millis, false),
+                event,
+                details,
+            },
+        );
+    }
+
+    fn rejected(&self, session: Option<&str>, reason: &str) {
+        let Some(log_path) = self.0.try_state::<LogPath>().map(|p| p.0.lock().unwrap().clone()) else {
+            return;
+        };
+        let mut entry = LogEntry::new("WARN", EventKind::RejectedPing, format!("Rejected hook ping: {}", reason));
+        if let Some(id) = session {
+            entry = entry.session(id);
+        }
+        launch_log::append(&log_path, &entry);
+    }
+
+    fn sessions(&self) -> Vec<PtySessionInfo> {
+        self.0.try_state::<PtySessions>().map(|s| s.list()).unwrap_or_default()
+    }
+}
+
+struct Listener {
+    token: String,
+    sink: Arc<dyn Sink>,
+}
+
+impl Listener {
+    /// The request carries the token; when it doesn't, that is logged.
+    fn authorized(&self, headers: &HeaderMap) -> bool {
+        let given = headers.get(TOKEN_HEADER).and_then(|v| v.to_str().ok()).unwrap_or("");
+        let ok = token_matches(given, &self.token);
+        if !ok {
+            self.sink.rejected(None, "missing or wrong token");
+        }
+        ok
+    }
+}
+
+/// Compare without stopping at the first differing byte, so response timing
+/// says nothing about how much of a guess was right.
+fn token_matches(given: &str, token: &str) -> bool {
+    !token.is_empty()
+        && given.len() == token.len()
+        && given.bytes().zip(token.bytes()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+fn router(token: String, sink: Arc<dyn Sink>) -> Router {
+    Router::new()
+        .route("/event", post(post_event))
+        .route("/health", get(health))
+        .route("/sessions", get(sessions))
+        .layer(DefaultBodyLimit::max(MAX_BODY))
+        .with_state(Arc::new(Listener { token, sink }))
+}
+
+async fn post_event(State(listener): State<Arc<Listener>>, headers: HeaderMap, body: Bytes) -> StatusCode {
+    if !listener.authorized(&headers) {
+        return StatusCode::UNAUTHORIZED;
+    }
+    let sink = &listener.sink;
+    // Parsed here rather than by `Json` so a script that gets the content
+    // type wrong still works, and every rejection is logged the same way.
+    let Ok(ping) = serde_json::from_slice::<Ping>(&body) else {
+        sink.rejected(None, "body is not JSON");
+        return StatusCode::BAD_REQUEST;
+    };
+    if ping.session.is_empty() {
+        sink.rejected(None, "no session id");
+        return StatusCode::BAD_REQUEST;
+    }
+    // Only act on sessions we own.
+    if !sink.owns(&ping.session) {
+        sink.rejected(Some(&ping.session), "not a session of this instance");
+        return StatusCode::NOT_FOUND;
+    }
+    // Checking the transcript path touches the disk, so not on a worker.
+    let details = match ping.payload {
+        Some(payload) => tauri::async_runtime::spawn_blocking(move || {
+            HookDetails::from_payload(&payload, transcripts_dir().as_deref())
+        })
+        .await
+        .ok()
+        .flatten(),
+        None => None,
+    };
+    // A script that couldn't pick out the tool name leaves it to the payload.
+    let tool = ping.tool.or_else(|| details.as_ref().and_then(|d| d.tool_name.clone()));
+    let Some(event) = SessionEvent::parse(&ping.event, tool) else {
+        sink.rejected(Some(&ping.session), &format!("unknown event {:?}", ping.event));
+        return StatusCode::BAD_REQUEST;
+    };
+    sink.event(&ping.session, &event, details.as_ref());
+    StatusCode::OK
+}
+
+async fn health() -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "status": "ok" }))
+}
+
+async fn sessions(
+    State(listener): State<Arc<Listener>>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<PtySessionInfo>>, StatusCode> {
+    if !listener.authorized(&headers) {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    Ok(Json(listener.sink.sessions()))
+}
+
+/// Resolves once `shutdown` is set.
+async fn stopped(mut shutdown: watch::Receiver<bool>) {
+    let _ = shutdown.wait_for(|stop| *stop).await;
+}
+
+/// Bind 127.0.0.1:<random> and serve `router` on the async runtime until
+/// `shutdown`. Binding happens before this returns, so the caller can stamp
+/// the real port onto sessions before any are spawned.
+fn serve_tcp(router: Router, shutdown: watch::Receiver<bool>) -> io::Result<u16> {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+    listener.set_nonblocking(true)?;
+    let port = listener.local_addr()?.port();
+    tauri::async_runtime::spawn(async move {
+        let Ok(listener) = tokio::net::TcpListener::from_std(listener) else {
+            return;
+        };
+        let _ = axum::serve(listener, router).with_graceful_shutdown(stopped(shutdown)).await;
+    });
+    Ok(port)
+}
+
+/// Where this instance's socket goes: a directory of ours under the runtime
+/// dir (the temp dir where there is none, as on macOS), one socket per
+/// process so instances don't collide.
+#[cfg(unix)]
+fn socket_path() -> io::Result<PathBuf> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+
+    let base = std::env::var_os("XDG_RUNTIME_DIR")
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir);
+    let dir = base.join("claude-launcher");
+    std::fs::DirBuilder::new().recursive(true).mode(0o700).create(&dir)?;
+    // Someone else's directory, or one opened up, is no place for it: in the
+    // shared temp dir anyone could have made it first.
+    let meta = std::fs::symlink_metadata(&dir)?;
+    if !meta.is_dir() || meta.permissions().mode() & 0o077 != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("{} is not a private directory", dir.display()),
+        ));
+    }
+    Ok(dir.join(format!("listener-{}.sock", std::process::id())))
+}
+
+/// Bind a Unix socket at `path`, readable and writable by this user only, and
+/// serve `router` on it until `shutdown`, then remove it.
+#[cfg(unix)]
+fn serve_unix(router: Router, path: PathBuf, shutdown: watch::Receiver<bool>) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    // Left behind by a crashed run with the same pid.
+    let _ = std::fs::remove_file(&path);
+    let listener = std::os::unix::net::UnixListener::bind(&path)?;
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+    listener.set_nonblocking(true)?;
+    tauri::async_runtime::spawn(async move {
+        if let Ok(listener) = tokio::net::UnixListener::from_std(listener) {
+            let _ = axum::serve(listener, router).with_graceful_shutdown(stopped(shutdown)).await;
+        }
+        let _ = std::fs::remove_file(&path);
+    });
+    Ok(())
+}
+
+/// Start the attention listener for `app`: on TCP everywhere, and on a Unix
+/// socket too where there are those. A transport that fails to bind is left
+/// out — the port is 0, the socket None — and sessions fall back to the other,
+/// or to the output-idle heuristic for their status.
+pub fn start_ide_listener(app: tauri::AppHandle, token: String) -> IdeListener {
+    let (shutdown, stop) = watch::channel(false);
+    let router = router(token, Arc::new(AppSink(app.clone())));
+    let port = serve_tcp(router.clone(), stop.clone()).unwrap_or(0);
+
+    #[cfg(unix)]
+    let socket = match socket_path().and_then(|path| serve_unix(router, path.clone(), stop).map(|()| path)) {
+        Ok(path) => Some(path),
+        Err(e) => {
+            if let Some(log_path) = app.try_state::<LogPath>().map(|p| p.0.lock().unwrap().clone()) {
+                write_log(&log_path, "WARN", &format!("Hook socket unavailable: {}", e));
+            }
+            None
+        }
+    };
+    #[cfg(not(unix))]
+    let socket = {
+        let _ = (router, stop);
+        None
+    };
+
+    IdeListener { port, socket, shutdown }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct TestSink {
+        events: Mutex<Vec<(String, SessionEvent)>>,
+        details: Mutex<Vec<Option<HookDetails>>>,
+        rejected: Mutex<Vec<String>>,
+    }
+
+    impl Sink for TestSink {
+        fn owns(&self, session: &str) -> bool {
+            session == "ours"
+        }
+        fn event(&self, session: &str, event: &SessionEvent, details: Option<&HookDetails>) {
+            self.events.lock().unwrap().push((session.to_string(), event.clone()));
+            self.details.lock().unwrap().push(details.cloned());
+        }
+        fn rejected(&self, _session: Option<&str>, reason: &str) {
+            self.rejected.lock().unwrap().push(reason.to_string());
+        }
+        fn sessions(&self) -> Vec<PtySessionInfo> {
+            Vec::new()
+        }
+    }
+
+    /// Send `head` (request line and headers), then `body` once the server
+    /// has answered an `Expect: 100-continue`, and return the final response.
+    fn request(port: u16, head: &str, body: &str) -> String {
+        let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        stream.set_read_timeout(Some(std::time::Duration::from_secs(5))).unwrap();
+        stream
+            .write_all(format!("{}Content-Length: {}\r\nConnection: close\r\n\r\n", head, body.len()).as_bytes())
+            .unwrap();
+        if head.contains("Expect: 100-continue") {
+            let mut buf = [0u8; 64];
+            let n = stream.read(&mut buf).unwrap();
+            assert!(buf[..n].starts_with(b"HTTP/1.1 100 Continue"));
+        }
+        stream.write_all(body.as_bytes()).unwrap();
+        let mut response = String::new();
+        let _ = stream.read_to_string(&mut response);
+        response
+    }
+
+    fn status(response: &str) -> &str {
+        response.split(' ').nth(1).unwrap_or("")
+    }
+
+    #[test]
+    fn serves_the_hook_wire_format() {
+        let sink = Arc::new(TestSink::default());
+        let (shutdown, stop) = watch::channel(false);
+        let port = serve_tcp(router("s3cret".into(), sink.clone()), stop).unwrap();
+        let ping = |token: &str, body: &str| {
+            request(
+                port,
+                &format!("POST /event HTTP/1.1\r\nHost: 127.0.0.1\r\n{}: {}\r\n", TOKEN_HEADER, token),
+                body,
+            )
+        };
+
+        // As PowerShell's Invoke-RestMethod sends it when Expect100Continue
+        // is left on: headers first, body after the 100.
+        let response = request(
+            port,
+            "POST /event HTTP/1.1\r\nHost: 127.0.0.1\r\nx-claude-launcher-token: s3cret\r\n\
+             Content-Type: application/json\r\nExpect: 100-continue\r\n",
+            r#"{"session":"ours","event":"stop"}"#,
+        );
+        assert_eq!(status(&response), "200", "{}", response);
+        assert_eq!(status(&ping("s3cret", r#"{"session":"ours","event":"notification"}"#)), "200");
+        assert_eq!(status(&ping("s3cret", r#"{"session":"ours","event":"pre-tool-use","tool":"mcp__git__status"}"#)), "200");
+        // A tool name that isn't one is dropped, not the event.
+        assert_eq!(status(&ping("s3cret", r#"{"session":"ours","event":"post-tool-use","tool":"<b>x</b>"}"#)), "200");
+        assert_eq!(status(&ping("s3cret", r#"{"session":"ours","event":"pre-compact"}"#)), "200");
+        // The tool name comes from the payload when the script sent none.
+        let with_payload = r#"{"session":"ours","event":"pre-tool-use","payload":
+            {"session_id":"9f1c","transcript_path":"/home/u/.claude/projects/p/9f1c.jsonl",
+             "hook_event_name":"PreToolUse","tool_name":"Bash","tool_input":{"command":"cargo test"}}}"#;
+        assert_eq!(status(&ping("s3cret", with_payload)), "200");
+        let events: Vec<SessionEvent> = sink.events.lock().unwrap().iter().map(|(_, e)| e.clone()).collect();
+        assert_eq!(
+            events,
+            [
+                SessionEvent::Stopped,
+                SessionEvent::Notification,
+                SessionEvent::ToolStarted { tool: Some("mcp__git__status".into()) },
+                SessionEvent::ToolFinished { tool: None },
+                SessionEvent::CompactionStarted,
+                SessionEvent::ToolStarted { tool: Some("Bash".into()) },
+            ]
+        );
+        let details = sink.details.lock().unwrap()[5].clone().unwrap();
+        assert_eq!(
+            serde_json::to_value(SessionEventPayload {
+                session_id: "ours",
+                at: "t".into(),
+                event: &events[5],
+                details: Some(&details),
+            })
+            .unwrap(),
+            serde_json::json!({
+                "sessionId": "ours", "at": "t", "kind": "toolStarted", "tool": "Bash",
+                // The transcript isn't under this machine's ~/.claude/projects.
+                "details": {
+                    "agentSessionId": "9f1c",
+                    "toolName": "Bash",
+                    "toolTarget": "cargo test",
+                },
+            })
+        );
+        assert!(sink.details.lock().unwrap()[..5].iter().all(Option::is_none));
+
+        assert_eq!(status(&ping("S3CRET", r#"{"session":"ours","event":"stop"}"#)), "401");
+        assert_eq!(status(&ping("s3cret", r#"{"session":"theirs","event":"stop"}"#)), "404");
+        assert_eq!(status(&ping("s3cret", r#"{"session":"ours","event":"bogus"}"#)), "400");
+        assert_eq!(status(&ping("s3cret", "not json")), "400");
+        assert_eq!(status(&ping("s3cret", &"x".repeat(MAX_BODY + 1))), "413");
+        assert_eq!(sink.events.lock().unwrap().len(), 6);
+        assert_eq!(
+            *sink.rejected.lock().unwrap(),
+            ["missing or wrong token", "not a session of this instance", "unknown event \"bogus\"", "body is not JSON"]
+        );
+
+        assert_eq!(status(&request(port, "GET /health HTTP/1.1\r\nHost: 127.0.0.1\r\n", "")), "200");
+        assert_eq!(status(&request(port, "GET /sessions HTTP/1.1\r\nHost: 127.0.0.1\r\n", "")), "401");
+        let listed = request(
+            port,
+            &format!("GET /sessions HTTP/1.1\r\nHost: 127.0.0.1\r\n{}: s3cret\r\n", TOKEN_HEADER),
+            "",
+        );
+        assert!(listed.starts_with("HTTP/1.1 200") && listed.ends_with("[]"), "{}", listed);
+        assert_eq!(status(&request(port, "GET /event HTTP/1.1\r\nHost: 127.0.0.1\r\n", "")), "405");
+
+        shutdown.send(true).unwrap();
+        let stopped = (0..50).any(|_| {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            TcpStream::connect(("127.0.0.1", port)).is_err()
+        });
+        assert!(stopped, "still accepting after shutdown");
+    }
+
+    /// A throwaway `~/.claude/projects` with one transcript in it, plus a
+    /// `.jsonl` file outside it.
+    fn fake_projects(name: &str) -> (PathBuf, PathBuf, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("claude-launcher-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let root = dir.join(".claude").join("projects");
+        std::fs::create_dir_all(root.join("p")).unwrap();
+        let transcript = root.join("p").join("0b7e2a4c.jsonl");
+        std::fs::write(&transcript, "{}\n").unwrap();
+        let outside = dir.join("x.jsonl");
+        std::fs::write(&outside, "{}\n").unwrap();
+        (root, transcript, outside)
+    }
+
+    #[test]
+    fn transcript_paths_stay_under_projects() {
+        let (root, transcript, outside) = fake_projects("transcripts");
+        assert!(is_transcript_path(&transcript.to_string_lossy(), &root));
+        assert!(!is_transcript_path("/tmp/x.jsonl", &root));
+        assert!(!is_transcript_path(&outside.to_string_lossy(), &root));
+        let escape = root.join("p").join("..").join("..").join("..").join("x.jsonl");
+        assert!(!is_transcript_path(&escape.to_string_lossy(), &root));
+        assert!(!is_transcript_path(&root.join("p").join("missing.jsonl").to_string_lossy(), &root));
+        assert!(!is_transcript_path("p/0b7e2a4c.jsonl", &root));
+        #[cfg(unix)]
+        {
+            let link = root.join("p").join("link.jsonl");
+            std::os::unix::fs::symlink(&outside, &link).unwrap();
+            assert!(!is_transcript_path(&link.to_string_lossy(), &root));
+        }
+        let _ = std::fs::remove_dir_all(root.parent().unwrap().parent().unwrap());
+    }
+
+    #[test]
+    fn hook_details_keep_a_checked_subset() {
+        let (root, transcript, outside) = fake_projects("hook-details");
+        let root = Some(root.as_path());
+        let transcript = transcript.to_string_lossy().to_string();
+        let notification = serde_json::json!({
+            "session_id": "0b7e2a4c-52d1-4f3e-9a57-2c0f3d1e8b61",
+            "transcript_path": transcript,
+            "cwd": "/home/u/p",
+            "hook_event_name": "Notification",
+            "message": "Claude needs your\npermission to use Bash\u{1b}[31m",
+        });
+        assert_eq!(
+            HookDetails::from_payload(&notification, root),
+            Some(HookDetails {
+                transcript_path: Some(transcript.clone()),
+                agent_session_id: Some("0b7e2a4c-52d1-4f3e-9a57-2c0f3d1e8b61".into()),
+                message: Some("Claude needs your permission to use Bash [31m".into()),
+                ..HookDetails::default()
+            })
+        );
+
+        // Wrong types, a path the UI must not open and an id that isn't one
+        // are all dropped; a long command is clipped.
+        let odd = serde_json::json!({
+            "session_id": "../../etc",
+            "transcript_path": "/etc/passwd",
+            "message": 42,
+            "tool_name": "Bash",
+            "tool_input": { "command": "x".repeat(1000) },
+        });
+        let details = HookDetails::from_payload(&odd, root).unwrap();
+        assert_eq!((details.transcript_path, details.agent_session_id, details.message), (None, None, None));
+        let target = details.tool_target.unwrap();
+        assert_eq!(target.chars().count(), MAX_TARGET + 1);
+        assert!(target.ends_with('…'));
+
+        assert_eq!(HookDetails::from_payload(&serde_json::json!({ "cwd": "/home/u/p" }), root), None);
+        assert_eq!(HookDetails::from_payload(&serde_json::json!("stop"), root), None);
+
+        // A `.jsonl` outside the projects directory, or any path when there is
+        // no home to check against, is dropped too.
+        let elsewhere = serde_json::json!({ "transcript_path": outside, "tool_name": "Bash" });
+        assert_eq!(HookDetails::from_payload(&elsewhere, root).unwrap().transcript_path, None);
+        assert_eq!(HookDetails::from_payload(&notification, None).unwrap().transcript_path, None);
+        let _ = std::fs::remove_dir_all(outside.parent().unwrap());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn serves_on_a_private_unix_socket() {
+        use std::os::unix::fs::PermissionsExt;
+        use std::os::unix::net::UnixStream;
+
+        let dir = std::env::temp_dir().join(format!("claude-launcher-socket-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("listener.sock");
+        let sink = Arc::new(TestSink::default());
+        let (shutdown, stop) = watch::channel(false);
+        serve_unix(router("s3cret".into(), sink.clone()), path.clone(), stop).unwrap();
+        assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+
+        let body = r#"{"session":"ours","event":"stop"}"#;
+        let mut stream = UnixStream::connect(&path).unwrap();
+        stream.set_read_timeout(Some(std::time::Duration::from_secs(5))).unwrap();
+        write!(
+            stream,
+            "POST /event HTTP/1.1\r\nHost: localhost\r\n{}: s3cret\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            TOKEN_HEADER,
+            body.len(),
+            body
+        )
+        .unwrap();
+        let mut response = String::new();
+        let _ = stream.read_to_string(&mut response);
+        assert_eq!(status(&response), "200", "{}", response);
+        assert_eq!(*sink.events.lock().unwrap(), [("ours".to_string(), SessionEvent::Stopped)]);
+
+        // Gone once the server stops.
+        shutdown.send(true).unwrap();
+        let removed = (0..50).any(|_| {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            !path.exists()
+        });
+        assert!(removed, "socket left behind after shutdown");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,367 @@
+//! The launch log on disk: appending, rotation and retention.
+//!
+//! Entries go to the active file (`claude-launcher.log`). Once it passes
+//! `MAX_SEGMENT_BYTES`, or its first entry is older than `MAX_SEGMENT_AGE`,
+//! it is renamed to a timestamped segment next to it
+//! (`claude-launcher-20261017-093015123.log`) and gzipped
+//! (`….log.gz`); a fresh active file starts with the next entry. Segments
+//! older than the retention period, and any beyond the newest
+//! `MAX_SEGMENTS`, are deleted at each rotation and at startup. The period
+//! the user picks is saved in the app data dir (`RETENTION_FILE`), so startup
+//! applies it before the frontend is up to send it again.
+//!
+//! Readers go through `segments` + `read_segment`, so a tail or a query that
+//! spans a rotation sees one continuous log.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, Local};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use tauri::Manager;
+
+use crate::{launch_log, logger, write_log, AppDataDir, LogPath};
+
+/// Rotate the active file once it reaches this size.
+pub(crate) const MAX_SEGMENT_BYTES: u64 = 5 * 1024 * 1024;
+/// Rotate the active file once its first entry is this old.
+pub(crate) const MAX_SEGMENT_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+/// Rotated segments kept, newest first, regardless of age.
+pub(crate) const MAX_SEGMENTS: usize = 10;
+/// Default for how long rotated segments are kept.
+pub(crate) const DEFAULT_RETENTION_DAYS: u32 = 14;
+/// Bounds for `set_log_retention`.
+const RETENTION_DAYS_RANGE: std::ops::RangeInclusive<u32> = 1..=365;
+/// The retention period last set, in days, under the app data dir.
+const RETENTION_FILE: &str = "log-retention-days";
+
+#[derive(Debug, Clone)]
+pub(crate) struct RotationPolicy {
+    pub max_bytes: u64,
+    pub max_age: Duration,
+    pub max_segments: usize,
+    pub retention: Duration,
+}
+
+impl Default for RotationPolicy {
+    fn default() -> Self {
+        RotationPolicy {
+            max_bytes: MAX_SEGMENT_BYTES,
+            max_age: MAX_SEGMENT_AGE,
+            max_segments: MAX_SEGMENTS,
+            retention: days(DEFAULT_RETENTION_DAYS),
+        }
+    }
+}
+
+fn days(n: u32) -> Duration {
+    Duration::from_secs(u64::from(n) * 24 * 60 * 60)
+}
+
+/// The retention period saved by `set_log_retention`, or the default when
+/// none was saved or the file doesn't hold a valid one.
+pub(crate) fn saved_retention(app_data: &Path) -> Duration {
+    let saved = fs::read_to_string(app_data.join(RETENTION_FILE))
+        .ok()
+        .and_then(|s| s.trim().parse::<u32>().ok())
+        .filter(|d| RETENTION_DAYS_RANGE.contains(d));
+    days(saved.unwrap_or(DEFAULT_RETENTION_DAYS))
+}
+
+fn save_retention(app_data: &Path, days: u32) -> io::Result<()> {
+    fs::create_dir_all(app_data)?;
+    fs::write(app_data.join(RETENTION_FILE), days.to_string())
+}
+
+/// Appends to the active file and rotates it when the policy says so. Owned
+/// by the logger thread (see logger.rs), so two writers never rotate the same
+/// file at once or interleave a line with a rename.
+pub(crate) struct LogWriter {
+    policy: RotationPolicy,
+    active: Option<ActiveFile>,
+}
+
+/// The active file, held open between batches.
+struct ActiveFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    /// When its first entry was written.
+    started: SystemTime,
+}
+
+impl LogWriter {
+    pub(crate) fn new(policy: RotationPolicy) -> Self {
+        LogWriter { policy, active: None }
+    }
+
+    /// Append already-formatted lines (each with its newline) to `log_path`,
+    /// rotating first if the active file is due. Returns whether they were
+    /// written.
+    pub(crate) fn append(&mut self, log_path: &Path, text: &str) -> bool {
+        let now = SystemTime::now();
+        if self.active.as_ref().is_some_and(|a| a.path != log_path) {
+            self.active = None;
+        }
+        if self.active.is_none() {
+            self.active = open_active(log_path, now);
+        }
+        let Some(active) = &self.active else {
+            return false;
+        };
+        let too_big = active.size + text.len() as u64 > self.policy.max_bytes;
+        let too_old = now.duration_since(active.started).is_ok_and(|age| age >= self.policy.max_age);
+        if active.size > 0 && (too_big || too_old) {
+            // Closed first: Windows won't rename a file that is open.
+            self.active = None;
+            if rotate(log_path, now).is_ok() {
+                enforce_retention(log_path, &self.policy, now);
+            }
+            self.active = open_active(log_path, now);
+        }
+        let Some(active) = &mut self.active else {
+            return false;
+        };
+        if active.file.write_all(text.as_bytes()).is_err() {
+            // Reopen on the next append rather than keep a bad handle.
+            self.active = None;
+            return false;
+        }
+        active.size += text.len() as u64;
+        true
+    }
+
+    pub(crate) fn policy(&self) -> &RotationPolicy {
+        &self.policy
+    }
+
+    pub(crate) fn set_retention(&mut self, retention: Duration) {
+        self.policy.retention = retention;
+    }
+}
+
+fn open_active(log_path: &Path, now: SystemTime) -> Option<ActiveFile> {
+    if let Some(parent) = log_path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let file = OpenOptions::new().create(true).append(true).open(log_path).ok()?;
+    let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let started = if size > 0 { first_entry_time(log_path).unwrap_or(now) } else { now };
+    Some(ActiveFile { path: log_path.to_path_buf(), file, size, started })
+}
+
+/// The first entry's timestamp, for an active file left by an earlier run.
+fn first_entry_time(log_path: &Path) -> Option<SystemTime> {
+    let mut first = String::new();
+    BufReader::new(File::open(log_path).ok()?).read_line(&mut first).ok()?;
+    let entry = launch_log::parse_line(first.trim_end())?;
+    let t = DateTime::parse_from_rfc3339(&entry.timestamp).ok()?;
+    Some(t.into())
+}
+
+/// `claude-launcher-` for `…/claude-launcher.log`: what every segment of that
+/// log is named with.
+fn segment_prefix(log_path: &Path) -> String {
+    let stem = log_path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    format!("{}-", stem)
+}
+
+/// The rotated segments of `log_path`, oldest first. The active file is not
+/// included.
+pub(crate) fn segments(log_path: &Path) -> Vec<PathBuf> {
+    let Some(dir) = log_path.parent() else {
+        return Vec::new();
+    };
+    let prefix = segment_prefix(log_path);
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut found: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name().map(|n| n.to_string_lossy()).is_some_and(|n| {
+                n.starts_with(&prefix) && (n.ends_with(".log") || n.ends_with(".log.gz"))
+            })
+        })
+        .collect();
+    // The timestamp in the name sorts chronologically.
+    found.sort();
+    found
+}
+
+/// Every file of the log, oldest first: the rotated segments, then the active
+/// file if it exists.
+pub(crate) fn all_files(log_path: &Path) -> Vec<PathBuf> {
+    let mut files = segments(log_path);
+    if log_path.exists() {
+        files.push(log_path.to_path_buf());
+    }
+    files
+}
+
+/// A segment's (or the active file's) text, decompressing if gzipped.
+pub(crate) fn read_segment(path: &Path) -> io::Result<String> {
+    let mut text = String::new();
+    if path.extension().is_some_and(|e| e == "gz") {
+        GzDecoder::new(File::open(path)?).read_to_string(&mut text)?;
+    } else {
+        File::open(path)?.read_to_string(&mut text)?;
+    }
+    Ok(text)
+}
+
+/// Move the active file aside as a new segment and compress it. A failed
+/// compression leaves the plain segment, which readers handle too.
+fn rotate(log_path: &Path, now: SystemTime) -> io::Result<PathBuf> {
+    let dir = log_path.parent().unwrap_or(Path::new("."));
+    let stamp = DateTime::<Local>::from(now).format("%Y%m%d-%H%M%S%3f");
+    let prefix = segment_prefix(log_path);
+    let mut segment = dir.join(format!("{}{}.log", prefix, stamp));
+    let mut n = 1;
+    while segment.exists() || segment.with_extension("log.gz").exists() {
+        // `_` sorts after `.`, keeping same-millisecond segments in order.
+        segment = dir.join(format!("{}{}_{:03}.log", prefix, stamp, n));
+        n += 1;
+    }
+    fs::rename(log_path, &segment)?;
+    Ok(compress(&segment).unwrap_or(segment))
+}
+
+fn compress(path: &Path) -> io::Result<PathBuf> {
+    let gz = path.with_extension("log.gz");
+    let mut encoder = GzEncoder::new(File::create(&gz)?, Compression::default());
+    io::copy(&mut File::open(path)?, &mut encoder)?;
+    encoder.finish()?;
+    fs::remove_file(path)?;
+    Ok(gz)
+}
+
+/// Delete segments last written more than `policy.retention` ago, and any
+/// beyond the newest `policy.max_segments`. Returns how many were deleted.
+pub(crate) fn enforce_retention(log_path: &Path, policy: &RotationPolicy, now: SystemTime) -> usize {
+    let segments = segments(log_path);
+    let excess = segments.len().saturating_sub(policy.max_segments);
+    segments
+        .iter()
+        .enumerate()
+        .filter(|(i, path)| {
+            *i < excess
+                || fs::metadata(path)
+                    .and_then(|m| m.modified())
+                    .ok()
+                    .and_then(|t| now.duration_since(t).ok())
+                    .is_some_and(|age| age > policy.retention)
+        })
+        .filter(|(_, path)| fs::remove_file(path).is_ok())
+        .count()
+}
+
+/// Keep rotated log segments for `days` days (1–365), and apply that now.
+#[tauri::command]
+pub async fn set_log_retention(app: tauri::AppHandle, days: u32) -> Result<(), String> {
+    if !RETENTION_DAYS_RANGE.contains(&days) {
+        return Err(format!("Log retention must be 1 to 365 days, not {}", days));
+    }
+    let log_path = app.state::<LogPath>().0.lock().unwrap().clone();
+    let app_data = app.state::<AppDataDir>().0.clone();
+    let (saved, removed) = tauri::async_runtime::spawn_blocking({
+        let log_path = log_path.clone();
+        move || (save_retention(&app_data, days), logger::retain(&log_path, Some(self::days(days))))
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    if let Err(e) = saved {
+        write_log(&log_path, "WARN", &format!("Could not save the log retention period: {}", e));
+    }
+    write_log(
+        &log_path,
+        "INFO",
+        &format!("Log retention set to {} day(s); removed {} old segment(s)", days, removed),
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("claude-launcher-logfile-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn tiny_policy() -> RotationPolicy {
+        RotationPolicy { max_bytes: 64, ..RotationPolicy::default() }
+    }
+
+    #[test]
+    fn rotates_by_size_into_gzipped_segments() {
+        let dir = scratch("size");
+        let log = dir.join("claude-launcher.log");
+        let mut w = LogWriter::new(tiny_policy());
+        for i in 0..6 {
+            w.append(&log, &format!("line {:02} ............................\n", i));
+        }
+        let segs = segments(&log);
+        assert!(segs.len() >= 2, "expected rotation, got {:?}", segs);
+        assert!(segs.iter().all(|p| p.to_string_lossy().ends_with(".log.gz")));
+
+        // Reading every file in order gives back every line, in order.
+        let all: String = all_files(&log).iter().map(|p| read_segment(p).unwrap()).collect();
+        let lines: Vec<&str> = all.lines().collect();
+        assert_eq!(lines.len(), 6);
+        assert!(lines[0].starts_with("line 00") && lines[5].starts_with("line 05"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn rotates_by_age() {
+        let dir = scratch("age");
+        let log = dir.join("claude-launcher.log");
+        let mut w = LogWriter::new(RotationPolicy { max_age: Duration::ZERO, ..RotationPolicy::default() });
+        w.append(&log, "first\n");
+        w.append(&log, "second\n");
+        assert_eq!(segments(&log).len(), 1);
+        assert_eq!(read_segment(&log).unwrap(), "second\n");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn retention_period_survives_a_restart() {
+        let dir = scratch("saved-retention");
+        assert_eq!(saved_retention(&dir), days(DEFAULT_RETENTION_DAYS));
+        save_retention(&dir, 90).unwrap();
+        assert_eq!(saved_retention(&dir), days(90));
+        // Out of range or garbled: back to the default.
+        fs::write(dir.join(RETENTION_FILE), "0").unwrap();
+        assert_eq!(saved_retention(&dir), days(DEFAULT_RETENTION_DAYS));
+        fs::write(dir.join(RETENTION_FILE), "ninety").unwrap();
+        assert_eq!(saved_retention(&dir), days(DEFAULT_RETENTION_DAYS));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn retention_drops_old_and_excess_segments() {
+        let dir = scratch("retention");
+        let log = dir.join("claude-launcher.log");
+        fs::create_dir_all(&dir).unwrap();
+        for name in ["claude-launcher-1.log.gz", "claude-launcher-2.log.gz", "claude-launcher-3.log", "other.log"] {
+            fs::write(dir.join(name), "").unwrap();
+        }
+        let keep_two = RotationPolicy { max_segments: 2, ..RotationPolicy::default() };
+        assert_eq!(enforce_retention(&log, &keep_two, SystemTime::now()), 1);
+        assert!(!dir.join("claude-launcher-1.log.gz").exists());
+
+        let later = SystemTime::now() + Duration::from_secs(3600);
+        let one_minute = RotationPolicy { retention: Duration::from_secs(60), ..RotationPolicy::default() };
+        assert_eq!(enforce_retention(&log, &one_minute, later), 2);
+        assert!(dir.join("other.log").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,281 @@
+//! The logger thread. Every log entry, from async commands and from the PTY
+//! reader/wait threads and hook listener alike, is sent here over a channel
+//! rather than written by the caller, so logging never blocks on the disk and
+//! the file is opened once instead of per entry.
+//!
+//! The thread drains whatever is queued (up to `MAX_BATCH` messages) and
+//! writes it as one batch through a `LogWriter` (see log_file.rs), which also
+//! does rotation. `flush` waits for everything queued before it to be written;
+//! `run` calls it on app exit so nothing logged during shutdown is lost.
+//!
+//! Once a batch is written its entries go to every live subscriber (see
+//! `subscribe_log`), so a viewer can tail the log without re-reading it. An
+//! entry whose write failed is not passed on: a viewer shows what the file
+//! holds.
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::LazyLock;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::launch_log::LogEntry;
+use crate::log_file::{enforce_retention, LogWriter, RotationPolicy};
+
+/// Most messages written as one batch.
+const MAX_BATCH: usize = 256;
+
+/// Receives each entry after it is written. Returns false once the other end
+/// has gone, which ends the subscription.
+pub(crate) type Sink = Box<dyn FnMut(&LogEntry) -> bool + Send>;
+
+struct Subscriber {
+    id: u64,
+    /// Least severe level passed on; None passes everything.
+    min_level: Option<u8>,
+    sink: Sink,
+}
+
+enum Message {
+    /// One entry, already redacted.
+    Append { path: PathBuf, entry: LogEntry },
+    Subscribe(Subscriber),
+    Unsubscribe(u64),
+    /// Apply (and optionally change) the retention policy to `path`'s
+    /// segments; replies with how many were deleted.
+    Retain { path: PathBuf, retention: Option<Duration>, reply: Sender<usize> },
+    /// Replies once everything queued before it is written.
+    Flush(Sender<()>),
+}
+
+static LOGGER: LazyLock<Sender<Message>> = LazyLock::new(|| {
+    let (tx, rx) = mpsc::channel();
+    thread::Builder::new()
+        .name("logger".to_string())
+        .spawn(move || run(rx))
+        .expect("failed to start logger thread");
+    tx
+});
+
+fn run(rx: Receiver<Message>) {
+    let mut writer = LogWriter::new(RotationPolicy::default());
+    let mut subscribers: Vec<Subscriber> = Vec::new();
+    while let Ok(first) = rx.recv() {
+        let mut batch = vec![first];
+        while batch.len() < MAX_BATCH {
+            match rx.try_recv() {
+                Ok(m) => batch.push(m),
+                Err(_) => break,
+            }
+        }
+        handle_batch(&mut writer, &mut subscribers, batch);
+    }
+}
+
+/// Appends to one file, joined into a single write, and their entries.
+struct Pending {
+    path: PathBuf,
+    text: String,
+    entries: Vec<LogEntry>,
+}
+
+/// Consecutive appends to the same file are joined into one write; anything
+/// else is handled in order after the appends queued before it.
+fn handle_batch(writer: &mut LogWriter, subscribers: &mut Vec<Subscriber>, batch: Vec<Message>) {
+    let mut pending: Option<Pending> = None;
+    let mut written: Vec<LogEntry> = Vec::new();
+    for message in batch {
+        match message {
+            Message::Append { path, entry } => {
+                let Ok(mut line) = serde_json::to_string(&entry) else {
+                    continue;
+                };
+                line.push('\n');
+                match &mut pending {
+                    Some(p) if p.path == path => {
+                        p.text.push_str(&line);
+                        p.entries.push(entry);
+                    }
+                    _ => {
+                        write_pending(writer, pending.take(), &mut written);
+                        pending = Some(Pending { path, text: line, entries: vec![entry] });
+                    }
+                }
+            }
+            Message::Subscribe(subscriber) => subscribers.push(subscriber),
+            Message::Unsubscribe(id) => subscribers.retain(|s| s.id != id),
+            Message::Retain { path, retention, reply } => {
+                write_pending(writer, pending.take(), &mut written);
+                if let Some(r) = retention {
+                    writer.set_retention(r);
+                }
+                let _ = reply.send(enforce_retention(&path, writer.policy(), SystemTime::now()));
+            }
+            Message::Flush(reply) => {
+                write_pending(writer, pending.take(), &mut written);
+                let _ = reply.send(());
+            }
+        }
+    }
+    write_pending(writer, pending, &mut written);
+
+    for entry in &written {
+        let rank = level_rank(&entry.level);
+        subscribers.retain_mut(|s| s.min_level.is_some_and(|min| rank < min) || (s.sink)(entry));
+    }
+}
+
+/// Write `pending`, adding its entries to `written` if that worked.
+fn write_pending(writer: &mut LogWriter, pending: Option<Pending>, written: &mut Vec<LogEntry>) {
+    if let Some(p) = pending {
+        if writer.append(&p.path, &p.text) {
+            written.extend(p.entries);
+        }
+    }
+}
+
+/// Severity order for `min_level` filtering. Unknown levels rank with INFO.
+pub(crate) fn level_rank(level: &str) -> u8 {
+    match level.to_ascii_uppercase().as_str() {
+        "DEBUG" => 0,
+        "WARN" => 2,
+        "ERROR" => 3,
+        _ => 1,
+    }
+}
+
+/// Queue `entry` for `log_path`.
+pub(crate) fn submit(log_path: &Path, entry: LogEntry) {
+    let _ = LOGGER.send(Message::Append { path: log_path.to_path_buf(), entry });
+}
+
+/// Pass every entry written from now on, at `min_level` or above if given, to
+/// `sink`. Returns the id to unsubscribe with.
+pub(crate) fn subscribe(min_level: Option<&str>, sink: Sink) -> u64 {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    let subscriber = Subscriber { id, min_level: min_level.map(level_rank), sink };
+    let _ = LOGGER.send(Message::Subscribe(subscriber));
+    id
+}
+
+pub(crate) fn unsubscribe(id: u64) {
+    let _ = LOGGER.send(Message::Unsubscribe(id));
+}
+
+/// Block until everything queued so far is on disk.
+pub(crate) fn flush() {
+    let (tx, rx) = mpsc::channel();
+    if LOGGER.send(Message::Flush(tx)).is_ok() {
+        let _ = rx.recv();
+    }
+}
+
+/// Delete `log_path`'s segments the retention policy no longer keeps, first
+/// setting the retention period if given. Blocks until done; returns how many
+/// were deleted.
+pub(crate) fn retain(log_path: &Path, retention: Option<Duration>) -> usize {
+    let (tx, rx) = mpsc::channel();
+    let message = Message::Retain { path: log_path.to_path_buf(), retention, reply: tx };
+    if LOGGER.send(message).is_err() {
+        return 0;
+    }
+    rx.recv().unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::launch_log::EventKind;
+    use std::sync::{Arc, Mutex};
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("claude-launcher-logger-test-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir.join("claude-launcher.log")
+    }
+
+    /// Entries sent from many threads all land, whole lines each, by the time
+    /// `flush` returns.
+    #[test]
+    fn concurrent_submits_are_written_by_flush() {
+        let log = scratch("concurrent");
+        let handles: Vec<_> = (0..4)
+            .map(|t| {
+                let log = log.clone();
+                thread::spawn(move || {
+                    for i in 0..50 {
+                        submit(&log, LogEntry::new("INFO", EventKind::Message, format!("thread {} line {}", t, i)));
+                    }
+                })
+            })
+            .collect();
+        for h in handles {
+            h.join().unwrap();
+        }
+        flush();
+
+        let written = std::fs::read_to_string(&log).unwrap();
+        assert_eq!(written.lines().count(), 200);
+        assert!(written.lines().all(|l| l.contains(r#""message":"thread "#)));
+        let _ = std::fs::remove_dir_all(log.parent().unwrap());
+    }
+
+    #[test]
+    fn subscribers_get_entries_at_their_level() {
+        let log = scratch("subscribe");
+        let seen: Arc<Mutex<Vec<String>>> = Arc::default();
+        let sink_seen = seen.clone();
+        let id = subscribe(
+            Some("warn"),
+            Box::new(move |e| {
+                sink_seen.lock().unwrap().push(e.message.clone());
+                true
+            }),
+        );
+        submit(&log, LogEntry::new("INFO", EventKind::Message, "quiet"));
+        submit(&log, LogEntry::new("ERROR", EventKind::Launch, "loud"));
+        flush();
+        unsubscribe(id);
+        submit(&log, LogEntry::new("ERROR", EventKind::Message, "after"));
+        flush();
+
+        // Other tests log concurrently, so look only for this test's messages.
+        let seen = seen.lock().unwrap();
+        assert!(seen.contains(&"loud".to_string()));
+        assert!(!seen.contains(&"quiet".to_string()));
+        assert!(!seen.contains(&"after".to_string()));
+        let _ = std::fs::remove_dir_all(log.parent().unwrap());
+    }
+
+    /// Only entries that reached the file are passed on.
+    #[test]
+    fn subscribers_skip_entries_that_were_not_written() {
+        let log = scratch("unwritten");
+        std::fs::create_dir_all(log.parent().unwrap()).unwrap();
+        // A directory where the file should be: every write fails.
+        let blocked = log.parent().unwrap().join("blocked.log");
+        std::fs::create_dir_all(&blocked).unwrap();
+
+        let seen: Arc<Mutex<Vec<String>>> = Arc::default();
+        let sink_seen = seen.clone();
+        let sink: Sink = Box::new(move |e| {
+            sink_seen.lock().unwrap().push(e.message.clone());
+            true
+        });
+        let append = |path: &Path, message: &str| Message::Append {
+            path: path.to_path_buf(),
+            entry: LogEntry::new("INFO", EventKind::Message, message),
+        };
+        let mut writer = LogWriter::new(RotationPolicy::default());
+        let mut subscribers = vec![Subscriber { id: 1, min_level: None, sink }];
+        handle_batch(
+            &mut writer,
+            &mut subscribers,
+            vec![append(&blocked, "lost"), append(&log, "kept"), append(&blocked, "lost too")],
+        );
+        assert_eq!(*seen.lock().unwrap(), ["kept"]);
+        let _ = std::fs::remove_dir_all(log.parent().unwrap());
+    }
+}
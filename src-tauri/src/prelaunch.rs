@@ -0,0 +1,144 @@
+//! Pre-launch scripts for the wt path (see `terminal::WindowsTerminal`), and
+//! the env files multiplexer windows source their secrets from (see
+//! `tmux::window_command`), which are handled the same way.
+//!
+//! Every launch writes its own script, named by a fresh launch id, so two
+//! projects launched in quick succession can never run each other's commands.
+//! Scripts live in a private directory, are created exclusively (never
+//! following or reusing an existing file), and are removed by the janitor:
+//!
+//! - the script deletes itself as its first statement — pwsh has read the
+//!   whole file by then, so the session has started;
+//! - a failed attempt removes its script straight away;
+//! - anything a session never got to (wt accepted the request but no tab
+//!   opened) is removed once it is older than `SCRIPT_TTL`, by a timer per
+//!   launch and by a sweep at startup for leftovers from an earlier run.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// How long an unstarted script may live before the janitor removes it.
+pub(crate) const SCRIPT_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// First line of every script: remove the file it was run from.
+pub(crate) const SELF_DELETE: &str =
+    "Remove-Item -LiteralPath $PSCommandPath -Force -ErrorAction SilentlyContinue";
+
+/// The directory pre-launch scripts are written to. Under the per-user temp
+/// dir, whose ACL already keeps other users out on Windows; on Unix the
+/// directory itself is made 0700 when first written.
+pub(crate) fn script_dir() -> PathBuf {
+    std::env::temp_dir().join("claude-launcher-prelaunch")
+}
+
+/// The script path for one launch.
+pub(crate) fn script_path(launch_id: &str) -> PathBuf {
+    script_dir().join(format!("{}.ps1", launch_id))
+}
+
+/// The env file for the launch whose pre-launch script is `script`.
+pub(crate) fn env_file_path(script: &Path) -> PathBuf {
+    script.with_extension("env")
+}
+
+/// Create `path` with `contents`, readable only by the current user. Fails if
+/// the file already exists rather than writing through it.
+pub(crate) fn write_script(path: &Path, contents: &str) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(dir, fs::Permissions::from_mode(0o700))?;
+        }
+    }
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(contents.as_bytes())
+}
+
+/// Remove `path` once `SCRIPT_TTL` has passed, if the session has not already.
+pub(crate) fn schedule_removal(path: PathBuf) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(SCRIPT_TTL).await;
+        let _ = fs::remove_file(&path);
+    });
+}
+
+/// Remove every script in `dir` last modified more than `ttl` ago. Returns how
+/// many were removed.
+pub(crate) fn sweep(dir: &Path, ttl: Duration) -> usize {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    let now = SystemTime::now();
+    entries
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "ps1" || ext == "env"))
+        .filter(|e| {
+            e.metadata()
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|t| now.duration_since(t).ok())
+                .is_some_and(|age| age > ttl)
+        })
+        .filter(|e| fs::remove_file(e.path()).is_ok())
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("claude-launcher-prelaunch-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn scripts_are_private_and_exclusive() {
+        let dir = scratch("write");
+        let path = dir.join("a.ps1");
+        write_script(&path, "echo hi").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "echo hi");
+        // A second write to the same name must not clobber the first.
+        assert!(write_script(&path, "evil").is_err());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+            assert_eq!(fs::metadata(&dir).unwrap().permissions().mode() & 0o777, 0o700);
+        }
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn sweep_removes_only_expired_scripts() {
+        let dir = scratch("sweep");
+        write_script(&dir.join("a.ps1"), "").unwrap();
+        write_script(&dir.join("a.env"), "").unwrap();
+        write_script(&dir.join("keep.txt"), "").unwrap();
+        assert_eq!(sweep(&dir, SCRIPT_TTL), 0);
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(sweep(&dir, Duration::from_millis(1)), 2);
+        assert!(!dir.join("a.ps1").exists());
+        assert!(!dir.join("a.env").exists());
+        assert!(dir.join("keep.txt").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn each_launch_gets_its_own_path() {
+        assert_ne!(script_path("one"), script_path("two"));
+        assert_eq!(script_path("one").parent().unwrap(), script_dir());
+    }
+}
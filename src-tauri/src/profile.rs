@@ -0,0 +1,223 @@
+//! Launch profiles: named bundles of flags, env vars, pre-launch steps and a
+//! renderer choice, applied on top of a `LaunchRequest` that names one in
+//! `LaunchRequest.launch_profile`. The set is defined here rather than in the
+//! frontend so that a profile is validated by the same code that runs it, and
+//! means the same thing whichever launch surface (`launch_agent`,
+//! `spawn_pty`) it is selected in.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::error::LaunchError;
+use crate::{is_safe_env_name, is_safe_env_value, is_safe_flag, LaunchRequest};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchProfile {
+    pub name: String,
+    pub description: String,
+    /// Appended to the request's flags (skipping any it already has).
+    pub flags: Vec<String>,
+    /// Set in the agent's environment.
+    pub env: BTreeMap<String, String>,
+    /// Run, in order, before the request's own pre-launch command. One
+    /// command per step.
+    pub pre_launch: Vec<String>,
+    /// Overrides `LaunchRequest.ide_renderer` when set.
+    pub renderer: Option<String>,
+    /// The flags and env are Claude Code's, so the profile is rejected for
+    /// any other agent rather than handing it options it doesn't understand.
+    pub claude_only: bool,
+}
+
+fn strings(v: &[&str]) -> Vec<String> {
+    v.iter().map(|s| s.to_string()).collect()
+}
+
+/// The profiles a request can name.
+pub(crate) fn builtin_profiles() -> Vec<LaunchProfile> {
+    vec![
+        LaunchProfile {
+            name: "review".to_string(),
+            description: "Read-only plan mode, with the scrollback renderer so the whole review stays scrollable"
+                .to_string(),
+            flags: strings(&["--permission-mode=plan"]),
+            env: BTreeMap::new(),
+            pre_launch: Vec::new(),
+            renderer: Some("classic".to_string()),
+            claude_only: true,
+        },
+        LaunchProfile {
+            name: "yolo".to_string(),
+            description: "Skip every permission prompt".to_string(),
+            flags: strings(&["--dangerously-skip-permissions"]),
+            env: BTreeMap::new(),
+            pre_launch: Vec::new(),
+            renderer: None,
+            claude_only: true,
+        },
+        LaunchProfile {
+            name: "offline".to_string(),
+            description: "No telemetry, error reporting, auto-update or other non-essential traffic".to_string(),
+            flags: Vec::new(),
+            env: [
+                "CLAUDE_CODE_DISABLE_NONESSENTIAL_TRAFFIC",
+                "DISABLE_AUTOUPDATER",
+                "DISABLE_ERROR_REPORTING",
+                "DISABLE_TELEMETRY",
+            ]
+            .into_iter()
+            .map(|k| (k.to_string(), "1".to_string()))
+            .collect(),
+            pre_launch: Vec::new(),
+            renderer: None,
+            claude_only: true,
+        },
+    ]
+}
+
+/// Everything in a profile must pass the checks a request's own values do:
+/// flags as flags, env names and values as env, and steps that stay one line
+/// each (they are joined by newlines).
+fn validate_profile(profile: &LaunchProfile) -> Result<(), LaunchError> {
+    let reject = |reason: String| Err(LaunchError::validation("launchProfile", reason));
+    for flag in &profile.flags {
+        if !is_safe_flag(flag) {
+            return reject(format!("Profile {} has an invalid flag: {}", profile.name, flag));
+        }
+    }
+    for (key, value) in &profile.env {
+        if !is_safe_env_name(key) {
+            return reject(format!("Profile {} has an invalid env var name: {}", profile.name, key));
+        }
+        if !is_safe_env_value(value) {
+            return reject(format!("Profile {} has an invalid value for env var {}", profile.name, key));
+        }
+    }
+    for step in &profile.pre_launch {
+        if step.is_empty() || step.contains(['\n', '\r']) {
+            return reject(format!("Profile {} has an invalid pre-launch step", profile.name));
+        }
+    }
+    if let Some(renderer) = profile.renderer.as_deref() {
+        if renderer != "classic" && renderer != "fullscreen" {
+            return reject(format!("Profile {} has an unknown renderer: {}", profile.name, renderer));
+        }
+    }
+    Ok(())
+}
+
+/// The request with its named profile, if any, folded in. Validation of the
+/// result is the caller's: profile values then go through the same checks as
+/// the rest of the request.
+pub(crate) fn apply_profile(request: &LaunchRequest) -> Result<LaunchRequest, LaunchError> {
+    let name = match request.launch_profile.as_deref() {
+        None | Some("") => return Ok(request.clone()),
+        Some(name) => name,
+    };
+    let profile = builtin_profiles()
+        .into_iter()
+        .find(|p| p.name == name)
+        .ok_or_else(|| LaunchError::validation("launchProfile", format!("Unknown launch profile rejected: {}", name)))?;
+    if profile.claude_only && !request.claude_features {
+        return Err(LaunchError::validation(
+            "launchProfile",
+            format!("Launch profile {} is only available for Claude Code", name),
+        ));
+    }
+    validate_profile(&profile)?;
+
+    let mut out = request.clone();
+    for flag in profile.flags {
+        if !out.flags.contains(&flag) {
+            out.flags.push(flag);
+        }
+    }
+    // The request's own env wins over the profile's.
+    for (key, value) in profile.env {
+        out.env.entry(key).or_insert(value);
+    }
+    let mut steps = profile.pre_launch;
+    if let Some(own) = request.pre_launch_command.as_deref().filter(|c| !c.is_empty()) {
+        steps.push(own.to_string());
+    }
+    if !steps.is_empty() {
+        out.pre_launch_command = Some(steps.join("\n"));
+    }
+    if profile.renderer.is_some() {
+        out.ide_renderer = profile.renderer;
+    }
+    Ok(out)
+}
+
+/// The built-in launch profiles, for the frontend to offer.
+#[tauri::command]
+pub async fn list_launch_profiles() -> Vec<LaunchProfile> {
+    builtin_profiles()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(profile: &str) -> LaunchRequest {
+        LaunchRequest {
+            agent_path: "/usr/bin/claude".to_string(),
+            project_path: "/srv/proj".to_string(),
+            flags: vec!["--verbose".to_string()],
+            claude_features: true,
+            pre_launch_command: Some("nvm use 20".to_string()),
+            launch_profile: Some(profile.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn builtin_profiles_validate() {
+        for p in builtin_profiles() {
+            assert!(validate_profile(&p).is_ok(), "{} failed validation", p.name);
+        }
+    }
+
+    #[test]
+    fn profile_folds_into_request() {
+        let review = apply_profile(&request("review")).unwrap();
+        assert_eq!(review.flags, ["--verbose", "--permission-mode=plan"]);
+        assert_eq!(review.ide_renderer.as_deref(), Some("classic"));
+        assert_eq!(review.pre_launch_command.as_deref(), Some("nvm use 20"));
+
+        let offline = apply_profile(&request("offline")).unwrap();
+        assert_eq!(offline.flags, ["--verbose"]);
+        assert_eq!(offline.env.get("DISABLE_TELEMETRY").map(String::as_str), Some("1"));
+
+        let none = apply_profile(&request("")).unwrap();
+        assert_eq!(none.flags, ["--verbose"]);
+        assert!(none.env.is_empty());
+    }
+
+    #[test]
+    fn unknown_or_foreign_profile_rejected() {
+        assert!(matches!(
+            apply_profile(&request("turbo")),
+            Err(LaunchError::Validation { field: "launchProfile", .. })
+        ));
+        let codex = LaunchRequest { claude_features: false, ..request("yolo") };
+        assert!(apply_profile(&codex).is_err());
+    }
+
+    #[test]
+    fn unsafe_profile_values_rejected() {
+        let mut p = builtin_profiles().remove(0);
+        p.env.insert("BAD NAME".to_string(), "1".to_string());
+        assert!(validate_profile(&p).is_err());
+
+        let mut p = builtin_profiles().remove(0);
+        p.env.insert("OK".to_string(), "$(id)".to_string());
+        assert!(validate_profile(&p).is_err());
+
+        let mut p = builtin_profiles().remove(0);
+        p.pre_launch.push("a\nb".to_string());
+        assert!(validate_profile(&p).is_err());
+    }
+}
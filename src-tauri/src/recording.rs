@@ -0,0 +1,331 @@
+//! IDE session recording in asciicast v2 (asciinema's format), so what an
+//! agent did can be reviewed or shared after the fact.
+//!
+//! Recording is opt-in per spawn (`spawn_pty`'s `recording`). The session's
+//! `Recorder` sits next to its scrollback in `PtyOutput`, so the reader thread
+//! tees every output chunk into it, `resize_pty` adds resize events and, when
+//! asked for, `write_pty` adds what was typed. Each event is written as its own
+//! line as it happens: a recording cut short by a crash is still a valid cast
+//! up to that point.
+//!
+//! Casts live in the app data dir's `recordings/`, one `<session id>.cast` per
+//! session. `list_recordings`, `export_recording` and `delete_recording` are
+//! the frontend's view of them.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use chrono::{Local, SecondsFormat, TimeZone};
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+use crate::ide::PtySessions;
+use crate::is_safe_path;
+
+/// Managed state: where casts are written.
+pub struct RecordingDir(pub PathBuf);
+
+/// What to record, from the frontend's settings.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RecordingOptions {
+    /// Also record what is typed into the session. Off by default: that
+    /// includes anything typed at a password prompt.
+    pub input: bool,
+}
+
+/// The first line of a cast.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CastHeader {
+    pub version: u32,
+    pub width: u16,
+    pub height: u16,
+    /// Unix seconds at the start of the recording.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+}
+
+/// One event line: seconds since the start, the event code ("o" output,
+/// "i" input, "r" resize as "COLSxROWS") and its data.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CastEvent(pub f64, pub String, pub String);
+
+/// Writes one session's cast.
+pub(crate) struct Recorder {
+    file: File,
+    started: Instant,
+    input: bool,
+    /// Output bytes that end mid UTF-8 sequence, held for the next chunk:
+    /// cast data is text, and a PTY read can split a character.
+    pending: Vec<u8>,
+}
+
+impl Recorder {
+    /// Create the cast at `path` and write its header. The cast holds
+    /// everything the session showed (and, with `input`, what was typed), so
+    /// it is readable only by the current user.
+    pub(crate) fn create(
+        path: &Path,
+        cols: u16,
+        rows: u16,
+        title: &str,
+        options: &RecordingOptions,
+    ) -> io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut open = fs::OpenOptions::new();
+        open.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            open.mode(0o600);
+        }
+        let mut file = open.open(path)?;
+        let header = CastHeader {
+            version: 2,
+            width: cols,
+            height: rows,
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH).ok().map(|d| d.as_secs() as i64),
+            title: Some(title.to_string()),
+            env: BTreeMap::from([("TERM".to_string(), "xterm-256color".to_string())]),
+        };
+        let mut line = serde_json::to_string(&header).map_err(io::Error::other)?;
+        line.push('\n');
+        file.write_all(line.as_bytes())?;
+        Ok(Recorder { file, started: Instant::now(), input: options.input, pending: Vec::new() })
+    }
+
+    pub(crate) fn output(&mut self, bytes: &[u8]) {
+        self.pending.extend_from_slice(bytes);
+        let text = take_utf8(&mut self.pending);
+        if !text.is_empty() {
+            self.event("o", &text);
+        }
+    }
+
+    /// Recorded only when the options asked for input.
+    pub(crate) fn input(&mut self, data: &str) {
+        if self.input {
+            self.event("i", data);
+        }
+    }
+
+    pub(crate) fn resize(&mut self, cols: u16, rows: u16) {
+        self.event("r", &format!("{}x{}", cols, rows));
+    }
+
+    /// Best-effort: a failed write loses the event, never the session.
+    fn event(&mut self, code: &str, data: &str) {
+        let event = CastEvent(self.started.elapsed().as_secs_f64(), code.to_string(), data.to_string());
+        if let Ok(mut line) = serde_json::to_string(&event) {
+            line.push('\n');
+            let _ = self.file.write_all(line.as_bytes());
+        }
+    }
+}
+
+/// Decode as much of `bytes` as is complete UTF-8, leaving a trailing partial
+/// character in place. Invalid sequences become U+FFFD.
+fn take_utf8(bytes: &mut Vec<u8>) -> String {
+    let mut out = String::new();
+    let mut rest: &[u8] = bytes;
+    loop {
+        match std::str::from_utf8(rest) {
+            Ok(s) => {
+                out.push_str(s);
+                rest = &[];
+                break;
+            }
+            Err(e) => {
+                let (valid, after) = rest.split_at(e.valid_up_to());
+                out.push_str(std::str::from_utf8(valid).unwrap_or_default());
+                match e.error_len() {
+                    Some(n) => {
+                        out.push('\u{FFFD}');
+                        rest = &after[n..];
+                    }
+                    None => {
+                        rest = after;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    *bytes = rest.to_vec();
+    out
+}
+
+/// A cast read back: its header and every event line that parses.
+pub(crate) struct Cast {
+    pub header: CastHeader,
+    pub events: Vec<CastEvent>,
+}
+
+pub(crate) fn parse_cast(text: &str) -> Result<Cast, String> {
+    let mut lines = text.lines();
+    let header: CastHeader = lines
+        .next()
+        .and_then(|l| serde_json::from_str(l).ok())
+        .ok_or_else(|| "Not an asciicast file".to_string())?;
+    if header.version != 2 {
+        return Err(format!("Unsupported asciicast version {}", header.version));
+    }
+    // A cast cut short by a crash may end in a torn line.
+    let events = lines.filter_map(|l| serde_json::from_str(l).ok()).collect();
+    Ok(Cast { header, events })
+}
+
+/// Recording ids are session ids: UUIDs from the frontend.
+fn is_safe_recording_id(id: &str) -> bool {
+    !id.is_empty() && id.len() <= 64 && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// The cast for `id` in `dir`, once `id` is known not to leave it.
+pub(crate) fn cast_path(dir: &Path, id: &str) -> Result<PathBuf, String> {
+    if !is_safe_recording_id(id) {
+        return Err("Invalid recording id".into());
+    }
+    Ok(dir.join(format!("{}.cast", id)))
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingInfo {
+    /// The session id.
+    pub id: String,
+    pub title: Option<String>,
+    /// RFC 3339, local offset.
+    pub started_at: Option<String>,
+    /// Seconds from the start to the last event.
+    pub duration: f64,
+    pub width: u16,
+    pub height: u16,
+    pub size_bytes: u64,
+    /// The session is still running and writing to it.
+    pub live: bool,
+}
+
+fn recording_info(path: &Path, live: &dyn Fn(&str) -> bool) -> Option<RecordingInfo> {
+    let id = path.file_stem()?.to_str()?.to_string();
+    let text = fs::read_to_string(path).ok()?;
+    let cast = parse_cast(&text).ok()?;
+    let started_at = cast
+        .header
+        .timestamp
+        .and_then(|t| Local.timestamp_opt(t, 0).single())
+        .map(|t| t.to_rfc3339_opts(SecondsFormat::Secs, false));
+    Some(RecordingInfo {
+        live: live(&id),
+        id,
+        title: cast.header.title,
+        started_at,
+        duration: cast.events.last().map_or(0.0, |e| e.0),
+        width: cast.header.width,
+        height: cast.header.height,
+        size_bytes: text.len() as u64,
+    })
+}
+
+/// Every cast in `dir`, newest first.
+fn list_in(dir: &Path, live: &dyn Fn(&str) -> bool) -> Vec<RecordingInfo> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut recordings: Vec<RecordingInfo> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|x| x == "cast"))
+        .filter_map(|p| recording_info(&p, live))
+        .collect();
+    recordings.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+    recordings
+}
+
+fn is_live(app: &tauri::AppHandle, id: &str) -> bool {
+    app.try_state::<PtySessions>().is_some_and(|s| s.is_recording(id))
+}
+
+#[tauri::command]
+pub async fn list_recordings(app: tauri::AppHandle) -> Vec<RecordingInfo> {
+    let dir = app.state::<RecordingDir>().0.clone();
+    tauri::async_runtime::spawn_blocking(move || list_in(&dir, &|id| is_live(&app, id)))
+        .await
+        .unwrap_or_default()
+}
+
+/// Copy a recording to `destination` (a path the user picked in a save
+/// dialog). A live recording is copied as far as it has got.
+#[tauri::command]
+pub async fn export_recording(app: tauri::AppHandle, id: String, destination: String) -> Result<(), String> {
+    if !is_safe_path(&destination) {
+        return Err("Invalid path".into());
+    }
+    let source = cast_path(&app.state::<RecordingDir>().0, &id)?;
+    fs::copy(&source, &destination)
+        .map(|_| ())
+        .map_err(|e| format!("Failed to export recording: {}", e))
+}
+
+#[tauri::command]
+pub async fn delete_recording(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    let path = cast_path(&app.state::<RecordingDir>().0, &id)?;
+    if is_live(&app, &id) {
+        return Err("The session is still recording".into());
+    }
+    fs::remove_file(&path).map_err(|e| format!("Failed to delete recording: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_a_valid_cast() {
+        let dir = std::env::temp_dir().join(format!("claude-launcher-recording-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = cast_path(&dir, "0f8c2a4e-1b2c-4d5e-8f90-123456789abc").unwrap();
+
+        let mut rec = Recorder::create(&path, 80, 24, "demo", &RecordingOptions::default()).unwrap();
+        // "é" split across two reads is written whole, in the second event.
+        rec.output(b"caf\xc3");
+        rec.output(b"\xa9\r\n");
+        rec.input("secret\r");
+        rec.resize(100, 30);
+        drop(rec);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+        let cast = parse_cast(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!((cast.header.width, cast.header.height), (80, 24));
+        assert_eq!(cast.header.title.as_deref(), Some("demo"));
+        let events: Vec<(&str, &str)> = cast.events.iter().map(|e| (e.1.as_str(), e.2.as_str())).collect();
+        // Input was not asked for, so it is not there.
+        assert_eq!(events, [("o", "caf"), ("o", "é\r\n"), ("r", "100x30")]);
+        assert!(cast.events.windows(2).all(|w| w[0].0 <= w[1].0));
+
+        let listed = list_in(&dir, &|_| false);
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, "0f8c2a4e-1b2c-4d5e-8f90-123456789abc");
+        assert!(cast_path(&dir, "../../etc/passwd").is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn invalid_utf8_is_replaced() {
+        let mut bytes = b"a\xffb\xe2\x82".to_vec();
+        assert_eq!(take_utf8(&mut bytes), "a\u{FFFD}b");
+        assert_eq!(bytes, b"\xe2\x82");
+    }
+}
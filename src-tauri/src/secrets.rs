@@ -0,0 +1,456 @@
+//! Per-project secrets (API keys and the like). Values live in the OS keyring
+//! — Credential Manager on Windows, Keychain on macOS, the Secret Service on
+//! Linux — or, where no keyring is reachable (a headless Linux box, a test),
+//! in an encrypted file in the app data dir. Projects reference entries by
+//! name through `LaunchRequest.secret_env`; the values are read only at spawn
+//! time and set only in the spawned agent's env. They are never logged, never
+//! part of `LaunchResult.command`, and there is no command that returns one.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use tauri::Manager;
+
+use crate::error::LaunchError;
+use crate::{write_log, LaunchRequest, LogPath};
+
+/// Keyring service every entry is stored under.
+const KEYRING_SERVICE: &str = "claude-launcher";
+
+/// Validate a secret name: ASCII letters, digits, `-`, `_` and `.`, up to 64.
+pub(crate) fn is_safe_secret_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+}
+
+/// Where secret values are kept.
+pub(crate) trait SecretBackend: Send + Sync {
+    /// Short name for the log ("keyring", "encrypted file").
+    fn name(&self) -> &'static str;
+    fn get(&self, name: &str) -> Result<Option<String>, String>;
+    fn set(&self, name: &str, value: &str) -> Result<(), String>;
+    fn delete(&self, name: &str) -> Result<(), String>;
+}
+
+/// The platform keyring, through the `keyring` crate.
+pub(crate) struct KeyringBackend;
+
+impl KeyringBackend {
+    fn entry(name: &str) -> Result<keyring::Entry, String> {
+        keyring::Entry::new(KEYRING_SERVICE, name).map_err(|e| e.to_string())
+    }
+
+    /// Whether a keyring is reachable at all. A lookup of an entry that does
+    /// not exist succeeds with NoEntry when one is.
+    fn available() -> bool {
+        match Self::entry("__probe__") {
+            Ok(e) => matches!(e.get_password(), Ok(_) | Err(keyring::Error::NoEntry)),
+            Err(_) => false,
+        }
+    }
+}
+
+impl SecretBackend for KeyringBackend {
+    fn name(&self) -> &'static str {
+        "keyring"
+    }
+
+    fn get(&self, name: &str) -> Result<Option<String>, String> {
+        match Self::entry(name)?.get_password() {
+            Ok(v) => Ok(Some(v)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    fn set(&self, name: &str, value: &str) -> Result<(), String> {
+        Self::entry(name)?.set_password(value).map_err(|e| e.to_string())
+    }
+
+    fn delete(&self, name: &str) -> Result<(), String> {
+        match Self::entry(name)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
+
+/// The fallback: a JSON map of name → ChaCha20-Poly1305 ciphertext, with the
+/// key in a separate file beside it. Both are created owner-only (0600 on
+/// Unix; the per-user app data dir on Windows). This keeps values out of
+/// backups, sync folders and casual `cat`s of the data file — not away from
+/// someone who can already read the user's files, which is what the keyring
+/// is for.
+pub(crate) struct EncryptedFileBackend {
+    path: PathBuf,
+    key_path: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl EncryptedFileBackend {
+    pub(crate) fn new(dir: &Path) -> Self {
+        EncryptedFileBackend {
+            path: dir.join("secrets.json"),
+            key_path: dir.join("secrets.key"),
+            lock: Mutex::new(()),
+        }
+    }
+
+    fn cipher(&self) -> Result<ChaCha20Poly1305, String> {
+        let key = match fs::read(&self.key_path) {
+            Ok(bytes) if bytes.len() == 32 => bytes,
+            Ok(_) => return Err("Secret key file is corrupt".to_string()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+                write_private(&self.key_path, key.as_slice())?;
+                key.to_vec()
+            }
+            Err(e) => return Err(format!("Failed to read secret key: {}", e)),
+        };
+        Ok(ChaCha20Poly1305::new(Key::from_slice(&key)))
+    }
+
+    fn load(&self) -> Result<BTreeMap<String, String>, String> {
+        match fs::read_to_string(&self.path) {
+            Ok(s) => serde_json::from_str(&s).map_err(|e| format!("Secret file is corrupt: {}", e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+            Err(e) => Err(format!("Failed to read secret file: {}", e)),
+        }
+    }
+
+    fn save(&self, entries: &BTreeMap<String, String>) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(entries).map_err(|e| e.to_string())?;
+        write_private(&self.path, json.as_bytes())
+    }
+}
+
+impl SecretBackend for EncryptedFileBackend {
+    fn name(&self) -> &'static str {
+        "encrypted file"
+    }
+
+    fn get(&self, name: &str) -> Result<Option<String>, String> {
+        let _guard = self.lock.lock().unwrap();
+        let Some(sealed) = self.load()?.remove(name) else {
+            return Ok(None);
+        };
+        let bytes = from_hex(&sealed).filter(|b| b.len() > 12).ok_or("Secret entry is corrupt")?;
+        let (nonce, ciphertext) = bytes.split_at(12);
+        // The name is bound as associated data, so an entry copied under
+        // another name fails to open rather than leaking into the wrong var.
+        let plain = self
+            .cipher()?
+            .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: name.as_bytes() })
+            .map_err(|_| format!("Secret {} could not be decrypted", name))?;
+        String::from_utf8(plain).map(Some).map_err(|e| e.to_string())
+    }
+
+    fn set(&self, name: &str, value: &str) -> Result<(), String> {
+        let _guard = self.lock.lock().unwrap();
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher()?
+            .encrypt(&nonce, Payload { msg: value.as_bytes(), aad: name.as_bytes() })
+            .map_err(|e| e.to_string())?;
+        let mut sealed = nonce.to_vec();
+        sealed.extend(ciphertext);
+        let mut entries = self.load()?;
+        entries.insert(name.to_string(), to_hex(&sealed));
+        self.save(&entries)
+    }
+
+    fn delete(&self, name: &str) -> Result<(), String> {
+        let _guard = self.lock.lock().unwrap();
+        let mut entries = self.load()?;
+        if entries.remove(name).is_some() {
+            self.save(&entries)?;
+        }
+        Ok(())
+    }
+}
+
+/// Write `bytes` to `path`, readable only by the current user.
+fn write_private(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(path)
+        .and_then(|mut f| f.write_all(bytes))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Managed state: the backend in use, chosen on first use (probing the
+/// keyring can block on D-Bus, so not at startup), plus the index of names.
+/// Keyrings cannot be listed by service, so the names — never the values —
+/// are kept in `secret-names.json`.
+pub struct SecretStore {
+    dir: PathBuf,
+    backend: OnceLock<Box<dyn SecretBackend>>,
+}
+
+impl SecretStore {
+    pub fn new(dir: PathBuf) -> Self {
+        SecretStore { dir, backend: OnceLock::new() }
+    }
+
+    /// A store over an already chosen backend.
+    #[cfg(test)]
+    pub(crate) fn with_backend(dir: PathBuf, backend: Box<dyn SecretBackend>) -> Self {
+        let store = SecretStore::new(dir);
+        let _ = store.backend.set(backend);
+        store
+    }
+
+    /// Which backend holds the values ("keyring" or "encrypted file").
+    pub(crate) fn backend_name(&self) -> &'static str {
+        self.backend().name()
+    }
+
+    fn backend(&self) -> &dyn SecretBackend {
+        self.backend
+            .get_or_init(|| {
+                if KeyringBackend::available() {
+                    Box::new(KeyringBackend)
+                } else {
+                    Box::new(EncryptedFileBackend::new(&self.dir))
+                }
+            })
+            .as_ref()
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.dir.join("secret-names.json")
+    }
+
+    pub(crate) fn names(&self) -> BTreeSet<String> {
+        fs::read_to_string(self.index_path())
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    fn save_names(&self, names: &BTreeSet<String>) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(names).map_err(|e| e.to_string())?;
+        write_private(&self.index_path(), json.as_bytes())
+    }
+
+    pub(crate) fn get(&self, name: &str) -> Result<Option<String>, String> {
+        self.backend().get(name)
+    }
+
+    pub(crate) fn set(&self, name: &str, value: &str) -> Result<(), String> {
+        self.backend().set(name, value)?;
+        let mut names = self.names();
+        if names.insert(name.to_string()) {
+            self.save_names(&names)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn delete(&self, name: &str) -> Result<(), String> {
+        self.backend().delete(name)?;
+        let mut names = self.names();
+        if names.remove(name) {
+            self.save_names(&names)?;
+        }
+        Ok(())
+    }
+}
+
+/// The request's `secret_env` (env var → secret name) resolved to values.
+/// Every referenced secret must exist.
+pub(crate) fn resolve_secret_env(
+    store: &SecretStore,
+    request: &LaunchRequest,
+) -> Result<BTreeMap<String, String>, LaunchError> {
+    let mut env = BTreeMap::new();
+    for (var, secret) in &request.secret_env {
+        match store.get(secret) {
+            Ok(Some(value)) => {
+                env.insert(var.clone(), value);
+            }
+            Ok(None) => return Err(LaunchError::MissingSecret { name: secret.clone() }),
+            Err(e) => return Err(LaunchError::io("Secret store", e)),
+        }
+    }
+    Ok(env)
+}
+
+/// The request with its secrets added to `env`, ready to spawn. Must come
+/// after anything that logs `request.env`: from here on it holds the values.
+/// Blocking — a keyring lookup may wait on the OS, or on the user unlocking
+/// it.
+pub(crate) fn with_secrets(
+    store: &SecretStore,
+    log_path: &Path,
+    request: &LaunchRequest,
+) -> Result<LaunchRequest, LaunchError> {
+    if request.secret_env.is_empty() {
+        return Ok(request.clone());
+    }
+    let names: Vec<String> = request
+        .secret_env
+        .iter()
+        .map(|(var, secret)| format!("{} (from {})", var, secret))
+        .collect();
+    write_log(log_path, "INFO", &format!("Agent secrets: {}", names.join(", ")));
+
+    let mut out = request.clone();
+    out.env.extend(resolve_secret_env(store, request)?);
+    Ok(out)
+}
+
+/// `with_secrets` off the async runtime's worker threads.
+pub(crate) async fn inject_secrets(
+    app: &tauri::AppHandle,
+    log_path: &Path,
+    request: &LaunchRequest,
+) -> Result<LaunchRequest, LaunchError> {
+    if request.secret_env.is_empty() {
+        return Ok(request.clone());
+    }
+    let app = app.clone();
+    let log_path = log_path.to_path_buf();
+    let request = request.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        with_secrets(&app.state::<SecretStore>(), &log_path, &request)
+    })
+    .await
+    .map_err(|e| LaunchError::io("Secret store", e))?
+}
+
+/// Names of the stored secrets, for the frontend to offer. Never values.
+#[tauri::command]
+pub async fn list_secrets(app: tauri::AppHandle) -> Vec<String> {
+    app.state::<SecretStore>().names().into_iter().collect()
+}
+
+#[tauri::command]
+pub async fn set_secret(app: tauri::AppHandle, name: String, value: String) -> Result<(), String> {
+    if !is_safe_secret_name(&name) {
+        return Err(format!("Invalid secret name rejected: {}", name));
+    }
+    let log_path = app.state::<LogPath>().0.lock().unwrap().clone();
+    let handle = app.clone();
+    let key = name.clone();
+    let backend = tauri::async_runtime::spawn_blocking(move || {
+        let store = handle.state::<SecretStore>();
+        store.set(&key, &value).map(|()| store.backend_name())
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    write_log(&log_path, "INFO", &format!("Stored secret {} in {}", name, backend));
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn delete_secret(app: tauri::AppHandle, name: String) -> Result<(), String> {
+    if !is_safe_secret_name(&name) {
+        return Err(format!("Invalid secret name rejected: {}", name));
+    }
+    let log_path = app.state::<LogPath>().0.lock().unwrap().clone();
+    let handle = app.clone();
+    let key = name.clone();
+    tauri::async_runtime::spawn_blocking(move || handle.state::<SecretStore>().delete(&key))
+        .await
+        .map_err(|e| e.to_string())??;
+    write_log(&log_path, "INFO", &format!("Deleted secret: {}", name));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("claude-launcher-secrets-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn encrypted_file_round_trip() {
+        let dir = scratch("roundtrip");
+        let backend = EncryptedFileBackend::new(&dir);
+        backend.set("work", "sk-ant-secret-value").unwrap();
+        assert_eq!(backend.get("work").unwrap().as_deref(), Some("sk-ant-secret-value"));
+        assert_eq!(backend.get("missing").unwrap(), None);
+
+        // Nothing on disk holds the plaintext.
+        let on_disk = fs::read_to_string(dir.join("secrets.json")).unwrap();
+        assert!(!on_disk.contains("sk-ant-secret-value"));
+
+        // An entry moved under another name does not decrypt.
+        let mut entries: BTreeMap<String, String> = serde_json::from_str(&on_disk).unwrap();
+        let sealed = entries.remove("work").unwrap();
+        entries.insert("other".to_string(), sealed);
+        fs::write(dir.join("secrets.json"), serde_json::to_string(&entries).unwrap()).unwrap();
+        assert!(backend.get("other").is_err());
+
+        backend.delete("other").unwrap();
+        assert_eq!(backend.get("other").unwrap(), None);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn secret_env_resolves_from_store() {
+        let dir = scratch("resolve");
+        let store = SecretStore::with_backend(dir.clone(), Box::new(EncryptedFileBackend::new(&dir)));
+        store.set("work-key", "abc").unwrap();
+        assert_eq!(store.names().into_iter().collect::<Vec<_>>(), ["work-key"]);
+
+        let mut request = LaunchRequest::default();
+        request.secret_env.insert("ANTHROPIC_API_KEY".to_string(), "work-key".to_string());
+        let env = resolve_secret_env(&store, &request).unwrap();
+        assert_eq!(env.get("ANTHROPIC_API_KEY").map(String::as_str), Some("abc"));
+
+        request.secret_env.insert("OPENAI_API_KEY".to_string(), "nope".to_string());
+        assert!(matches!(
+            resolve_secret_env(&store, &request),
+            Err(LaunchError::MissingSecret { .. })
+        ));
+
+        store.delete("work-key").unwrap();
+        assert!(store.names().is_empty());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn secret_names() {
+        assert!(is_safe_secret_name("anthropic.work-2"));
+        assert!(!is_safe_secret_name(""));
+        assert!(!is_safe_secret_name("a b"));
+        assert!(!is_safe_secret_name("../x"));
+    }
+}
@@ -0,0 +1,734 @@
+//! Launcher-Mode terminal backends. Each backend turns a validated
+//! `LaunchRequest` into a pure `LaunchPlan` — program, argv, env changes and
+//! any script to write first — without touching the system, so every plan can
+//! be tested on any host. `launch_with_backends` is the thin executor that
+//! spawns the plans in order until one starts.
+//!
+//! Windows tries Windows Terminal, then plain pwsh. Elsewhere the agent runs
+//! under a POSIX shell inside the first common Linux terminal emulator that is
+//! installed, tried in a configurable order (`LaunchRequest.terminal_order`).
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+
+use serde::Serialize;
+
+use crate::error::{LaunchAttempt, LaunchError};
+use crate::prelaunch;
+use crate::{
+    build_agent_pwsh_cmd, build_agent_sh_cmd, is_safe_color, is_safe_title, write_log,
+    LaunchRequest, LaunchResult, FULL_REPAINT_ENV,
+};
+
+/// A script a plan needs on disk before its program runs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PlannedScript {
+    pub path: PathBuf,
+    pub contents: String,
+}
+
+/// Everything needed to spawn one launch attempt.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LaunchPlan {
+    pub program: String,
+    pub args: Vec<String>,
+    /// Set on the child, overriding anything inherited.
+    pub env: BTreeMap<String, String>,
+    /// Stripped from the inherited environment.
+    pub env_remove: Vec<String>,
+    /// Working directory for the spawn, for programs that cannot take one as
+    /// an argument. None inherits ours.
+    pub cwd: Option<String>,
+    pub script: Option<PlannedScript>,
+}
+
+impl LaunchPlan {
+    pub(crate) fn new(program: impl Into<String>) -> Self {
+        LaunchPlan {
+            program: program.into(),
+            args: Vec::new(),
+            env: BTreeMap::new(),
+            env_remove: Vec::new(),
+            cwd: None,
+            script: None,
+        }
+    }
+
+    /// The command line as logged and reported in `LaunchResult.command`.
+    pub(crate) fn display(&self) -> String {
+        if self.args.is_empty() {
+            self.program.clone()
+        } else {
+            format!("{} {}", self.program, self.args.join(" "))
+        }
+    }
+}
+
+/// Inputs a plan needs that are not part of the request. Resolved by the
+/// caller so that planning itself stays free of I/O.
+pub(crate) struct PlanContext {
+    /// The Codex notify `--config` argument, when the request asked for the
+    /// hook and its script has been installed.
+    pub notify_arg: Option<String>,
+    /// Where this launch's pre-launch script is written; unique per launch
+    /// (see `prelaunch::script_path`).
+    pub prelaunch_script: PathBuf,
+}
+
+/// Turns a validated request into a spawnable plan.
+pub(crate) trait TerminalBackend {
+    /// Short name used in log lines ("Launch successful via <name>").
+    fn name(&self) -> &'static str;
+    fn plan(&self, request: &LaunchRequest, ctx: &PlanContext) -> LaunchPlan;
+}
+
+/// The Claude Code env tweaks shared by the wt and pwsh plans: prevent the
+/// nested-session detection, and set the full-repaint fix directly on the
+/// child as belt-and-suspenders for the persisted HKCU var (see
+/// FULL_REPAINT_ENV).
+fn apply_windows_claude_env(plan: &mut LaunchPlan, request: &LaunchRequest) {
+    if request.claude_features {
+        plan.env_remove.push("CLAUDECODE".to_string());
+        plan.env.insert(FULL_REPAINT_ENV.to_string(), "1".to_string());
+    }
+}
+
+/// `wt new-tab`, the primary Windows path.
+pub(crate) struct WindowsTerminal;
+
+impl TerminalBackend for WindowsTerminal {
+    fn name(&self) -> &'static str {
+        "wt"
+    }
+
+    // Without pre-launch: wt new-tab --profile "PowerShell" -d "path" -- claude --flags
+    // With pre-launch:    wt new-tab --profile "PowerShell" -d "path" -- pwsh -NoExit -File <script>
+    fn plan(&self, request: &LaunchRequest, ctx: &PlanContext) -> LaunchPlan {
+        let mut plan = LaunchPlan::new("wt");
+        let args = &mut plan.args;
+        args.push("new-tab".to_string());
+        args.push("--profile".to_string());
+        args.push(request.terminal_profile.clone());
+        args.push("-d".to_string());
+        args.push(request.project_path.clone());
+
+        // Color the terminal tab per-project. This is a wt new-tab option, so it
+        // must come before the `--` command separator.
+        if let Some(color) = &request.tab_color {
+            if is_safe_color(color) {
+                args.push("--tabColor".to_string());
+                args.push(color.clone());
+            }
+        }
+
+        // Title the terminal tab per-project. --suppressApplicationTitle keeps it
+        // fixed; without it Claude Code's own OSC title updates (and the installed
+        // statusLine's "<name> — <model>" title) replace it. Both `dynamic_title`
+        // and `model_in_title` need the title left un-suppressed.
+        let live_title = request.dynamic_title.unwrap_or(false)
+            || request.model_in_title.unwrap_or(false);
+        if let Some(title) = &request.tab_title {
+            if is_safe_title(title) {
+                args.push("--title".to_string());
+                args.push(title.clone());
+                if !live_title {
+                    args.push("--suppressApplicationTitle".to_string());
+                }
+            }
+        }
+
+        args.push("--".to_string());
+
+        let pre_cmd = request.pre_launch_command.as_deref().filter(|c| !c.is_empty());
+        // A wt tab does not inherit our env when an existing wt window services
+        // the request, so extra env needs the script too. That includes
+        // secrets: like a multiplexer window's env file, the script is private
+        // to the user and deletes itself once pwsh has read it.
+        if pre_cmd.is_some() || !request.env.is_empty() {
+            // Both commands go in a temp PowerShell script to avoid wt
+            // semicolon parsing issues. wt treats ';' as a subcommand
+            // delimiter (opening separate tabs) even within quoted
+            // arguments, and \; escaping is unreliable when args are passed
+            // through Rust's Command API.
+            args.push("pwsh".to_string());
+            args.push("-NoExit".to_string());
+            args.push("-ExecutionPolicy".to_string());
+            args.push("Bypass".to_string());
+            args.push("-File".to_string());
+            args.push(ctx.prelaunch_script.to_string_lossy().to_string());
+            let mut lines = vec![prelaunch::SELF_DELETE.to_string()];
+            for (key, value) in &request.env {
+                lines.push(format!("$env:{} = '{}'", key, value.replace('\'', "''")));
+            }
+            lines.extend(pre_cmd.map(str::to_string));
+            lines.push(build_agent_pwsh_cmd(request));
+            plan.script = Some(PlannedScript {
+                path: ctx.prelaunch_script.clone(),
+                contents: lines.join("\n"),
+            });
+        } else {
+            args.push(request.agent_path.clone());
+            if let Some(sub) = request.subcommand.as_deref() {
+                args.push(sub.to_string());
+            }
+            args.extend(request.flags.iter().cloned());
+            // Turn-completion callback. In a wt tab there's no session id,
+            // so the script chimes and skips the status POST.
+            if let Some(arg) = &ctx.notify_arg {
+                args.push(arg.clone());
+            }
+        }
+
+        // When this spawn starts a fresh wt.exe (no existing window services
+        // the request), the new wt — and the claude tab under it — inherit our
+        // env directly, so the repaint fix applies even before the persisted
+        // HKCU var has propagated to a new shell session.
+        apply_windows_claude_env(&mut plan, request);
+        plan
+    }
+}
+
+/// Plain `pwsh`, the Windows fallback when wt is missing or fails.
+pub(crate) struct Pwsh;
+
+impl TerminalBackend for Pwsh {
+    fn name(&self) -> &'static str {
+        "pwsh"
+    }
+
+    // Launch the agent as a direct process via pwsh, passing the executable
+    // and flags as separate arguments to avoid shell interpretation. Using
+    // -NoExit keeps the terminal open; -Command with & (call operator) and
+    // individually quoted args prevents injection.
+    fn plan(&self, request: &LaunchRequest, _ctx: &PlanContext) -> LaunchPlan {
+        let agent_cmd = build_agent_pwsh_cmd(request);
+        let agent_cmd = match &request.pre_launch_command {
+            Some(pre_cmd) if !pre_cmd.is_empty() => format!("{}; {}", pre_cmd, agent_cmd),
+            _ => agent_cmd,
+        };
+        // No --suppressApplicationTitle equivalent here, so the agent may still
+        // retitle the window later; set the initial title at least.
+        let agent_cmd = match &request.tab_title {
+            Some(title) if is_safe_title(title) => format!(
+                "$Host.UI.RawUI.WindowTitle = '{}'; {}",
+                title.replace('\'', "''"),
+                agent_cmd
+            ),
+            _ => agent_cmd,
+        };
+
+        let mut plan = LaunchPlan::new("pwsh");
+        plan.args = vec![
+            "-NoExit".to_string(),
+            "-WorkingDirectory".to_string(),
+            request.project_path.clone(),
+            "-Command".to_string(),
+            agent_cmd,
+        ];
+        plan.env.extend(request.env.clone());
+        apply_windows_claude_env(&mut plan, request);
+        plan
+    }
+}
+
+/// The agent under a POSIX shell, in the project directory: the agent itself,
+/// or — with a pre-launch command — `sh -c` running it followed by an `exec`
+/// of the agent. Unlike wt there is no `;` splitting to dodge, so no temp
+/// script is needed. Has no window of its own; the Linux terminals wrap it.
+pub(crate) struct PosixShell;
+
+impl TerminalBackend for PosixShell {
+    fn name(&self) -> &'static str {
+        "sh"
+    }
+
+    fn plan(&self, request: &LaunchRequest, ctx: &PlanContext) -> LaunchPlan {
+        let mut plan = match request.pre_launch_command.as_deref() {
+            Some(pre) if !pre.is_empty() => {
+                let mut p = LaunchPlan::new("sh");
+                p.args = vec![
+                    "-c".to_string(),
+                    format!("{}\n{}", pre, build_agent_sh_cmd(request)),
+                ];
+                p
+            }
+            _ => {
+                let mut p = LaunchPlan::new(request.agent_path.clone());
+                if let Some(sub) = request.subcommand.as_deref() {
+                    p.args.push(sub.to_string());
+                }
+                p.args.extend(request.flags.iter().cloned());
+                if let Some(arg) = &ctx.notify_arg {
+                    p.args.push(arg.clone());
+                }
+                p
+            }
+        };
+        plan.cwd = Some(request.project_path.clone());
+        plan.env.extend(request.env.clone());
+        if request.claude_features {
+            // Prevent Claude's nested-session detection, as on the wt path.
+            // FULL_REPAINT_ENV is deliberately not set: the bug it fixes is
+            // Windows Terminal's own.
+            plan.env_remove.push("CLAUDECODE".to_string());
+        }
+        plan
+    }
+}
+
+/// A terminal emulator the non-Windows launch path knows how to drive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LinuxTerminal {
+    GnomeTerminal,
+    Konsole,
+    Kitty,
+    Wezterm,
+    Xterm,
+}
+
+/// Tried in this order when the request does not name its own. Roughly "most
+/// likely to be the desktop's own terminal" first, with xterm as the fallback
+/// that is installed almost everywhere X is.
+pub(crate) const DEFAULT_LINUX_TERMINALS: &[LinuxTerminal] = &[
+    LinuxTerminal::GnomeTerminal,
+    LinuxTerminal::Konsole,
+    LinuxTerminal::Kitty,
+    LinuxTerminal::Wezterm,
+    LinuxTerminal::Xterm,
+];
+
+impl LinuxTerminal {
+    /// Parse a name from `LaunchRequest.terminal_order`. The vocabulary is
+    /// closed — the name becomes the program we spawn, so nothing else passes.
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "gnome-terminal" => Some(Self::GnomeTerminal),
+            "konsole" => Some(Self::Konsole),
+            "kitty" => Some(Self::Kitty),
+            "wezterm" => Some(Self::Wezterm),
+            "xterm" => Some(Self::Xterm),
+            _ => None,
+        }
+    }
+
+    /// Executable name, looked up on PATH at spawn time.
+    pub(crate) fn program(self) -> &'static str {
+        match self {
+            Self::GnomeTerminal => "gnome-terminal",
+            Self::Konsole => "konsole",
+            Self::Kitty => "kitty",
+            Self::Wezterm => "wezterm",
+            Self::Xterm => "xterm",
+        }
+    }
+
+    /// The emulator's own options (excluding the program itself) that open a
+    /// tab in `request.project_path`, titled and colored where the emulator
+    /// supports it, ending in its "end of options" marker so the command that
+    /// follows can never be read as a terminal option. Title and color are
+    /// re-validated exactly as the wt path does, so a bad value is dropped
+    /// rather than passed through.
+    fn options(self, request: &LaunchRequest) -> Vec<String> {
+        let title = request.tab_title.as_deref().filter(|t| is_safe_title(t));
+        let color = request.tab_color.as_deref().filter(|c| is_safe_color(c));
+        let mut args: Vec<String> = Vec::new();
+        match self {
+            Self::GnomeTerminal => {
+                args.push("--tab".to_string());
+                args.push(format!("--working-directory={}", request.project_path));
+                if let Some(t) = title {
+                    args.push(format!("--title={}", t));
+                }
+                args.push("--".to_string());
+            }
+            Self::Konsole => {
+                args.push("--new-tab".to_string());
+                args.push("--workdir".to_string());
+                args.push(request.project_path.clone());
+                if let Some(t) = title {
+                    args.push("-p".to_string());
+                    args.push(format!("tabtitle={}", t));
+                }
+                if let Some(c) = color {
+                    args.push("-p".to_string());
+                    args.push(format!("TabColor={}", c));
+                }
+                // konsole has no `--`; `-e` consumes the rest of argv.
+                args.push("-e".to_string());
+            }
+            Self::Kitty => {
+                args.push("--directory".to_string());
+                args.push(request.project_path.clone());
+                if let Some(t) = title {
+                    args.push("--title".to_string());
+                    args.push(t.to_string());
+                }
+                if let Some(c) = color {
+                    args.push("-o".to_string());
+                    args.push(format!("active_tab_background={}", c));
+                }
+                args.push("--".to_string());
+            }
+            Self::Wezterm => {
+                // `wezterm start` has no title option; the agent's own OSC
+                // title (if any) is what shows.
+                args.push("start".to_string());
+                args.push("--cwd".to_string());
+                args.push(request.project_path.clone());
+                args.push("--".to_string());
+            }
+            Self::Xterm => {
+                // xterm has no working-directory option; the plan's cwd
+                // covers it.
+                if let Some(t) = title {
+                    args.push("-T".to_string());
+                    args.push(t.to_string());
+                }
+                // Like konsole, `-e` must be last and takes the rest of argv.
+                args.push("-e".to_string());
+            }
+        }
+        args
+    }
+}
+
+impl TerminalBackend for LinuxTerminal {
+    fn name(&self) -> &'static str {
+        self.program()
+    }
+
+    /// The `PosixShell` plan, wrapped in this emulator's options.
+    fn plan(&self, request: &LaunchRequest, ctx: &PlanContext) -> LaunchPlan {
+        let inner = PosixShell.plan(request, ctx);
+        let mut plan = LaunchPlan::new(self.program());
+        plan.args = self.options(request);
+        plan.args.push(inner.program);
+        plan.args.extend(inner.args);
+        plan.env = inner.env;
+        plan.env_remove = inner.env_remove;
+        plan.cwd = inner.cwd;
+        plan
+    }
+}
+
+/// Resolve the request's terminal order, or the built-in one when it names
+/// none. Unknown names are an error rather than silently skipped, so a typo in
+/// settings surfaces instead of quietly launching something else.
+pub(crate) fn resolve_terminal_order(names: &[String]) -> Result<Vec<LinuxTerminal>, LaunchError> {
+    if names.is_empty() {
+        return Ok(DEFAULT_LINUX_TERMINALS.to_vec());
+    }
+    names
+        .iter()
+        .map(|n| {
+            LinuxTerminal::from_name(n).ok_or_else(|| {
+                LaunchError::validation("terminalOrder", format!("Unknown terminal rejected: {}", n))
+            })
+        })
+        .collect()
+}
+
+/// The backends to try for this host, in order.
+pub(crate) fn backends_for_host(order: &[LinuxTerminal]) -> Vec<Box<dyn TerminalBackend + Send + Sync>> {
+    if cfg!(windows) {
+        vec![Box::new(WindowsTerminal), Box::new(Pwsh)]
+    } else {
+        order
+            .iter()
+            .map(|&t| Box::new(t) as Box<dyn TerminalBackend + Send + Sync>)
+            .collect()
+    }
+}
+
+/// Write the plan's script, if any, and spawn its program.
+pub(crate) fn spawn_plan(plan: &LaunchPlan) -> Result<Child, LaunchError> {
+    if let Some(script) = &plan.script {
+        prelaunch::write_script(&script.path, &script.contents)
+            .map_err(|e| LaunchError::io("Failed to write pre-launch script", e))?;
+    }
+    let mut cmd = Command::new(&plan.program);
+    cmd.args(&plan.args);
+    for key in &plan.env_remove {
+        cmd.env_remove(key);
+    }
+    cmd.envs(&plan.env);
+    if let Some(cwd) = &plan.cwd {
+        cmd.current_dir(cwd);
+    }
+    cmd.spawn().map_err(|e| {
+        discard_script(plan);
+        LaunchError::spawn(&plan.program, &e)
+    })
+}
+
+/// Remove the plan's script now: its attempt failed, so no session will run it.
+fn discard_script(plan: &LaunchPlan) {
+    if let Some(script) = &plan.script {
+        let _ = std::fs::remove_file(&script.path);
+    }
+}
+
+/// The attempt started: its script deletes itself once pwsh has read it, and
+/// the janitor removes it after `prelaunch::SCRIPT_TTL` should that never
+/// happen.
+fn expire_script(plan: &LaunchPlan) {
+    if let Some(script) = &plan.script {
+        prelaunch::schedule_removal(script.path.clone());
+    }
+}
+
+/// Launch through the first backend whose plan starts. Each attempt is
+/// spawned, given a moment, and treated as failed if it has already exited
+/// non-zero — wt in particular reports a bad profile that way — in which case
+/// the next backend is tried.
+pub(crate) async fn launch_with_backends(
+    log_path: &Path,
+    request: &LaunchRequest,
+    backends: &[Box<dyn TerminalBackend + Send + Sync>],
+    ctx: &PlanContext,
+) -> LaunchResult {
+    let mut attempts: Vec<LaunchAttempt> = Vec::new();
+
+    for backend in backends {
+        if !attempts.is_empty() {
+            write_log(log_path, "INFO", &format!("Falling back to {}", backend.name()));
+        }
+
+        let plan = backend.plan(request, ctx);
+        let full_command = plan.display();
+        write_log(log_path, "INFO", &format!("Executing: {}", full_command));
+
+        match spawn_plan(&plan) {
+            Ok(mut child) => {
+                tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+                match child.try_wait() {
+                    Ok(Some(status)) if !status.success() => {
+                        let error = LaunchError::Exited {
+                            program: backend.name().to_string(),
+                            code: status.code(),
+                            stderr: String::new(),
+                        };
+                        write_log(log_path, "WARN", &error.to_string());
+                        attempts.push(LaunchAttempt { backend: backend.name().to_string(), error });
+                        discard_script(&plan);
+                    }
+                    Ok(_) => {
+                        write_log(log_path, "INFO", &format!("Launch successful via {}", backend.name()));
+                        expire_script(&plan);
+                        return LaunchResult { success: true, command: full_command, error: None };
+                    }
+                    Err(e) => {
+                        // Can't tell; the spawn itself worked, so call it a success.
+                        write_log(
+                            log_path,
+                            "WARN",
+                            &format!("Error checking {} status: {}", backend.name(), e),
+                        );
+                        expire_script(&plan);
+                        return LaunchResult { success: true, command: full_command, error: None };
+                    }
+                }
+            }
+            Err(error) => {
+                write_log(log_path, "WARN", &error.to_string());
+                attempts.push(LaunchAttempt { backend: backend.name().to_string(), error });
+            }
+        }
+    }
+
+    let error = LaunchError::FallbackExhausted { attempts };
+    write_log(log_path, "ERROR", &error.to_string());
+    LaunchResult { success: false, command: String::new(), error: Some(error) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(pre_launch: Option<&str>) -> LaunchRequest {
+        LaunchRequest {
+            agent_path: "C:\\Tools\\claude.exe".to_string(),
+            project_path: "C:\\src\\proj".to_string(),
+            terminal_profile: "PowerShell".to_string(),
+            flags: vec!["--model=opus".to_string()],
+            subcommand: None,
+            claude_features: true,
+            notify_hook: false,
+            pre_launch_command: pre_launch.map(str::to_string),
+            tab_color: Some("#ff8800".to_string()),
+            tab_title: Some("proj".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn ctx() -> PlanContext {
+        PlanContext {
+            notify_arg: None,
+            prelaunch_script: PathBuf::from("T:\\claude-launcher-prelaunch.ps1"),
+        }
+    }
+
+    fn strings(v: &[&str]) -> Vec<String> {
+        v.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn wt_plan_snapshot() {
+        let plan = WindowsTerminal.plan(&request(None), &ctx());
+        assert_eq!(plan.program, "wt");
+        assert_eq!(
+            plan.args,
+            strings(&[
+                "new-tab", "--profile", "PowerShell", "-d", "C:\\src\\proj",
+                "--tabColor", "#ff8800", "--title", "proj", "--suppressApplicationTitle",
+                "--", "C:\\Tools\\claude.exe", "--model=opus",
+            ])
+        );
+        assert_eq!(plan.env_remove, strings(&["CLAUDECODE"]));
+        assert_eq!(plan.env.get(FULL_REPAINT_ENV).map(String::as_str), Some("1"));
+        assert!(plan.script.is_none());
+    }
+
+    /// A live title (dynamic or model-in-title) must leave the title
+    /// un-suppressed, and a non-Claude agent gets none of Claude's env.
+    #[test]
+    fn wt_plan_live_title_and_other_agent() {
+        let mut req = request(None);
+        req.model_in_title = Some(true);
+        req.claude_features = false;
+        let plan = WindowsTerminal.plan(&req, &ctx());
+        assert!(!plan.args.iter().any(|a| a == "--suppressApplicationTitle"));
+        assert!(plan.env.is_empty());
+        assert!(plan.env_remove.is_empty());
+    }
+
+    #[test]
+    fn wt_plan_pre_launch_uses_script() {
+        let plan = WindowsTerminal.plan(&request(Some("nvm use 20")), &ctx());
+        let sep = plan.args.iter().position(|a| a == "--").unwrap();
+        assert_eq!(
+            plan.args[sep + 1..],
+            strings(&["pwsh", "-NoExit", "-ExecutionPolicy", "Bypass", "-File", "T:\\claude-launcher-prelaunch.ps1"])
+        );
+        let script = plan.script.expect("pre-launch needs a script");
+        assert_eq!(
+            script.contents,
+            format!("{}\nnvm use 20\n& 'C:\\Tools\\claude.exe' '--model=opus'", prelaunch::SELF_DELETE)
+        );
+    }
+
+    /// An existing wt window serves the new tab with its own env, not the
+    /// wt process's, so a secret must reach the agent through the script
+    /// alone: set there before the agent runs, and nowhere else.
+    #[test]
+    fn wt_plan_secrets_reach_a_tab_of_an_existing_window() {
+        let mut req = request(None);
+        req.secret_env.insert("ANTHROPIC_API_KEY".to_string(), "work-key".to_string());
+        req.env.insert("ANTHROPIC_API_KEY".to_string(), "sk-ant-value".to_string());
+        let plan = WindowsTerminal.plan(&req, &ctx());
+        assert!(!plan.env.contains_key("ANTHROPIC_API_KEY"));
+        let tail = plan.args.iter().skip_while(|a| *a != "--").skip(1).cloned().collect::<Vec<_>>();
+        assert_eq!(tail.first().map(String::as_str), Some("pwsh"));
+        assert_eq!(tail.last(), Some(&ctx().prelaunch_script.to_string_lossy().to_string()));
+        let script = plan.script.expect("a secret needs the script");
+        assert_eq!(script.path, ctx().prelaunch_script);
+        let lines: Vec<&str> = script.contents.lines().collect();
+        assert_eq!(lines[0], prelaunch::SELF_DELETE);
+        let set = lines.iter().position(|l| *l == "$env:ANTHROPIC_API_KEY = 'sk-ant-value'");
+        let agent = lines.iter().position(|l| l.starts_with("& 'C:\\Tools\\claude.exe'"));
+        assert!(matches!((set, agent), (Some(s), Some(a)) if s < a), "{}", script.contents);
+    }
+
+    #[test]
+    fn wt_plan_env_goes_through_script() {
+        let mut req = request(None);
+        req.env.insert("DISABLE_TELEMETRY".to_string(), "1".to_string());
+        req.env.insert("NOTE".to_string(), "it's".to_string());
+        let plan = WindowsTerminal.plan(&req, &ctx());
+        let script = plan.script.expect("env needs a script");
+        assert_eq!(
+            script.contents,
+            format!(
+                "{}\n$env:DISABLE_TELEMETRY = '1'\n$env:NOTE = 'it''s'\n& 'C:\\Tools\\claude.exe' '--model=opus'",
+                prelaunch::SELF_DELETE
+            )
+        );
+        // The direct spawns carry it on the process instead.
+        assert_eq!(Pwsh.plan(&req, &ctx()).env.get("NOTE").map(String::as_str), Some("it's"));
+        assert_eq!(PosixShell.plan(&req, &ctx()).env.get("NOTE").map(String::as_str), Some("it's"));
+    }
+
+    #[test]
+    fn wt_plan_appends_notify_arg() {
+        let mut c = ctx();
+        c.notify_arg = Some("--config=notify=['x']".to_string());
+        let plan = WindowsTerminal.plan(&request(None), &c);
+        assert_eq!(plan.args.last().map(String::as_str), Some("--config=notify=['x']"));
+    }
+
+    #[test]
+    fn pwsh_plan_snapshot() {
+        let plan = Pwsh.plan(&request(Some("nvm use 20")), &ctx());
+        assert_eq!(
+            plan.display(),
+            "pwsh -NoExit -WorkingDirectory C:\\src\\proj -Command \
+             $Host.UI.RawUI.WindowTitle = 'proj'; nvm use 20; & 'C:\\Tools\\claude.exe' '--model=opus'"
+        );
+        assert_eq!(plan.env_remove, strings(&["CLAUDECODE"]));
+    }
+
+    #[test]
+    fn posix_plan_snapshot() {
+        let mut req = request(Some("source ~/.venv/bin/activate"));
+        req.agent_path = "/usr/local/bin/claude".to_string();
+        req.project_path = "/home/dev/proj".to_string();
+        let plan = PosixShell.plan(&req, &ctx());
+        assert_eq!(plan.program, "sh");
+        assert_eq!(
+            plan.args,
+            strings(&["-c", "source ~/.venv/bin/activate\nexec '/usr/local/bin/claude' '--model=opus'"])
+        );
+        assert_eq!(plan.cwd.as_deref(), Some("/home/dev/proj"));
+        assert!(!plan.env.contains_key(FULL_REPAINT_ENV));
+    }
+
+    /// The agent argv must come after the emulator's end-of-options marker,
+    /// untouched, so a flag can never be parsed as a terminal option.
+    #[test]
+    fn linux_command_follows_separator() {
+        let req = request(None);
+        let inner = PosixShell.plan(&req, &ctx());
+        let mut cmd = vec![inner.program];
+        cmd.extend(inner.args);
+        for term in DEFAULT_LINUX_TERMINALS {
+            let plan = term.plan(&req, &ctx());
+            let args = &plan.args;
+            assert_eq!(&args[args.len() - cmd.len()..], cmd.as_slice(), "{:?}", term);
+            let sep = &args[args.len() - cmd.len() - 1];
+            assert!(sep == "--" || sep == "-e", "{:?} separator was {}", term, sep);
+        }
+    }
+
+    /// A bad title or color is dropped, never forwarded to the emulator.
+    #[test]
+    fn linux_unsafe_title_and_color_dropped() {
+        let mut req = request(None);
+        req.tab_title = Some("a;b".to_string());
+        req.tab_color = Some("red".to_string());
+        let plan = LinuxTerminal::Kitty.plan(&req, &ctx());
+        assert!(!plan.args.iter().any(|a| a == "--title" || a.contains("active_tab_background")));
+    }
+
+    #[test]
+    fn terminal_order_resolution() {
+        assert_eq!(resolve_terminal_order(&[]).unwrap(), DEFAULT_LINUX_TERMINALS);
+        assert_eq!(
+            resolve_terminal_order(&["xterm".to_string(), "kitty".to_string()]).unwrap(),
+            vec![LinuxTerminal::Xterm, LinuxTerminal::Kitty]
+        );
+        assert!(resolve_terminal_order(&["/bin/evil".to_string()]).is_err());
+    }
+}
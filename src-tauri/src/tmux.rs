@@ -0,0 +1,670 @@
+//! tmux / screen launch target. Instead of opening a terminal tab, the agent
+//! starts in a window of a named multiplexer session — created on first use,
+//! reused after — so it survives the launcher (and an SSH connection) going
+//! away. Windows get the same env an embedded IDE session does. Secrets and the
+//! listener token never go on a command line, where `ps` and tmux's
+//! `pane_start_command` would show them to anyone: the window sources them
+//! from a private file instead.
+//!
+//! tmux windows are tagged with a window option carrying the launcher session
+//! id, which is what `list_tmux_windows` filters on; screen has no per-window
+//! options, so screen launches are fire-and-forget. Either way the id is
+//! registered in `MultiplexerSessions`, so the window's hook pings reach the
+//! listener like an IDE session's.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::process::Command;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::Manager;
+
+use crate::error::LaunchError;
+use crate::ide::{session_env, HookEndpoint};
+use crate::prelaunch;
+use crate::redact::redact_env_value;
+use crate::terminal::{LaunchPlan, PlanContext, PlannedScript, PosixShell, TerminalBackend};
+use crate::{is_safe_title, write_log, LaunchRequest, LaunchResult, LogPath};
+
+/// Session used when the request does not name one.
+pub(crate) const DEFAULT_SESSION: &str = "claude-launcher";
+
+/// tmux window option holding the launcher session id of the agent running
+/// in that window. Its presence is what makes a window "launcher-owned".
+const OWNER_OPTION: &str = "@claude_launcher_session";
+
+/// Managed state: the launcher session ids this run has given multiplexer
+/// windows. The listener owns their pings as it does a live PTY's. Never
+/// pruned: the app cannot see a window close, and an id is a few bytes.
+#[derive(Default)]
+pub struct MultiplexerSessions(Mutex<HashSet<String>>);
+
+impl MultiplexerSessions {
+    pub(crate) fn contains(&self, session_id: &str) -> bool {
+        self.0.lock().unwrap().contains(session_id)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Multiplexer {
+    Tmux,
+    Screen,
+}
+
+impl Multiplexer {
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "tmux" => Some(Self::Tmux),
+            "screen" => Some(Self::Screen),
+            _ => None,
+        }
+    }
+
+    fn program(self) -> &'static str {
+        match self {
+            Self::Tmux => "tmux",
+            Self::Screen => "screen",
+        }
+    }
+}
+
+/// Validate a multiplexer session name: ASCII letters, digits, `-` and `_`.
+/// tmux reserves `:` and `.` for target syntax, and the name is passed as a
+/// target, so anything looser could address a different session.
+pub(crate) fn is_safe_session_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// The window name: the tab title when it is valid, else the project
+/// directory's last component.
+fn window_name(request: &LaunchRequest) -> String {
+    match request.tab_title.as_deref() {
+        Some(t) if is_safe_title(t) => t.to_string(),
+        _ => std::path::Path::new(&request.project_path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "agent".to_string()),
+    }
+}
+
+/// What the window runs, and the env file it reads first.
+#[derive(Debug)]
+pub(crate) struct WindowCommand {
+    pub argv: Vec<String>,
+    /// The env kept off `argv`, as `export` lines, for the window to source and
+    /// delete before the agent starts. None when there is none.
+    pub env_file: Option<PlannedScript>,
+}
+
+/// Run by the window when it has an env file: `$1` is the file, the rest is the
+/// agent's argv. A file the shell cannot read ends the window there, rather
+/// than starting the agent without its secrets.
+const SOURCE_ENV_FILE: &str = r#"f=$1; shift; . "$f"; rm -f -- "$f"; exec "$@""#;
+
+/// Env the window must not get through argv: secrets from the store, and the
+/// listener token, which would let anyone who read it send pings.
+fn is_private_env(request: &LaunchRequest, key: &str) -> bool {
+    key == "CLAUDE_LAUNCHER_TOKEN" || request.secret_env.contains_key(key)
+}
+
+/// The command the window runs: the `PosixShell` plan behind `env(1)`, which
+/// applies the session env. The multiplexer server's own environment is
+/// whatever it was started with, so `-u` is the only reliable way to strip a
+/// var (CLAUDECODE in particular) from the window. Private env goes in the
+/// env file at `prelaunch::env_file_path`, sourced by `sh` in front of the
+/// agent.
+pub(crate) fn window_command(
+    request: &LaunchRequest,
+    ctx: &PlanContext,
+    session_id: &str,
+    endpoint: &HookEndpoint,
+) -> WindowCommand {
+    let inner = PosixShell.plan(request, ctx);
+    let (set, mut remove) = session_env(request, session_id, endpoint);
+    for key in inner.env_remove {
+        if !remove.contains(&key) {
+            remove.push(key);
+        }
+    }
+    let mut argv = vec!["env".to_string()];
+    for key in remove {
+        argv.push("-u".to_string());
+        argv.push(key);
+    }
+    // Session env last, so the request's own env cannot override it. The file
+    // is sourced after env(1) has applied the rest, so it keeps that order.
+    let mut exports = Vec::new();
+    for (key, value) in inner.env.iter().chain(set.iter()) {
+        if is_private_env(request, key) {
+            exports.push(format!("export {}='{}'\n", key, value.replace('\'', "'\\''")));
+        } else {
+            argv.push(format!("{}={}", key, value));
+        }
+    }
+    let env_file = (!exports.is_empty()).then(|| PlannedScript {
+        path: prelaunch::env_file_path(&ctx.prelaunch_script),
+        contents: exports.concat(),
+    });
+    if let Some(file) = &env_file {
+        argv.push("sh".to_string());
+        argv.push("-c".to_string());
+        argv.push(SOURCE_ENV_FILE.to_string());
+        argv.push("sh".to_string());
+        argv.push(file.path.to_string_lossy().to_string());
+    }
+    argv.push(inner.program);
+    argv.extend(inner.args);
+    WindowCommand { argv, env_file }
+}
+
+/// `arg`, with the value replaced when it is one of the request's `K=V` env
+/// args and looks like a secret.
+fn redact_env_arg(request: &LaunchRequest, arg: &str) -> String {
+    if let Some((key, value)) = arg.split_once('=') {
+        if request.env.get(key).is_some_and(|v| v == value) {
+            return format!("{}={}", key, redact_env_value(key, value));
+        }
+    }
+    arg.to_string()
+}
+
+/// tmux argv for the window. A new session is created detached with this as
+/// its first window; an existing one gets a new window, which becomes its
+/// current window so any attached client switches to it. Either way the new
+/// window's id is printed so it can be tagged.
+pub(crate) fn tmux_args(
+    request: &LaunchRequest,
+    session: &str,
+    session_exists: bool,
+    command: &[String],
+) -> Vec<String> {
+    let mut args: Vec<String> = if session_exists {
+        vec![
+            "new-window".to_string(),
+            "-t".to_string(),
+            format!("={}:", session),
+        ]
+    } else {
+        vec![
+            "new-session".to_string(),
+            "-d".to_string(),
+            "-s".to_string(),
+            session.to_string(),
+        ]
+    };
+    args.push("-n".to_string());
+    args.push(window_name(request));
+    args.push("-c".to_string());
+    args.push(request.project_path.clone());
+    args.push("-P".to_string());
+    args.push("-F".to_string());
+    args.push("#{window_id}".to_string());
+    args.push("--".to_string());
+    args.extend(command.iter().cloned());
+    args
+}
+
+/// screen invocations for the window, run in order. screen has no
+/// working-directory option for `-X screen`, so an existing session is first
+/// told to `chdir`; a new session takes the spawn's cwd instead.
+pub(crate) fn screen_invocations(
+    request: &LaunchRequest,
+    session: &str,
+    session_exists: bool,
+    command: &[String],
+) -> Vec<Vec<String>> {
+    let title = window_name(request);
+    if session_exists {
+        let mut window = vec![
+            "-S".to_string(),
+            session.to_string(),
+            "-X".to_string(),
+            "screen".to_string(),
+            "-t".to_string(),
+            title,
+        ];
+        window.extend(command.iter().cloned());
+        vec![
+            vec![
+                "-S".to_string(),
+                session.to_string(),
+                "-X".to_string(),
+                "chdir".to_string(),
+                request.project_path.clone(),
+            ],
+            window,
+        ]
+    } else {
+        let mut new = vec![
+            "-dmS".to_string(),
+            session.to_string(),
+            "-t".to_string(),
+            title,
+        ];
+        new.extend(command.iter().cloned());
+        vec![new]
+    }
+}
+
+fn invocations(
+    request: &LaunchRequest,
+    multiplexer: Multiplexer,
+    session: &str,
+    session_exists: bool,
+    command: &[String],
+) -> Vec<Vec<String>> {
+    match multiplexer {
+        Multiplexer::Tmux => vec![tmux_args(request, session, session_exists, command)],
+        Multiplexer::Screen => screen_invocations(request, session, session_exists, command),
+    }
+}
+
+/// The invocations `launch_in_multiplexer` would run, as plans, for a session
+/// that does or does not exist yet; the first carries the env file as its
+/// script. The session id is only minted at launch, so a placeholder stands in
+/// for it; the listener token, which is not for display, gets one from the
+/// caller.
+pub(crate) fn preview_plans(
+    request: &LaunchRequest,
+    multiplexer: Multiplexer,
+    session: &str,
+    session_exists: bool,
+    ctx: &PlanContext,
+    endpoint: &HookEndpoint,
+) -> Vec<LaunchPlan> {
+    let command = window_command(request, ctx, "<session-id>", endpoint);
+    let mut plans: Vec<LaunchPlan> = invocations(request, multiplexer, session, session_exists, &command.argv)
+        .into_iter()
+        .map(|args| {
+            let mut plan = LaunchPlan::new(multiplexer.program());
+            plan.args = args;
+            plan.cwd = Some(request.project_path.clone());
+            plan
+        })
+        .collect();
+    if let Some(first) = plans.first_mut() {
+        first.script = command.env_file;
+    }
+    plans
+}
+
+fn run(program: &str, args: &[String], cwd: &str) -> Result<String, LaunchError> {
+    let out = Command::new(program)
+        .args(args)
+        .current_dir(cwd)
+        .output()
+        .map_err(|e| LaunchError::spawn(program, &e))?;
+    if !out.status.success() {
+        return Err(LaunchError::Exited {
+            program: program.to_string(),
+            code: out.status.code(),
+            stderr: String::from_utf8_lossy(&out.stderr).trim().to_string(),
+        });
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).to_string())
+}
+
+fn session_exists(multiplexer: Multiplexer, session: &str) -> bool {
+    match multiplexer {
+        Multiplexer::Tmux => Command::new("tmux")
+            .args(["has-session", "-t", &format!("={}", session)])
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false),
+        // `screen -ls` exits non-zero even when it lists sessions on some
+        // builds, so go by its output: each line reads "\t<pid>.<name>\t(...)".
+        Multiplexer::Screen => Command::new("screen")
+            .arg("-ls")
+            .output()
+            .map(|o| {
+                String::from_utf8_lossy(&o.stdout).lines().any(|l| {
+                    l.trim_start()
+                        .split('\t')
+                        .next()
+                        .and_then(|id| id.split_once('.'))
+                        .is_some_and(|(_, name)| name == session)
+                })
+            })
+            .unwrap_or(false),
+    }
+}
+
+/// Start the agent in a window of `session`, creating the session if needed.
+/// The window registers and pings under `session_id`. Every tmux/screen call
+/// blocks until it returns, so they run off the async runtime's workers.
+pub(crate) async fn launch_in_multiplexer(
+    app: &tauri::AppHandle,
+    log_path: &Path,
+    request: &LaunchRequest,
+    multiplexer: Multiplexer,
+    session: &str,
+    session_id: &str,
+    ctx: &PlanContext,
+) -> LaunchResult {
+    let session_id = session_id.to_string();
+    let command = window_command(request, ctx, &session_id, &HookEndpoint::of(app));
+    let (app, log_path, request, session) =
+        (app.clone(), log_path.to_path_buf(), request.clone(), session.to_string());
+    tauri::async_runtime::spawn_blocking(move || {
+        launch_blocking(&app, &log_path, &request, multiplexer, &session, session_id, command)
+    })
+    .await
+    .unwrap_or_else(|e| LaunchResult {
+        success: false,
+        command: String::new(),
+        error: Some(LaunchError::io("Multiplexer launch", e)),
+    })
+}
+
+fn launch_blocking(
+    app: &tauri::AppHandle,
+    log_path: &Path,
+    request: &LaunchRequest,
+    multiplexer: Multiplexer,
+    session: &str,
+    session_id: String,
+    command: WindowCommand,
+) -> LaunchResult {
+    let exists = session_exists(multiplexer, session);
+    let program = multiplexer.program();
+
+    let invocations = invocations(request, multiplexer, session, exists, &command.argv);
+    // The window command carries the request's other env as `K=V` args;
+    // secret-looking values stay out of the log and the reported command.
+    let full_command = invocations
+        .iter()
+        .map(|args| {
+            let shown: Vec<String> = args.iter().map(|a| redact_env_arg(request, a)).collect();
+            format!("{} {}", program, shown.join(" "))
+        })
+        .collect::<Vec<_>>()
+        .join(" && ");
+    write_log(log_path, "INFO", &format!("Executing: {}", full_command));
+
+    // Registered before the window can start, and ping.
+    let sessions = app.state::<MultiplexerSessions>();
+    sessions.0.lock().unwrap().insert(session_id.clone());
+    if let Some(file) = &command.env_file {
+        if let Err(e) = prelaunch::write_script(&file.path, &file.contents) {
+            sessions.0.lock().unwrap().remove(&session_id);
+            let e = LaunchError::io("Env file", e);
+            write_log(log_path, "ERROR", &e.to_string());
+            return LaunchResult { success: false, command: full_command, error: Some(e) };
+        }
+    }
+    let mut stdout = String::new();
+    for args in &invocations {
+        match run(program, args, &request.project_path) {
+            Ok(out) => stdout = out,
+            Err(e) => {
+                if let Some(file) = &command.env_file {
+                    let _ = std::fs::remove_file(&file.path);
+                }
+                sessions.0.lock().unwrap().remove(&session_id);
+                write_log(log_path, "ERROR", &e.to_string());
+                return LaunchResult { success: false, command: full_command, error: Some(e) };
+            }
+        }
+    }
+    // The window removes it once read; this catches a window that never ran.
+    if let Some(file) = &command.env_file {
+        prelaunch::schedule_removal(file.path.clone());
+    }
+
+    // Tag the window so it can be found again. Best-effort: an untagged
+    // window still runs, it just won't be listed.
+    if multiplexer == Multiplexer::Tmux {
+        let window_id = stdout.trim();
+        if let Err(e) = run(
+            "tmux",
+            &[
+                "set-option".to_string(),
+                "-w".to_string(),
+                "-t".to_string(),
+                window_id.to_string(),
+                OWNER_OPTION.to_string(),
+                session_id.clone(),
+            ],
+            &request.project_path,
+        ) {
+            write_log(log_path, "WARN", &format!("Failed to tag tmux window: {}", e));
+        }
+    }
+
+    write_log(
+        log_path,
+        "INFO",
+        &format!("Launch successful via {} session {}", program, session),
+    );
+    LaunchResult { success: true, command: full_command, error: None }
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TmuxWindow {
+    session: String,
+    window_id: String,
+    name: String,
+    cwd: String,
+    active: bool,
+    /// The `CLAUDE_LAUNCHER_SESSION` id the window's agent was started with.
+    launcher_session: String,
+}
+
+/// Tab-separated `list-windows` format; parsed by `parse_tmux_windows`.
+const LIST_FORMAT: &str =
+    "#{session_name}\t#{window_id}\t#{window_name}\t#{pane_current_path}\t#{window_active}\t#{@claude_launcher_session}";
+
+/// Parse `tmux list-windows -a -F LIST_FORMAT` output, keeping only windows
+/// the launcher tagged.
+fn parse_tmux_windows(output: &str) -> Vec<TmuxWindow> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let f: Vec<&str> = line.split('\t').collect();
+            if f.len() != 6 || f[5].is_empty() {
+                return None;
+            }
+            Some(TmuxWindow {
+                session: f[0].to_string(),
+                window_id: f[1].to_string(),
+                name: f[2].to_string(),
+                cwd: f[3].to_string(),
+                active: f[4] == "1",
+                launcher_session: f[5].to_string(),
+            })
+        })
+        .collect()
+}
+
+/// `cmd`'s output, waited for off the async runtime's workers.
+async fn output_blocking(mut cmd: Command) -> std::io::Result<std::process::Output> {
+    tauri::async_runtime::spawn_blocking(move || cmd.output())
+        .await
+        .map_err(std::io::Error::other)?
+}
+
+/// Live launcher-owned tmux windows across every session. No tmux server (or
+/// no tmux at all) simply means there are none.
+#[tauri::command]
+pub async fn list_tmux_windows() -> Result<Vec<TmuxWindow>, String> {
+    let mut cmd = Command::new("tmux");
+    cmd.args(["list-windows", "-a", "-F", LIST_FORMAT]);
+    let out = match output_blocking(cmd).await {
+        Ok(o) if o.status.success() => o,
+        _ => return Ok(Vec::new()),
+    };
+    Ok(parse_tmux_windows(&String::from_utf8_lossy(&out.stdout)))
+}
+
+/// Run tmux with `args`, off the async runtime's workers: its stdout, or
+/// what it said on failure.
+async fn tmux(args: &[&str]) -> Result<String, String> {
+    let mut cmd = Command::new("tmux");
+    cmd.args(args);
+    let out = output_blocking(cmd).await.map_err(|e| format!("tmux spawn failed: {}", e))?;
+    if !out.status.success() {
+        return Err(format!("tmux {} failed: {}", args[0], String::from_utf8_lossy(&out.stderr).trim()));
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
+/// Make a launcher-owned window its session's current window and bring an
+/// attached client to it. `window_id` must be a tmux window id (`@<n>`) of a
+/// window the launcher tagged; any other window is refused.
+#[tauri::command]
+pub async fn focus_tmux_window(app: tauri::AppHandle, window_id: String) -> Result<(), String> {
+    let valid = window_id
+        .strip_prefix('@')
+        .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+    if !valid {
+        return Err(format!("Invalid tmux window id rejected: {}", window_id));
+    }
+    let result = focus_window(&window_id).await;
+    if let Err(msg) = &result {
+        let log_path = app.state::<LogPath>().0.lock().unwrap().clone();
+        write_log(&log_path, "WARN", msg);
+    }
+    result
+}
+
+async fn focus_window(window_id: &str) -> Result<(), String> {
+    let owner = tmux(&["show-options", "-wqv", "-t", window_id, OWNER_OPTION]).await?;
+    if owner.trim().is_empty() {
+        return Err(format!("Not a launcher window, not focused: {}", window_id));
+    }
+    tmux(&["select-window", "-t", window_id]).await?;
+    // select-window only changes what the window's session shows; a client
+    // looking at another session has to be switched over to it.
+    let clients = tmux(&["list-clients", "-F", "#{client_name}"]).await?;
+    if !clients.trim().is_empty() {
+        tmux(&["switch-client", "-t", window_id]).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request() -> LaunchRequest {
+        LaunchRequest {
+            agent_path: "/usr/local/bin/claude".to_string(),
+            project_path: "/srv/work/proj".to_string(),
+            terminal_profile: String::new(),
+            flags: vec!["--continue".to_string()],
+            subcommand: None,
+            claude_features: true,
+            notify_hook: false,
+            pre_launch_command: None,
+            tab_color: None,
+            tab_title: Some("proj".to_string()),
+            multiplexer: Some("tmux".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn ctx() -> PlanContext {
+        PlanContext {
+            notify_arg: None,
+            prelaunch_script: std::path::PathBuf::from("/tmp/unused.ps1"),
+        }
+    }
+
+    /// The window gets the IDE session env via env(1), with the agent argv
+    /// intact at the end.
+    #[test]
+    fn window_command_carries_session_env() {
+        let endpoint = HookEndpoint {
+            port: 4242,
+            socket: Some(std::path::PathBuf::from("/run/user/1000/claude-launcher/listener-1.sock")),
+            token: "t0k3n".to_string(),
+        };
+        let WindowCommand { argv: cmd, env_file } = window_command(&request(), &ctx(), "sid-1", &endpoint);
+        // The token goes in the env file, not on the command line.
+        assert!(cmd.iter().all(|a| !a.contains("t0k3n")), "{:?}", cmd);
+        assert_eq!(env_file.unwrap().contents, "export CLAUDE_LAUNCHER_TOKEN='t0k3n'\n");
+        assert_eq!(cmd[0], "env");
+        let unset: Vec<&str> = cmd
+            .windows(2)
+            .filter(|w| w[0] == "-u")
+            .map(|w| w[1].as_str())
+            .collect();
+        assert!(unset.contains(&"CLAUDECODE"));
+        assert!(cmd.contains(&"CLAUDE_LAUNCHER_SESSION=sid-1".to_string()));
+        assert!(cmd.contains(&"CLAUDE_LAUNCHER_PORT=4242".to_string()));
+        assert!(cmd.contains(&"CLAUDE_LAUNCHER_SOCKET=/run/user/1000/claude-launcher/listener-1.sock".to_string()));
+        assert_eq!(cmd[cmd.len() - 2..], ["/usr/local/bin/claude".to_string(), "--continue".to_string()]);
+    }
+
+    /// A secret reaches the window through the env file alone, which the
+    /// window sources and deletes before exec'ing the agent.
+    #[cfg(unix)]
+    #[test]
+    fn secrets_go_through_an_env_file() {
+        let mut req = request();
+        req.secret_env.insert("ANTHROPIC_API_KEY".to_string(), "work".to_string());
+        req.env.insert("ANTHROPIC_API_KEY".to_string(), "sk-'x'".to_string());
+        let endpoint = HookEndpoint { port: 4242, socket: None, token: "t0k3n".to_string() };
+        let dir = std::env::temp_dir().join(format!("claude-launcher-envfile-test-{}", std::process::id()));
+        let ctx = PlanContext { notify_arg: None, prelaunch_script: dir.join("launch.ps1") };
+        let WindowCommand { argv, env_file } = window_command(&req, &ctx, "sid-1", &endpoint);
+        assert!(argv.iter().all(|a| !a.contains("sk-")), "{:?}", argv);
+        let file = env_file.expect("a secret needs the env file");
+        assert_eq!(file.path, dir.join("launch.env"));
+
+        // Run it for real, with `sh -c 'echo ...'` standing in for the agent.
+        prelaunch::write_script(&file.path, &file.contents).unwrap();
+        let sh = argv.iter().position(|a| a == "sh").unwrap();
+        let out = std::process::Command::new("sh")
+            .args(&argv[sh + 1..sh + 5])
+            .args(["sh", "-c", "printf %s \"$ANTHROPIC_API_KEY\""])
+            .output()
+            .unwrap();
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "sk-'x'");
+        assert!(!file.path.exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn tmux_new_vs_existing_session() {
+        let cmd = vec!["claude".to_string()];
+        let new = tmux_args(&request(), "work", false, &cmd);
+        assert_eq!(new[..4], ["new-session", "-d", "-s", "work"].map(String::from));
+        let existing = tmux_args(&request(), "work", true, &cmd);
+        assert_eq!(existing[..3], ["new-window", "-t", "=work:"].map(String::from));
+        for args in [new, existing] {
+            let sep = args.iter().position(|a| a == "--").unwrap();
+            assert_eq!(args[sep + 1..], cmd[..]);
+            assert!(args.windows(2).any(|w| w[0] == "-n" && w[1] == "proj"));
+            assert!(args.windows(2).any(|w| w[0] == "-c" && w[1] == "/srv/work/proj"));
+        }
+    }
+
+    #[test]
+    fn session_name_validation() {
+        assert!(is_safe_session_name("claude-launcher"));
+        assert!(is_safe_session_name("team_2"));
+        assert!(!is_safe_session_name(""));
+        assert!(!is_safe_session_name("a:b"));
+        assert!(!is_safe_session_name("a.b"));
+        assert!(!is_safe_session_name("a b"));
+        assert!(!is_safe_session_name(&"a".repeat(65)));
+    }
+
+    #[test]
+    fn only_tagged_windows_are_listed() {
+        let out = "work\t@1\tproj\t/srv/work/proj\t1\tsid-1\n\
+                   work\t@2\tbash\t/root\t0\t\n";
+        let windows = parse_tmux_windows(out);
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows[0].window_id, "@1");
+        assert!(windows[0].active);
+        assert_eq!(windows[0].launcher_session, "sid-1");
+    }
+}
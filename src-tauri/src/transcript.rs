@@ -0,0 +1,368 @@
+//! Readable transcripts of IDE sessions: the text that was on screen, without
+//! the escape sequences, cursor moves and redraws a raw capture is full of.
+//!
+//! A session's output is run through a VT parser (vt100) and the screen is
+//! read back every few lines of output, at alternate-screen switches and at
+//! pauses in a recording. Comparing each reading with the previous one tells
+//! how far the text scrolled: lines that went off the top are final and are
+//! written out, lines still on screen may yet change (a prompt being typed, a
+//! spinner) and wait. A full-screen app (the alternate screen) redraws instead
+//! of scrolling, so each of its views is written out once it changes, with
+//! lines already written (its header, its footer) left out.
+//!
+//! `export_transcript` works on a stored or live recording when there is one,
+//! which covers the whole session, and otherwise on a live session's
+//! scrollback.
+
+use std::collections::HashSet;
+use std::fs;
+
+use chrono::{Local, SecondsFormat, TimeZone};
+use serde::Deserialize;
+use tauri::Manager;
+
+use crate::ide::PtySessions;
+use crate::is_safe_path;
+use crate::recording::{self, Cast, RecordingDir};
+
+/// A pause in a recording at least this long (seconds) means the screen has
+/// settled, so it is read back there too.
+const SETTLE_GAP: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptFormat {
+    Text,
+    Markdown,
+}
+
+/// One screen's (primary or alternate) last reading.
+#[derive(Default)]
+struct Pane {
+    rows: Vec<String>,
+    /// Rows at the top of `rows` already written out.
+    written: usize,
+}
+
+/// A run of lines from one screen.
+struct Section {
+    fullscreen: bool,
+    lines: Vec<String>,
+}
+
+pub(crate) struct Transcript {
+    parser: vt100::Parser,
+    alternate: bool,
+    primary: Pane,
+    fullscreen: Pane,
+    /// Lines written in the current full-screen view, which redraws the same
+    /// header and footer over and over.
+    seen: HashSet<String>,
+    sections: Vec<Section>,
+    /// Bytes and line feeds processed since the last reading.
+    pending: (usize, usize),
+}
+
+impl Transcript {
+    pub(crate) fn new(cols: u16, rows: u16) -> Self {
+        Transcript {
+            parser: vt100::Parser::new(rows.max(1), cols.max(1), 0),
+            alternate: false,
+            primary: Pane::default(),
+            fullscreen: Pane::default(),
+            seen: HashSet::new(),
+            sections: Vec::new(),
+            pending: (0, 0),
+        }
+    }
+
+    /// Process output, reading the screen back often enough that no more
+    /// than about half a screen scrolls by unseen.
+    pub(crate) fn feed(&mut self, mut bytes: &[u8]) {
+        let (rows, cols) = self.parser.screen().size();
+        let max_lines = (rows as usize / 2).max(1);
+        let max_bytes = (rows as usize * cols as usize / 4).max(256);
+        while !bytes.is_empty() {
+            let (mut taken, mut lines) = self.pending;
+            let mut cut = bytes.len();
+            for (i, &b) in bytes.iter().enumerate() {
+                // Read the screen just before it switches, so the one being
+                // left is seen as it was.
+                if taken > 0 && b == 0x1b && is_screen_switch(&bytes[i..]) {
+                    cut = i;
+                    break;
+                }
+                taken += 1;
+                if b == b'\n' {
+                    lines += 1;
+                }
+                if lines >= max_lines || taken >= max_bytes {
+                    cut = i + 1;
+                    break;
+                }
+            }
+            self.parser.process(&bytes[..cut]);
+            bytes = &bytes[cut..];
+            if bytes.is_empty() && lines < max_lines && taken < max_bytes {
+                self.pending = (taken, lines);
+            } else {
+                self.snapshot();
+            }
+        }
+    }
+
+    pub(crate) fn resize(&mut self, cols: u16, rows: u16) {
+        self.snapshot();
+        self.parser.screen_mut().set_size(rows.max(1), cols.max(1));
+        // Whatever the resize cut off or rewrapped is not output.
+        let rows = self.read_rows();
+        let pane = self.pane();
+        pane.written = pane.written.min(rows.len());
+        pane.rows = rows;
+    }
+
+    /// Write out what is still on screen and render the transcript.
+    pub(crate) fn finish(mut self, format: TranscriptFormat, title: &str, started_at: Option<&str>) -> String {
+        self.snapshot();
+        self.flush();
+        render(&self.sections, format, title, started_at)
+    }
+
+    fn pane(&mut self) -> &mut Pane {
+        if self.alternate {
+            &mut self.fullscreen
+        } else {
+            &mut self.primary
+        }
+    }
+
+    fn read_rows(&self) -> Vec<String> {
+        let screen = self.parser.screen();
+        let (_, cols) = screen.size();
+        screen.rows(0, cols).map(|r| r.trim_end().to_string()).collect()
+    }
+
+    fn snapshot(&mut self) {
+        self.pending = (0, 0);
+        let alternate = self.parser.screen().alternate_screen();
+        if alternate != self.alternate {
+            // The screen being left is done; a new full-screen view starts
+            // blank.
+            self.flush();
+            self.alternate = alternate;
+            if alternate {
+                self.fullscreen = Pane::default();
+                self.seen.clear();
+            }
+        }
+        let rows = self.read_rows();
+        let pane = self.pane();
+        let gone: Vec<String> = if alternate {
+            // A full-screen app redraws rather than scrolls: once any of its
+            // text is gone, the view it was part of is done. Repeats are
+            // dropped in `push`.
+            if pane.rows.iter().any(|r| !r.is_empty() && !rows.contains(r)) {
+                pane.rows.clone()
+            } else {
+                Vec::new()
+            }
+        } else {
+            let scrolled = scroll_offset(&pane.rows, &rows);
+            let gone = pane.rows.get(pane.written..scrolled).unwrap_or_default().to_vec();
+            pane.written = pane.written.saturating_sub(scrolled);
+            gone
+        };
+        pane.rows = rows;
+        for line in gone {
+            self.push(line);
+        }
+    }
+
+    /// Write out the current screen's rows up to its last non-blank one.
+    fn flush(&mut self) {
+        let pane = self.pane();
+        let end = pane.rows.iter().rposition(|r| !r.is_empty()).map_or(0, |i| i + 1);
+        let rows: Vec<String> = pane.rows.get(pane.written..end).unwrap_or_default().to_vec();
+        pane.written = pane.written.max(end);
+        for line in rows {
+            self.push(line);
+        }
+    }
+
+    fn push(&mut self, line: String) {
+        let fullscreen = self.alternate;
+        if fullscreen && (line.is_empty() || !self.seen.insert(line.clone())) {
+            return;
+        }
+        if self.sections.last().is_none_or(|s| s.fullscreen != fullscreen) {
+            if line.is_empty() {
+                return;
+            }
+            self.sections.push(Section { fullscreen, lines: Vec::new() });
+        }
+        let lines = &mut self.sections.last_mut().expect("pushed above").lines;
+        // Runs of blank lines collapse to one.
+        if line.is_empty() && lines.last().is_some_and(|l| l.is_empty()) {
+            return;
+        }
+        lines.push(line);
+    }
+}
+
+/// `bytes` starts with a switch to or from the alternate screen.
+fn is_screen_switch(bytes: &[u8]) -> bool {
+    [&b"\x1b[?1049"[..], b"\x1b[?1047", b"\x1b[?47"].iter().any(|p| bytes.starts_with(p))
+}
+
+/// How many rows `cur` has scrolled up from `prev`: the least shift at which
+/// most of the text left on screen still lines up. When nothing does (a clear,
+/// a redraw) that is every row of `prev` with text.
+fn scroll_offset(prev: &[String], cur: &[String]) -> usize {
+    let n = prev.len().min(cur.len());
+    for shift in 0..n {
+        let (mut text, mut same) = (0, 0);
+        for (p, c) in prev[shift..n].iter().zip(cur) {
+            if !p.is_empty() {
+                text += 1;
+                if p == c {
+                    same += 1;
+                }
+            }
+        }
+        if text == 0 || same * 2 > text {
+            return shift;
+        }
+    }
+    n
+}
+
+fn render(sections: &[Section], format: TranscriptFormat, title: &str, started_at: Option<&str>) -> String {
+    let body = |s: &Section| {
+        let end = s.lines.iter().rposition(|l| !l.is_empty()).map_or(0, |i| i + 1);
+        s.lines[..end].join("\n")
+    };
+    match format {
+        TranscriptFormat::Text => {
+            let mut out = sections.iter().map(body).collect::<Vec<_>>().join("\n\n");
+            out.push('\n');
+            out
+        }
+        TranscriptFormat::Markdown => {
+            let mut out = format!("# {}\n\n", title);
+            if let Some(started_at) = started_at {
+                out.push_str(&format!("Started {}\n\n", started_at));
+            }
+            for section in sections {
+                let text = body(section);
+                // A fence longer than any run of backticks in the text.
+                let longest = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+                let fence = "`".repeat(longest.max(2) + 1);
+                if section.fullscreen {
+                    out.push_str("*Full-screen view:*\n\n");
+                }
+                out.push_str(&format!("{}text\n{}\n{}\n\n", fence, text, fence));
+            }
+            out
+        }
+    }
+}
+
+fn cast_transcript(cast: &Cast, format: TranscriptFormat, title: &str) -> String {
+    let mut transcript = Transcript::new(cast.header.width, cast.header.height);
+    let mut last = 0.0;
+    for event in &cast.events {
+        if event.0 - last >= SETTLE_GAP {
+            transcript.snapshot();
+        }
+        last = event.0;
+        match event.1.as_str() {
+            "o" => transcript.feed(event.2.as_bytes()),
+            "r" => {
+                if let Some((Ok(cols), Ok(rows))) = event.2.split_once('x').map(|(c, r)| (c.parse(), r.parse())) {
+                    transcript.resize(cols, rows);
+                }
+            }
+            _ => {}
+        }
+    }
+    let started_at = cast
+        .header
+        .timestamp
+        .and_then(|t| Local.timestamp_opt(t, 0).single())
+        .map(|t| t.to_rfc3339_opts(SecondsFormat::Secs, false));
+    transcript.finish(format, cast.header.title.as_deref().unwrap_or(title), started_at.as_deref())
+}
+
+/// Write a transcript of session `id` to `destination` (a path the user
+/// picked in a save dialog). Uses the session's recording when there is one,
+/// else a live session's scrollback.
+#[tauri::command]
+pub async fn export_transcript(
+    app: tauri::AppHandle,
+    id: String,
+    format: TranscriptFormat,
+    destination: String,
+) -> Result<(), String> {
+    if !is_safe_path(&destination) {
+        return Err("Invalid path".into());
+    }
+    let cast_path = recording::cast_path(&app.state::<RecordingDir>().0, &id)?;
+    let live = app.state::<PtySessions>().scrollback(&id);
+    tauri::async_runtime::spawn_blocking(move || {
+        let text = if cast_path.exists() {
+            let text = fs::read_to_string(&cast_path).map_err(|e| format!("Failed to read recording: {}", e))?;
+            cast_transcript(&recording::parse_cast(&text)?, format, &id)
+        } else if let Some((bytes, (cols, rows), cwd)) = live {
+            let mut transcript = Transcript::new(cols, rows);
+            transcript.feed(&bytes);
+            transcript.finish(format, &cwd, None)
+        } else {
+            return Err("No such session or recording".to_string());
+        };
+        fs::write(&destination, text).map_err(|e| format!("Failed to write transcript: {}", e))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(transcript: Transcript) -> String {
+        transcript.finish(TranscriptFormat::Text, "t", None)
+    }
+
+    #[test]
+    fn scrolled_output_and_redraws_come_out_once() {
+        let mut t = Transcript::new(20, 4);
+        for i in 1..=9 {
+            t.feed(format!("line {}\r\n", i).as_bytes());
+        }
+        // A prompt edited in place: only what it ended up as.
+        t.feed(b"> hel");
+        t.feed(b"\x08\x08\x08ok\x1b[K");
+        assert_eq!(text(t), (1..=9).map(|i| format!("line {}\n", i)).collect::<String>() + "> ok\n");
+    }
+
+    #[test]
+    fn fullscreen_views_are_deduplicated_and_kept_in_order() {
+        let mut t = Transcript::new(20, 4);
+        t.feed(b"$ app\r\n");
+        t.feed(b"\x1b[?1049h\x1b[H\x1b[2JTitle\r\nfirst\r\n\r\n-- footer --");
+        t.snapshot();
+        // Redraw with new content under the same title and footer.
+        t.feed(b"\x1b[2J\x1b[HTitle\r\nsecond\r\n\r\n-- footer --");
+        t.feed(b"\x1b[?1049l$ done\r\n");
+        let out = text(t);
+        assert_eq!(out, "$ app\n\nTitle\nfirst\n-- footer --\nsecond\n\n$ done\n");
+
+        let md = render(
+            &[Section { fullscreen: false, lines: vec!["a ``` b".into()] }],
+            TranscriptFormat::Markdown,
+            "demo",
+            None,
+        );
+        assert_eq!(md, "# demo\n\n````text\na ``` b\n````\n\n");
+    }
+}
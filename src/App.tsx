@@ -9,7 +9,14 @@ import IdeView from "./components/ide/IdeView";
 import { useProjects } from "./hooks/useProjects";
 import { useSettings } from "./hooks/useSettings";
 import { useUpdateChecker } from "./hooks/useUpdateChecker";
-import { launchProject } from "./services/launcher";
+import {
+  launchProject,
+  describeLaunchError,
+  isAgentPathError,
+  detectAgentPath,
+} from "./services/launcher";
+import { agentPath } from "./utils/flags";
+import { getAgent } from "./agents/registry";
 import type { Project } from "./types";
 import "./theme/chromeRust.css";
 
@@ -21,6 +28,9 @@ export default function App() {
   const [showAddProject, setShowAddProject] = useState(false);
   const [editingProject, setEditingProject] = useState<string | null>(null);
   const [launchError, setLaunchError] = useState<string | null>(null);
+  // Set when the last launch failed on the agent executable itself, so the
+  // toast can offer to re-run detection for that project's agent.
+  const [redetectFor, setRedetectFor] = useState<Project | null>(null);
 
   // IDE Mode hosts live PTY sessions inside its <Terminal> components, so it
   // must stay MOUNTED across a switch to the Launcher view — unmounting it
@@ -36,12 +46,18 @@ export default function App() {
   async function handleLaunch(project: Project) {
     if (!settingsHook.settings) return;
     setLaunchError(null);
+    setRedetectFor(null);
     try {
       const result = await launchProject(project, settingsHook.settings);
       if (result.success) {
         await projectsHook.updateLastLaunched(project.id);
+      } else if (result.error) {
+        setLaunchError(describeLaunchError(result.error));
+        if (isAgentPathError(result.error, agentPath(settingsHook.settings, project.agentId))) {
+          setRedetectFor(project);
+        }
       } else {
-        setLaunchError(result.error ?? "Launch failed — check logs in Settings");
+        setLaunchError("Launch failed — check logs in Settings");
       }
     } catch (err) {
       const message = err instanceof Error ? err.message : String(err);
@@ -49,6 +65,23 @@ export default function App() {
     }
   }
 
+  async function handleRedetect(project: Project) {
+    if (!settingsHook.settings) return;
+    try {
+      const agentId = getAgent(project.agentId).id;
+      const detected = await detectAgentPath(agentId);
+      await settingsHook.updateSettings({
+        agentPaths: { ...settingsHook.settings.agentPaths, [agentId]: detected },
+      });
+      setLaunchError(null);
+      setRedetectFor(null);
+    } catch (err) {
+      const message = err instanceof Error ? err.message : String(err);
+      setLaunchError(`Agent detection failed: ${message}`);
+      setRedetectFor(null);
+    }
+  }
+
   if (projectsHook.loading || settingsHook.loading) {
     return (
       <div className="min-h-screen bg-gray-900 flex items-center justify-center">
@@ -88,12 +121,25 @@ export default function App() {
           {launchError && (
             <div className="bg-red-900/50 border border-red-700 rounded-lg px-4 py-3 text-sm text-red-200 flex items-center justify-between">
               <span>Launch failed: {launchError}</span>
-              <button
-                onClick={() => setLaunchError(null)}
-                className="text-red-400 hover:text-red-200 ml-4"
-              >
-                Dismiss
-              </button>
+              <div className="flex items-center">
+                {redetectFor && (
+                  <button
+                    onClick={() => handleRedetect(redetectFor)}
+                    className="text-red-400 hover:text-red-200 ml-4"
+                  >
+                    Re-detect agent path
+                  </button>
+                )}
+                <button
+                  onClick={() => {
+                    setLaunchError(null);
+                    setRedetectFor(null);
+                  }}
+                  className="text-red-400 hover:text-red-200 ml-4"
+                >
+                  Dismiss
+                </button>
+              </div>
             </div>
           )}
 
@@ -1,5 +1,7 @@
 import { useState, useEffect, useRef } from "react";
 import { getCurrentWebview } from "@tauri-apps/api/webview";
+import { save } from "@tauri-apps/plugin-dialog";
+import { open } from "@tauri-apps/plugin-shell";
 import { SquareChevronRight, Terminal as TerminalIcon } from "lucide-react";
 import type { Project, GlobalSettings } from "../../types";
 import {
@@ -10,6 +12,7 @@ import {
 import { useSessions } from "../../hooks/useSessions";
 import { launchShell } from "../../services/launcher";
 import { writePty, ensureIdeHooks } from "../../services/ide";
+import { exportTranscript } from "../../services/recording";
 import SessionRail from "./SessionRail";
 import Terminal from "./Terminal";
 import FilesDrawer from "./FilesDrawer";
@@ -71,7 +74,7 @@ export default function IdeView({
     markWorking,
     setLiveModel,
     setSessionNote,
-  } = useSessions();
+  } = useSessions(projects);
   const [now, setNow] = useState(Date.now());
   const [showPicker, setShowPicker] = useState(false);
   const [filesOpen, setFilesOpen] = useState(false);
@@ -158,6 +161,21 @@ export default function IdeView({
     setConfirm(null);
   };
 
+  // A readable transcript of the active session, as far as it has got.
+  const saveTranscript = async () => {
+    if (!active) return;
+    const destination = await save({
+      defaultPath: `${active.title}.md`.replace(/[\\/:*?"<>|]/g, "_"),
+      filters: [
+        { name: "Markdown", extensions: ["md"] },
+        { name: "Text", extensions: ["txt"] },
+      ],
+    });
+    if (!destination) return;
+    const format = destination.endsWith(".txt") ? "text" : "markdown";
+    exportTranscript(active.id, format, destination).catch(() => {});
+  };
+
   const doKill = () => {
     if (active) closeSession(active.id);
     setConfirm(null);
@@ -245,6 +263,18 @@ export default function IdeView({
             >
               ▸ Files
             </button>
+            <button className="tbtn" onClick={saveTranscript} disabled={!active}>
+              Transcript
+            </button>
+            {active?.transcriptPath && (
+              <button
+                className="tbtn"
+                onClick={() => active.transcriptPath && void open(active.transcriptPath).catch(() => {})}
+                title={`Open the agent's own transcript: ${active.transcriptPath}`}
+              >
+                Agent log
+              </button>
+            )}
             <button
               className="tbtn"
               onClick={() => active && setConfirm("clear")}
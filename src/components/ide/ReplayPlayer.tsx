@@ -0,0 +1,148 @@
+import { useEffect, useRef, useState } from "react";
+import { Channel } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { Terminal as XTerm } from "@xterm/xterm";
+import { ArrowLeft, Pause, Play } from "lucide-react";
+import "@xterm/xterm/css/xterm.css";
+import { controlReplay, startReplay } from "../../services/recording";
+import type { RecordingInfo, ReplayState } from "../../types";
+
+const SPEEDS = [0.5, 1, 2, 4, 8];
+
+/** Pauses longer than this (seconds) are shortened to it. */
+const IDLE_LIMIT = 2;
+
+function formatTime(seconds: number): string {
+  const s = Math.floor(seconds);
+  return `${Math.floor(s / 60)}:${String(s % 60).padStart(2, "0")}`;
+}
+
+interface ReplayPlayerProps {
+  recording: RecordingInfo;
+  onClose: () => void;
+}
+
+/**
+ * Plays a stored recording in a read-only xterm. The backend paces the output
+ * and streams it exactly as a live session's; this only renders it and sends
+ * pause/seek/speed controls.
+ */
+export default function ReplayPlayer({ recording, onClose }: ReplayPlayerProps) {
+  const hostRef = useRef<HTMLDivElement>(null);
+  const replayIdRef = useRef<number | null>(null);
+  const [state, setState] = useState<ReplayState | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!hostRef.current) return;
+    const term = new XTerm({
+      fontSize: 12,
+      disableStdin: true,
+      cursorBlink: false,
+      cols: recording.width,
+      rows: recording.height,
+      theme: { background: "#030712" },
+    });
+    term.open(hostRef.current);
+
+    let disposed = false;
+    let unlisten: (() => void) | null = null;
+    const onOutput = new Channel<number[]>();
+    onOutput.onmessage = (msg) => term.write(new Uint8Array(msg));
+
+    listen<ReplayState>("replay-state", (e) => {
+      if (e.payload.replayId !== replayIdRef.current) return;
+      if (e.payload.cols !== term.cols || e.payload.rows !== term.rows) {
+        term.resize(e.payload.cols, e.payload.rows);
+      }
+      setState(e.payload);
+    }).then((u) => {
+      if (disposed) u();
+      else unlisten = u;
+    });
+
+    startReplay(recording.id, onOutput, { idleLimit: IDLE_LIMIT })
+      .then((info) => {
+        if (disposed) {
+          controlReplay(info.replayId, { action: "stop" }).catch(() => {});
+          return;
+        }
+        replayIdRef.current = info.replayId;
+        term.resize(info.cols, info.rows);
+      })
+      .catch((e) => setError(String(e)));
+
+    return () => {
+      disposed = true;
+      unlisten?.();
+      if (replayIdRef.current !== null) {
+        controlReplay(replayIdRef.current, { action: "stop" }).catch(() => {});
+      }
+      term.dispose();
+    };
+  }, [recording.id, recording.width, recording.height]);
+
+  function control(c: Parameters<typeof controlReplay>[1]) {
+    if (replayIdRef.current === null) return;
+    controlReplay(replayIdRef.current, c).catch((e) => setError(String(e)));
+  }
+
+  const duration = state?.duration ?? recording.duration;
+  const playing = state !== null && !state.paused && !state.finished;
+
+  return (
+    <div>
+      <div className="flex items-center gap-2 mb-2">
+        <button
+          onClick={onClose}
+          className="p-1.5 rounded text-gray-400 hover:text-white hover:bg-gray-700 transition-colors"
+          title="Back to recordings"
+        >
+          <ArrowLeft size={14} />
+        </button>
+        <span className="text-sm text-white truncate">{recording.title ?? recording.id}</span>
+      </div>
+
+      {error && <p className="text-xs text-red-400 mb-2">{error}</p>}
+
+      <div className="bg-gray-950 border border-gray-700 rounded-lg p-2 overflow-auto max-h-80">
+        <div ref={hostRef} />
+      </div>
+
+      <div className="flex items-center gap-2 mt-2 text-xs text-gray-400">
+        <button
+          onClick={() => {
+            if (state?.finished) control({ action: "seek", time: 0 });
+            control({ action: playing ? "pause" : "resume" });
+          }}
+          className="p-1.5 rounded text-gray-300 hover:text-white hover:bg-gray-700 transition-colors"
+          title={playing ? "Pause" : "Play"}
+        >
+          {playing ? <Pause size={14} /> : <Play size={14} />}
+        </button>
+        <span className="tabular-nums w-10 text-right">{formatTime(state?.time ?? 0)}</span>
+        <input
+          type="range"
+          min={0}
+          max={duration}
+          step={0.1}
+          value={state?.time ?? 0}
+          onChange={(e) => control({ action: "seek", time: Number(e.target.value) })}
+          className="flex-1 accent-amber-500"
+        />
+        <span className="tabular-nums w-10">{formatTime(duration)}</span>
+        <select
+          value={state?.speed ?? 1}
+          onChange={(e) => control({ action: "speed", speed: Number(e.target.value) })}
+          className="bg-gray-900 border border-gray-600 rounded px-1 py-0.5 text-xs text-white focus:outline-none"
+        >
+          {SPEEDS.map((s) => (
+            <option key={s} value={s}>
+              {s}×
+            </option>
+          ))}
+        </select>
+      </div>
+    </div>
+  );
+}
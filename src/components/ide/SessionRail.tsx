@@ -58,7 +58,7 @@ function stateGlyph(s: Session): string {
 function statusLabel(s: Session): string {
   switch (s.status) {
     case "waiting":
-      return "Waiting on user";
+      return s.attention ?? "Waiting on user";
     case "complete":
       return "Complete";
     case "exited":
@@ -66,7 +66,7 @@ function statusLabel(s: Session): string {
     case "starting":
       return "Starting";
     case "working":
-      return "Working";
+      return s.activity ? `Working · ${s.activity}` : "Working";
     default:
       return "Idle";
   }
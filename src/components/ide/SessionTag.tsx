@@ -76,7 +76,11 @@ export default function SessionTag({ session, active, now, onClick, onSetNote }:
   let bar: { cls: string; label: React.ReactNode; t?: string };
   switch (session.status) {
     case "waiting":
-      bar = { cls: "need", label: "⚠ Waiting on User", t: mmss(now - session.lastActivityAt) };
+      bar = {
+        cls: "need",
+        label: `⚠ ${session.attention ?? "Waiting on User"}`,
+        t: mmss(now - session.lastActivityAt),
+      };
       break;
     case "complete":
       bar = { cls: "done", label: "✓ Complete", t: idle(now - session.lastActivityAt) };
@@ -91,7 +95,7 @@ export default function SessionTag({ session, active, now, onClick, onSetNote }:
         label: (
           <>
             <span className="pulse-dot" />
-            {session.status === "starting" ? "Starting…" : "Working…"}
+            {session.status === "starting" ? "Starting…" : session.activity ?? "Working…"}
           </>
         ),
         t: mmss(now - session.startedAt),
@@ -164,7 +168,9 @@ export default function SessionTag({ session, active, now, onClick, onSetNote }:
         </div>
       )}
       <div className={`bar ${bar.cls}`}>
-        <span className="lbl">{bar.label}</span>
+        <span className="lbl" title={typeof bar.label === "string" ? bar.label : undefined}>
+          {bar.label}
+        </span>
         {bar.t && <span className="t">{bar.t}</span>}
       </div>
     </div>
@@ -6,7 +6,7 @@ import { WebglAddon } from "@xterm/addon-webgl";
 import { Unicode11Addon } from "@xterm/addon-unicode11";
 import "@xterm/xterm/css/xterm.css";
 import { registerTerminalLinks } from "./terminalLinks";
-import type { Project, GlobalSettings, Session } from "../../types";
+import type { Project, GlobalSettings, Session, LaunchError } from "../../types";
 import { IDE_FONT_SIZE_DEFAULT } from "../../types";
 import { getAgent } from "../../agents/registry";
 import {
@@ -15,11 +15,13 @@ import {
 } from "@tauri-apps/plugin-clipboard-manager";
 import {
   spawnPty,
+  attachPty,
   writePty,
   resizePty,
   killPty,
   getOsBuild,
 } from "../../services/ide";
+import { describeLaunchError } from "../../services/launcher";
 
 interface TerminalProps {
   session: Session;
@@ -584,17 +586,31 @@ export default function Terminal({
           term.refresh(0, term.rows - 1);
         }, 250);
 
-        spawnPty(
-          session.id,
-          project,
-          settings,
-          session.flags,
-          term.cols,
-          term.rows,
-          onOutput
-        ).catch((err) => {
-          term?.write(`\r\n\x1b[31m[launch failed: ${String(err)}]\x1b[0m\r\n`);
-        });
+        if (session.reattach) {
+          // The PTY outlived the webview: replay its scrollback, then bring it
+          // to this terminal's size (which repaints a fullscreen TUI).
+          attachPty(session.id, onOutput)
+            .then(() => resizePty(session.id, term?.cols ?? FALLBACK_COLS, term?.rows ?? FALLBACK_ROWS))
+            .catch(() => {
+              term?.write("\r\n\x1b[31m[session is no longer running]\x1b[0m\r\n");
+            });
+        } else {
+          spawnPty(
+            session.id,
+            project,
+            settings,
+            session.flags,
+            term.cols,
+            term.rows,
+            onOutput
+          ).catch((err) => {
+            const message =
+              typeof err === "object" && err !== null && "kind" in err
+                ? describeLaunchError(err as LaunchError)
+                : String(err);
+            term?.write(`\r\n\x1b[31m[launch failed: ${message}]\x1b[0m\r\n`);
+          });
+        }
 
         dataSub = term.onData((data) => {
           onActivity(session.id);
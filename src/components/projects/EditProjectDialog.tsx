@@ -1,4 +1,4 @@
-import { useState } from "react";
+import { useEffect, useState } from "react";
 import { open } from "@tauri-apps/plugin-dialog";
 import { FolderOpen } from "lucide-react";
 import Modal from "../shared/Modal";
@@ -7,12 +7,14 @@ import ModelField from "./ModelField";
 import { agentGlobalFlags, agentCustomFlags } from "../../utils/flags";
 import { PROJECT_COLORS } from "../../utils/colors";
 import { ALL_AGENTS, getAgent } from "../../agents/registry";
+import { listLaunchProfiles, listSecrets } from "../../services/launcher";
 import type {
   Project,
   GlobalSettings,
   FlagOverrides,
   IdeRenderer,
   AgentId,
+  LaunchProfile,
 } from "../../types";
 
 type TriState = "global" | "on" | "off";
@@ -23,6 +25,24 @@ function getTriState(flagName: string, overrides: FlagOverrides): TriState {
   return val ? "on" : "off";
 }
 
+/** `KEY=value` per line, as edited in the Environment field. */
+function formatEnv(env: Record<string, string> | undefined): string {
+  return Object.entries(env ?? {})
+    .map(([k, v]) => `${k}=${v}`)
+    .join("\n");
+}
+
+/** Inverse of `formatEnv`. Blank lines and lines without `=` are dropped. */
+function parseEnv(text: string): Record<string, string> {
+  const env: Record<string, string> = {};
+  for (const line of text.split("\n")) {
+    const eq = line.indexOf("=");
+    if (eq <= 0) continue;
+    env[line.slice(0, eq).trim()] = line.slice(eq + 1);
+  }
+  return env;
+}
+
 function cycleTriState(current: TriState): TriState {
   if (current === "global") return "on";
   if (current === "on") return "off";
@@ -46,6 +66,9 @@ interface EditProjectDialogProps {
       modelInTitle?: boolean;
       model?: string;
       ideRenderer?: IdeRenderer;
+      launchProfile?: string;
+      env?: Record<string, string>;
+      secretEnv?: Record<string, string>;
     }
   ) => void;
   onClose: () => void;
@@ -77,7 +100,21 @@ export default function EditProjectDialog({
     project.ideRenderer ?? "global"
   );
 
+  const [launchProfile, setLaunchProfile] = useState(project.launchProfile ?? "");
+  const [envText, setEnvText] = useState(formatEnv(project.env));
+  const [secretEnvText, setSecretEnvText] = useState(formatEnv(project.secretEnv));
+  const [profiles, setProfiles] = useState<LaunchProfile[]>([]);
+  const [secretNames, setSecretNames] = useState<string[]>([]);
+  useEffect(() => {
+    listLaunchProfiles().then(setProfiles).catch(() => setProfiles([]));
+    listSecrets().then(setSecretNames).catch(() => setSecretNames([]));
+  }, []);
+
   const agent = getAgent(agentId);
+  // The backend rejects a Claude-only profile for any other agent.
+  const availableProfiles = profiles.filter(
+    (p) => !p.claudeOnly || agentId === "claude"
+  );
 
   const allFlags = [
     ...agentGlobalFlags(settings, agentId).map((gf) => ({
@@ -103,6 +140,7 @@ export default function EditProjectDialog({
     setOverrides({});
     setModel(nextAgent.defaultModel);
     if (!nextAgent.capabilities.modelInTitle) setModelInTitle(false);
+    if (next !== "claude") setLaunchProfile("");
   }
 
   function handleToggle(flagName: string) {
@@ -127,6 +165,8 @@ export default function EditProjectDialog({
   function handleSubmit(e: React.FormEvent) {
     e.preventDefault();
     if (!path.trim()) return;
+    const env = parseEnv(envText);
+    const secretEnv = parseEnv(secretEnvText);
     onSave(project.id, {
       name: name.trim() || path.split(/[/\\]/).filter(Boolean).pop() || path,
       path: path.trim(),
@@ -139,6 +179,9 @@ export default function EditProjectDialog({
       modelInTitle,
       model,
       ideRenderer: ideRenderer === "global" ? undefined : ideRenderer,
+      launchProfile: launchProfile || undefined,
+      env: Object.keys(env).length > 0 ? env : undefined,
+      secretEnv: Object.keys(secretEnv).length > 0 ? secretEnv : undefined,
     });
     onClose();
   }
@@ -295,6 +338,73 @@ export default function EditProjectDialog({
           </p>
         </div>
 
+        {/* Launch Profile */}
+        {availableProfiles.length > 0 && (
+          <div>
+            <label className="block text-sm font-medium text-gray-300 mb-1">
+              Launch Profile
+            </label>
+            <select
+              value={launchProfile}
+              onChange={(e) => setLaunchProfile(e.target.value)}
+              className="w-full bg-gray-900 border border-gray-600 rounded-lg px-3 py-2 text-sm text-white
+                         focus:outline-none focus:border-amber-500 focus:ring-1 focus:ring-amber-500"
+            >
+              <option value="">None</option>
+              {availableProfiles.map((p) => (
+                <option key={p.name} value={p.name}>
+                  {p.name}
+                </option>
+              ))}
+            </select>
+            <p className="text-xs text-gray-500 mt-1">
+              {availableProfiles.find((p) => p.name === launchProfile)?.description ??
+                "Extra flags, environment and renderer applied on every launch of this project."}
+            </p>
+          </div>
+        )}
+
+        {/* Environment */}
+        <div>
+          <label className="block text-sm font-medium text-gray-300 mb-1">
+            Environment
+          </label>
+          <p className="text-xs text-gray-500 mb-2">
+            One <code>NAME=value</code> per line, set for {agent.label} only.
+            Values that look like keys or tokens are kept out of the log.
+          </p>
+          <textarea
+            value={envText}
+            onChange={(e) => setEnvText(e.target.value)}
+            placeholder="e.g. ANTHROPIC_BASE_URL=https://proxy.example/v1"
+            rows={2}
+            spellCheck={false}
+            className="w-full bg-gray-900 border border-gray-600 rounded-lg px-3 py-2 text-sm text-white font-mono
+                       placeholder-gray-500 focus:border-amber-500 focus:ring-1 focus:ring-amber-500 resize-y"
+          />
+        </div>
+
+        {/* Secrets */}
+        <div>
+          <label className="block text-sm font-medium text-gray-300 mb-1">
+            Secrets
+          </label>
+          <p className="text-xs text-gray-500 mb-2">
+            One <code>NAME=secret-name</code> per line. The value comes from the
+            secret store (Settings → Secrets) at launch and is never logged.
+            {secretNames.length > 0 && <> Stored: {secretNames.join(", ")}.</>}
+          </p>
+          <textarea
+            value={secretEnvText}
+            onChange={(e) => setSecretEnvText(e.target.value)}
+            placeholder="e.g. ANTHROPIC_API_KEY=anthropic-work"
+            rows={2}
+            spellCheck={false}
+            className="w-full bg-gray-900 border border-gray-600 rounded-lg px-3 py-2 text-sm text-white font-mono
+                       placeholder-gray-500 focus:border-amber-500 focus:ring-1 focus:ring-amber-500 resize-y"
+          />
+        </div>
+
         {/* Pre-Launch Command */}
         <div>
           <label className="block text-sm font-medium text-gray-300 mb-1">
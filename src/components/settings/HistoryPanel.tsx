@@ -0,0 +1,128 @@
+import { useCallback, useEffect, useState } from "react";
+import { RefreshCw } from "lucide-react";
+import { launchStats } from "../../services/history";
+import { relativeTime } from "../../utils/dateFormat";
+import type { HistoryStats, ProjectStats } from "../../types";
+
+const RANGES: { label: string; days: number | null }[] = [
+  { label: "All time", days: null },
+  { label: "Last 7 days", days: 7 },
+  { label: "Last 30 days", days: 30 },
+  { label: "Last 90 days", days: 90 },
+];
+
+function projectName(path: string): string {
+  return path.split(/[/\\]/).filter(Boolean).pop() ?? path;
+}
+
+function formatHours(hours: number): string {
+  return hours < 1 ? `${Math.round(hours * 60)}m` : `${hours.toFixed(1)}h`;
+}
+
+function formatRate(rate: number): string {
+  return `${Math.round(rate * 100)}%`;
+}
+
+function StatsRow({ stats, label }: { stats: ProjectStats; label?: string }) {
+  return (
+    <tr className="border-b border-gray-800 last:border-0">
+      <td className="py-1.5 pr-3 text-gray-300 truncate max-w-[12rem]" title={stats.project}>
+        {label ?? projectName(stats.project)}
+      </td>
+      <td className="py-1.5 pr-3 text-right">{stats.launches}</td>
+      <td className={`py-1.5 pr-3 text-right ${stats.failures > 0 ? "text-red-400" : ""}`}>
+        {formatRate(stats.failureRate)}
+      </td>
+      <td className="py-1.5 pr-3 text-right">{formatHours(stats.agentHours)}</td>
+      <td className="py-1.5 pr-3 text-right">
+        {stats.completions} / {stats.waits}
+      </td>
+      <td className="py-1.5 text-right text-gray-500">{relativeTime(stats.lastLaunchedAt)}</td>
+    </tr>
+  );
+}
+
+/** The History tab: per-project launch counts, failure rates and agent hours. */
+export default function HistoryPanel() {
+  const [rangeDays, setRangeDays] = useState<number | null>(null);
+  const [stats, setStats] = useState<HistoryStats | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const load = useCallback(async () => {
+    setLoading(true);
+    try {
+      const since =
+        rangeDays === null
+          ? undefined
+          : new Date(Date.now() - rangeDays * 24 * 60 * 60 * 1000).toISOString();
+      setStats(await launchStats(since));
+      setError(null);
+    } catch (e) {
+      setError(String(e));
+    }
+    setLoading(false);
+  }, [rangeDays]);
+
+  useEffect(() => {
+    load();
+  }, [load]);
+
+  return (
+    <div>
+      <div className="flex items-center justify-between mb-2">
+        <select
+          value={rangeDays ?? ""}
+          onChange={(e) => setRangeDays(e.target.value === "" ? null : Number(e.target.value))}
+          className="bg-gray-900 border border-gray-600 rounded-lg px-2 py-1.5 text-xs text-white focus:outline-none focus:border-amber-500 focus:ring-1 focus:ring-amber-500"
+        >
+          {RANGES.map((r) => (
+            <option key={r.label} value={r.days ?? ""}>
+              {r.label}
+            </option>
+          ))}
+        </select>
+        <button
+          onClick={load}
+          disabled={loading}
+          className="flex items-center gap-1 text-xs text-amber-400 hover:text-amber-300 transition-colors"
+        >
+          <RefreshCw size={12} className={loading ? "animate-spin" : ""} />
+          Refresh
+        </button>
+      </div>
+
+      {error ? (
+        <p className="text-xs text-red-400">{error}</p>
+      ) : !stats || stats.projects.length === 0 ? (
+        <p className="text-xs text-gray-500">{loading ? "Loading…" : "No launches recorded yet."}</p>
+      ) : (
+        <table className="w-full text-xs text-gray-400">
+          <thead>
+            <tr className="text-left text-gray-500 border-b border-gray-700">
+              <th className="py-1.5 pr-3 font-medium">Project</th>
+              <th className="py-1.5 pr-3 font-medium text-right">Launches</th>
+              <th className="py-1.5 pr-3 font-medium text-right">Failed</th>
+              <th className="py-1.5 pr-3 font-medium text-right" title="IDE Mode sessions only">
+                Agent time
+              </th>
+              <th className="py-1.5 pr-3 font-medium text-right" title="Times a session finished a turn / asked for input">
+                Done / Waiting
+              </th>
+              <th className="py-1.5 font-medium text-right">Last</th>
+            </tr>
+          </thead>
+          <tbody>
+            {stats.projects.map((p) => (
+              <StatsRow key={p.project} stats={p} />
+            ))}
+            <StatsRow stats={stats.total} label="All projects" />
+          </tbody>
+        </table>
+      )}
+      <p className="text-xs text-gray-500 mt-2">
+        Agent time counts IDE Mode sessions only; a terminal launch is not tracked after it starts.
+      </p>
+    </div>
+  );
+}
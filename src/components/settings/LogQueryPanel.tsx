@@ -0,0 +1,255 @@
+import { useCallback, useEffect, useState } from "react";
+import { Radio, RefreshCw } from "lucide-react";
+import { queryLog, subscribeLog } from "../../services/log";
+import type { LogEntry, LogEventKind, LogPage } from "../../types";
+
+const PAGE_SIZE = 100;
+
+const EVENT_LABELS: Record<LogEventKind, string> = {
+  launch: "Launches",
+  ptySpawn: "IDE session starts",
+  ptyExit: "IDE session exits",
+  sessionState: "Session state",
+  rejectedPing: "Rejected hook pings",
+  message: "Messages",
+};
+
+/** "Any time" or a window ending now, in days. */
+const RANGES: { label: string; days: number | null }[] = [
+  { label: "Any time", days: null },
+  { label: "Last 24 hours", days: 1 },
+  { label: "Last 7 days", days: 7 },
+  { label: "Last 30 days", days: 30 },
+];
+
+const selectClass =
+  "bg-gray-900 border border-gray-600 rounded-lg px-2 py-1.5 text-xs text-white " +
+  "focus:outline-none focus:border-amber-500 focus:ring-1 focus:ring-amber-500";
+
+function levelClass(level: string): string {
+  switch (level.toUpperCase()) {
+    case "ERROR":
+      return "text-red-400";
+    case "WARN":
+      return "text-amber-400";
+    default:
+      return "text-gray-500";
+  }
+}
+
+/**
+ * Client-side match for live entries; the backend filters them by level only.
+ * They are new, so every time range includes them.
+ */
+function matchesFilters(entry: LogEntry, event: LogEventKind | "", project: string): boolean {
+  if (event && entry.event !== event) return false;
+  const trim = (p: string) => p.replace(/[/\\]+$/, "");
+  return !project.trim() || trim(entry.project ?? "") === trim(project.trim());
+}
+
+function formatTime(timestamp: string): string {
+  const d = new Date(timestamp);
+  return Number.isNaN(d.getTime()) ? timestamp : d.toLocaleString();
+}
+
+function EntryRow({ entry }: { entry: LogEntry }) {
+  const context = [
+    entry.project,
+    entry.agent,
+    entry.sessionId && `session ${entry.sessionId.slice(0, 8)}`,
+    entry.durationMs !== undefined && `${(entry.durationMs / 1000).toFixed(1)}s`,
+  ].filter(Boolean);
+  return (
+    <div className="py-1 border-b border-gray-800 last:border-0">
+      <div className="flex gap-2">
+        <span className="text-gray-500 shrink-0">{formatTime(entry.timestamp)}</span>
+        <span className={`shrink-0 ${levelClass(entry.level)}`}>{entry.level}</span>
+        <span className="text-gray-300 break-all">{entry.message}</span>
+      </div>
+      {context.length > 0 && (
+        <div className="text-gray-500 pl-4 break-all">{context.join(" · ")}</div>
+      )}
+    </div>
+  );
+}
+
+/** The Logs tab's filterable view of the structured launch log. */
+export default function LogQueryPanel() {
+  const [level, setLevel] = useState("");
+  const [event, setEvent] = useState<LogEventKind | "">("");
+  const [project, setProject] = useState("");
+  const [rangeDays, setRangeDays] = useState<number | null>(null);
+  const [offset, setOffset] = useState(0);
+  const [page, setPage] = useState<LogPage | null>(null);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [live, setLive] = useState(false);
+
+  const load = useCallback(async () => {
+    setLoading(true);
+    try {
+      const since =
+        rangeDays === null
+          ? undefined
+          : new Date(Date.now() - rangeDays * 24 * 60 * 60 * 1000).toISOString();
+      setPage(
+        await queryLog({
+          levels: level ? [level] : [],
+          events: event ? [event] : [],
+          project: project.trim() || undefined,
+          since,
+          offset,
+          limit: PAGE_SIZE,
+        })
+      );
+      setError(null);
+    } catch (e) {
+      setError(String(e));
+    }
+    setLoading(false);
+  }, [level, event, project, rangeDays, offset]);
+
+  useEffect(() => {
+    load();
+  }, [load]);
+
+  // Live: new entries are pushed by the backend and prepended to the first
+  // page, instead of re-querying the whole log.
+  useEffect(() => {
+    if (!live || offset !== 0) return;
+    let unsubscribe: (() => void) | null = null;
+    let cancelled = false;
+    subscribeLog((entry) => {
+      if (!matchesFilters(entry, event, project)) return;
+      setPage((p) => ({
+        entries: [entry, ...(p?.entries ?? [])].slice(0, PAGE_SIZE),
+        total: (p?.total ?? 0) + 1,
+      }));
+    }, level || undefined)
+      .then((unsub) => {
+        if (cancelled) unsub();
+        else unsubscribe = unsub;
+      })
+      .catch((e) => setError(String(e)));
+    return () => {
+      cancelled = true;
+      unsubscribe?.();
+    };
+  }, [live, offset, level, event, project]);
+
+  // Any filter change starts again from the newest entry.
+  function filterSetter<T>(set: (v: T) => void) {
+    return (v: T) => {
+      set(v);
+      setOffset(0);
+    };
+  }
+
+  const total = page?.total ?? 0;
+
+  return (
+    <div>
+      <div className="flex items-center justify-between mb-2">
+        <h3 className="text-sm font-medium text-gray-300">Log Entries</h3>
+        <div className="flex items-center gap-3">
+          <button
+            onClick={() => setLive(!live)}
+            className={`flex items-center gap-1 text-xs transition-colors ${
+              live ? "text-green-400 hover:text-green-300" : "text-gray-400 hover:text-gray-200"
+            }`}
+            title="Show new entries as they are written"
+          >
+            <Radio size={12} className={live ? "animate-pulse" : ""} />
+            Live
+          </button>
+          <button
+            onClick={load}
+            disabled={loading}
+            className="flex items-center gap-1 text-xs text-amber-400 hover:text-amber-300 transition-colors"
+          >
+            <RefreshCw size={12} className={loading ? "animate-spin" : ""} />
+            Refresh
+          </button>
+        </div>
+      </div>
+
+      <div className="flex flex-wrap gap-2 mb-2">
+        <select
+          value={level}
+          onChange={(e) => filterSetter(setLevel)(e.target.value)}
+          className={selectClass}
+        >
+          <option value="">All levels</option>
+          <option value="ERROR">Errors</option>
+          <option value="WARN">Warnings</option>
+          <option value="INFO">Info</option>
+        </select>
+        <select
+          value={event}
+          onChange={(e) => filterSetter(setEvent)(e.target.value as LogEventKind | "")}
+          className={selectClass}
+        >
+          <option value="">All events</option>
+          {(Object.keys(EVENT_LABELS) as LogEventKind[]).map((k) => (
+            <option key={k} value={k}>
+              {EVENT_LABELS[k]}
+            </option>
+          ))}
+        </select>
+        <select
+          value={rangeDays ?? ""}
+          onChange={(e) =>
+            filterSetter(setRangeDays)(e.target.value === "" ? null : Number(e.target.value))
+          }
+          className={selectClass}
+        >
+          {RANGES.map((r) => (
+            <option key={r.label} value={r.days ?? ""}>
+              {r.label}
+            </option>
+          ))}
+        </select>
+        <input
+          type="text"
+          value={project}
+          onChange={(e) => filterSetter(setProject)(e.target.value)}
+          placeholder="Project path"
+          spellCheck={false}
+          className={`${selectClass} flex-1 min-w-[8rem] font-mono placeholder-gray-500`}
+        />
+      </div>
+
+      <div className="bg-gray-950 border border-gray-700 rounded-lg p-3 text-xs font-mono overflow-auto max-h-64">
+        {error ? (
+          <p className="text-red-400">{error}</p>
+        ) : page && page.entries.length > 0 ? (
+          page.entries.map((entry, i) => <EntryRow key={`${offset + i}`} entry={entry} />)
+        ) : (
+          <p className="text-gray-500">{loading ? "Loading…" : "No matching log entries."}</p>
+        )}
+      </div>
+
+      {total > PAGE_SIZE && (
+        <div className="flex items-center justify-between mt-2 text-xs text-gray-400">
+          <button
+            onClick={() => setOffset(Math.max(0, offset - PAGE_SIZE))}
+            disabled={offset === 0}
+            className="hover:text-gray-200 disabled:opacity-40"
+          >
+            Newer
+          </button>
+          <span>
+            {offset + 1}–{Math.min(offset + PAGE_SIZE, total)} of {total}
+          </span>
+          <button
+            onClick={() => setOffset(offset + PAGE_SIZE)}
+            disabled={offset + PAGE_SIZE >= total}
+            className="hover:text-gray-200 disabled:opacity-40"
+          >
+            Older
+          </button>
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,172 @@
+import { useCallback, useEffect, useState } from "react";
+import { save } from "@tauri-apps/plugin-dialog";
+import { Download, FileText, Play, RefreshCw, Trash2 } from "lucide-react";
+import ReplayPlayer from "../ide/ReplayPlayer";
+import { deleteRecording, exportRecording, exportTranscript, listRecordings } from "../../services/recording";
+import { relativeTime } from "../../utils/dateFormat";
+import type { RecordingInfo } from "../../types";
+
+function formatDuration(seconds: number): string {
+  const s = Math.round(seconds);
+  const h = Math.floor(s / 3600);
+  const m = Math.floor((s % 3600) / 60);
+  const pad = (n: number) => String(n).padStart(2, "0");
+  return h > 0 ? `${h}:${pad(m)}:${pad(s % 60)}` : `${m}:${pad(s % 60)}`;
+}
+
+function formatSize(bytes: number): string {
+  if (bytes < 1024) return `${bytes} B`;
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(0)} KB`;
+  return `${(bytes / 1024 / 1024).toFixed(1)} MB`;
+}
+
+/**
+ * Stored IDE session recordings, with in-app playback, export (to a `.cast`
+ * file asciinema can play, or a readable transcript) and delete. Recording itself is switched on in the
+ * General tab.
+ */
+export default function RecordingsPanel() {
+  const [recordings, setRecordings] = useState<RecordingInfo[]>([]);
+  const [loading, setLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+  const [playing, setPlaying] = useState<RecordingInfo | null>(null);
+
+  const refresh = useCallback(async () => {
+    setLoading(true);
+    try {
+      setRecordings(await listRecordings());
+      setError(null);
+    } catch (e) {
+      setError(String(e));
+    }
+    setLoading(false);
+  }, []);
+
+  useEffect(() => {
+    refresh();
+  }, [refresh]);
+
+  async function handleExport(rec: RecordingInfo) {
+    const destination = await save({
+      defaultPath: `${rec.title ?? rec.id}.cast`.replace(/[\\/:*?"<>|]/g, "_"),
+      filters: [{ name: "asciicast", extensions: ["cast"] }],
+    });
+    if (!destination) return;
+    try {
+      await exportRecording(rec.id, destination);
+      setError(null);
+    } catch (e) {
+      setError(String(e));
+    }
+  }
+
+  async function handleTranscript(rec: RecordingInfo) {
+    const destination = await save({
+      defaultPath: `${rec.title ?? rec.id}.md`.replace(/[\\/:*?"<>|]/g, "_"),
+      filters: [
+        { name: "Markdown", extensions: ["md"] },
+        { name: "Text", extensions: ["txt"] },
+      ],
+    });
+    if (!destination) return;
+    try {
+      await exportTranscript(rec.id, destination.endsWith(".txt") ? "text" : "markdown", destination);
+      setError(null);
+    } catch (e) {
+      setError(String(e));
+    }
+  }
+
+  async function handleDelete(rec: RecordingInfo) {
+    try {
+      await deleteRecording(rec.id);
+      setError(null);
+      refresh();
+    } catch (e) {
+      setError(String(e));
+    }
+  }
+
+  if (playing) {
+    return <ReplayPlayer recording={playing} onClose={() => setPlaying(null)} />;
+  }
+
+  return (
+    <div>
+      <div className="flex items-center justify-between mb-2">
+        <h3 className="text-sm font-medium text-gray-300">Session Recordings</h3>
+        <button
+          onClick={refresh}
+          disabled={loading}
+          className="flex items-center gap-1 text-xs text-amber-400 hover:text-amber-300 transition-colors"
+        >
+          <RefreshCw size={12} className={loading ? "animate-spin" : ""} />
+          Refresh
+        </button>
+      </div>
+
+      {error && <p className="text-xs text-red-400 mb-2">{error}</p>}
+
+      {recordings.length === 0 ? (
+        <p className="text-xs text-gray-500">
+          {loading
+            ? "Loading…"
+            : "No recordings. Turn on “Record IDE sessions” in the General tab to record new sessions."}
+        </p>
+      ) : (
+        <div className="space-y-1">
+          {recordings.map((rec) => (
+            <div
+              key={rec.id}
+              className="flex items-center gap-2 bg-gray-900 border border-gray-700 rounded-lg px-3 py-2"
+            >
+              <div className="flex-1 min-w-0">
+                <div className="text-sm text-white truncate flex items-center gap-2">
+                  {rec.title ?? rec.id}
+                  {rec.live && (
+                    <span className="text-[10px] font-medium uppercase tracking-wide px-1.5 py-0.5 rounded bg-red-600/30 text-red-300">
+                      Recording
+                    </span>
+                  )}
+                </div>
+                <div className="text-xs text-gray-500">
+                  {relativeTime(rec.startedAt)} · {formatDuration(rec.duration)} · {rec.width}×{rec.height} ·{" "}
+                  {formatSize(rec.sizeBytes)}
+                </div>
+              </div>
+              <button
+                onClick={() => setPlaying(rec)}
+                className="p-1.5 rounded text-gray-500 hover:text-green-400 hover:bg-gray-700 transition-colors"
+                title="Play"
+              >
+                <Play size={14} />
+              </button>
+              <button
+                onClick={() => handleExport(rec)}
+                className="p-1.5 rounded text-gray-500 hover:text-blue-400 hover:bg-gray-700 transition-colors"
+                title="Export .cast file"
+              >
+                <Download size={14} />
+              </button>
+              <button
+                onClick={() => handleTranscript(rec)}
+                className="p-1.5 rounded text-gray-500 hover:text-blue-400 hover:bg-gray-700 transition-colors"
+                title="Save transcript"
+              >
+                <FileText size={14} />
+              </button>
+              <button
+                onClick={() => handleDelete(rec)}
+                disabled={rec.live}
+                className="p-1.5 rounded text-gray-500 hover:text-red-400 hover:bg-gray-700 transition-colors disabled:opacity-40"
+                title={rec.live ? "Still recording" : "Delete recording"}
+              >
+                <Trash2 size={14} />
+              </button>
+            </div>
+          ))}
+        </div>
+      )}
+    </div>
+  );
+}
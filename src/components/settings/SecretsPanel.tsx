@@ -0,0 +1,107 @@
+import { useEffect, useState } from "react";
+import { Plus, X } from "lucide-react";
+import { listSecrets, setSecret, deleteSecret } from "../../services/launcher";
+
+/**
+ * Names in the secret store, with add/replace and delete. Values are
+ * write-only: the backend never sends one back, so there is nothing to show.
+ */
+export default function SecretsPanel() {
+  const [names, setNames] = useState<string[]>([]);
+  const [name, setName] = useState("");
+  const [value, setValue] = useState("");
+  const [error, setError] = useState<string | null>(null);
+
+  function refresh() {
+    listSecrets()
+      .then(setNames)
+      .catch((e) => setError(String(e)));
+  }
+
+  useEffect(refresh, []);
+
+  async function handleAdd(e: React.FormEvent) {
+    e.preventDefault();
+    if (!name.trim() || !value) return;
+    try {
+      await setSecret(name.trim(), value);
+      setName("");
+      setValue("");
+      setError(null);
+      refresh();
+    } catch (err) {
+      setError(String(err));
+    }
+  }
+
+  async function handleDelete(secret: string) {
+    try {
+      await deleteSecret(secret);
+      setError(null);
+      refresh();
+    } catch (err) {
+      setError(String(err));
+    }
+  }
+
+  return (
+    <div className="space-y-4">
+      <p className="text-xs text-gray-500">
+        Stored in the OS keyring where one is available, otherwise in an
+        encrypted file in the app data folder. Reference a secret from a
+        project&apos;s Secrets field as <code>NAME=secret-name</code>.
+      </p>
+
+      <div className="space-y-1">
+        {names.length === 0 && (
+          <p className="text-sm text-gray-500">No secrets stored.</p>
+        )}
+        {names.map((secret) => (
+          <div
+            key={secret}
+            className="flex items-center justify-between bg-gray-900 rounded-lg px-3 py-2"
+          >
+            <span className="text-sm text-gray-300 font-mono">{secret}</span>
+            <button
+              onClick={() => handleDelete(secret)}
+              className="text-gray-500 hover:text-red-400 transition-colors"
+              title="Delete secret"
+            >
+              <X size={14} />
+            </button>
+          </div>
+        ))}
+      </div>
+
+      <form onSubmit={handleAdd} className="flex gap-2">
+        <input
+          type="text"
+          value={name}
+          onChange={(e) => setName(e.target.value)}
+          placeholder="secret-name"
+          spellCheck={false}
+          className="w-40 bg-gray-900 border border-gray-600 rounded-lg px-3 py-2 text-sm text-white font-mono
+                     placeholder-gray-500 focus:outline-none focus:border-amber-500 focus:ring-1 focus:ring-amber-500"
+        />
+        <input
+          type="password"
+          value={value}
+          onChange={(e) => setValue(e.target.value)}
+          placeholder="value"
+          autoComplete="off"
+          className="flex-1 bg-gray-900 border border-gray-600 rounded-lg px-3 py-2 text-sm text-white font-mono
+                     placeholder-gray-500 focus:outline-none focus:border-amber-500 focus:ring-1 focus:ring-amber-500"
+        />
+        <button
+          type="submit"
+          className="px-3 py-2 bg-amber-600 hover:bg-amber-500 text-white rounded-lg transition-colors"
+          title="Add or replace secret"
+        >
+          <Plus size={16} />
+        </button>
+      </form>
+
+      {error && <p className="text-xs text-red-400">{error}</p>}
+    </div>
+  );
+}
@@ -1,12 +1,16 @@
 import { useState, useEffect } from "react";
 import { open } from "@tauri-apps/plugin-dialog";
 import { invoke } from "@tauri-apps/api/core";
-import { FolderOpen, Plus, X, FileText, RefreshCw, Bell, Tag } from "lucide-react";
+import { FolderOpen, Plus, X, FileText, Bell, Tag, KeyRound, History, Film } from "lucide-react";
 import Modal from "../shared/Modal";
 import FlagToggle from "./FlagToggle";
+import SecretsPanel from "./SecretsPanel";
+import HistoryPanel from "./HistoryPanel";
+import RecordingsPanel from "./RecordingsPanel";
+import LogQueryPanel from "./LogQueryPanel";
 import { agentGlobalFlags, agentCustomFlags, agentPath } from "../../utils/flags";
 import { ALL_AGENTS, getAgent, DEFAULT_AGENT_ID } from "../../agents/registry";
-import { getLogPath, readLog, openLogFolder } from "../../services/log";
+import { getLogPath, openLogFolder, setLogRedaction } from "../../services/log";
 import type { GlobalSettings, AgentId } from "../../types";
 
 interface SettingsModalProps {
@@ -18,7 +22,7 @@ interface SettingsModalProps {
   onClose: () => void;
 }
 
-type SettingsTab = "general" | "logs";
+type SettingsTab = "general" | "secrets" | "history" | "recordings" | "logs";
 
 export default function SettingsModal({
   settings,
@@ -32,8 +36,6 @@ export default function SettingsModal({
   const [tab, setTab] = useState<SettingsTab>("general");
   const [agentId, setAgentId] = useState<AgentId>(DEFAULT_AGENT_ID);
   const [logPath, setLogPath] = useState("");
-  const [logContent, setLogContent] = useState("");
-  const [logLoading, setLogLoading] = useState(false);
   const [terminalProfiles, setTerminalProfiles] = useState<string[]>([]);
   const [chimeBusy, setChimeBusy] = useState(false);
   const [chimeStatus, setChimeStatus] = useState<{ ok: boolean; message: string } | null>(null);
@@ -41,6 +43,10 @@ export default function SettingsModal({
   const [codexNotifyStatus, setCodexNotifyStatus] = useState<{ ok: boolean; message: string } | null>(null);
   const [statuslineBusy, setStatuslineBusy] = useState(false);
   const [statuslineStatus, setStatuslineStatus] = useState<{ ok: boolean; message: string } | null>(null);
+  const [redactPatternsText, setRedactPatternsText] = useState(
+    (settings.logRedactPatterns ?? []).join("\n")
+  );
+  const [redactError, setRedactError] = useState<string | null>(null);
 
   useEffect(() => {
     getLogPath().then(setLogPath).catch(() => {});
@@ -49,15 +55,19 @@ export default function SettingsModal({
       .catch(() => setTerminalProfiles(["PowerShell", "Command Prompt"]));
   }, []);
 
-  async function handleLoadLog() {
-    setLogLoading(true);
+  async function applyRedaction(redactHome: boolean, patternsText: string) {
+    const patterns = patternsText
+      .split("\n")
+      .map((p) => p.trim())
+      .filter(Boolean);
     try {
-      const content = await readLog(200);
-      setLogContent(content);
+      // Validate in the backend first so a bad regex is never saved.
+      await setLogRedaction(redactHome, patterns);
+      setRedactError(null);
+      onUpdateSettings({ logRedactHome: redactHome, logRedactPatterns: patterns });
     } catch (e) {
-      setLogContent(`Error reading log: ${e}`);
+      setRedactError(String(e));
     }
-    setLogLoading(false);
   }
 
   const agent = getAgent(agentId);
@@ -142,10 +152,40 @@ export default function SettingsModal({
           General
         </button>
         <button
-          onClick={() => {
-            setTab("logs");
-            if (!logContent) handleLoadLog();
-          }}
+          onClick={() => setTab("secrets")}
+          className={`px-3 py-2 text-sm font-medium transition-colors border-b-2 flex items-center gap-1.5 ${
+            tab === "secrets"
+              ? "text-amber-400 border-amber-400"
+              : "text-gray-400 border-transparent hover:text-gray-200"
+          }`}
+        >
+          <KeyRound size={14} />
+          Secrets
+        </button>
+        <button
+          onClick={() => setTab("history")}
+          className={`px-3 py-2 text-sm font-medium transition-colors border-b-2 flex items-center gap-1.5 ${
+            tab === "history"
+              ? "text-amber-400 border-amber-400"
+              : "text-gray-400 border-transparent hover:text-gray-200"
+          }`}
+        >
+          <History size={14} />
+          History
+        </button>
+        <button
+          onClick={() => setTab("recordings")}
+          className={`px-3 py-2 text-sm font-medium transition-colors border-b-2 flex items-center gap-1.5 ${
+            tab === "recordings"
+              ? "text-amber-400 border-amber-400"
+              : "text-gray-400 border-transparent hover:text-gray-200"
+          }`}
+        >
+          <Film size={14} />
+          Recordings
+        </button>
+        <button
+          onClick={() => setTab("logs")}
           className={`px-3 py-2 text-sm font-medium transition-colors border-b-2 flex items-center gap-1.5 ${
             tab === "logs"
               ? "text-amber-400 border-amber-400"
@@ -293,6 +333,24 @@ export default function SettingsModal({
             />
           </div>
 
+          {/* IDE session recording */}
+          <div>
+            <FlagToggle
+              label="Record IDE sessions"
+              description="Save each new IDE session as an asciicast (.cast) recording, listed under Recordings."
+              enabled={settings.ideRecord ?? false}
+              onToggle={() => onUpdateSettings({ ideRecord: !settings.ideRecord })}
+            />
+            {settings.ideRecord && (
+              <FlagToggle
+                label="Include typed input"
+                description="Also record keystrokes — including anything typed at a password prompt."
+                enabled={settings.ideRecordInput ?? false}
+                onToggle={() => onUpdateSettings({ ideRecordInput: !settings.ideRecordInput })}
+              />
+            )}
+          </div>
+
           {/* Subcommand toggle (Claude's remote control) */}
           {agent.subcommand && (
             <div>
@@ -482,6 +540,12 @@ export default function SettingsModal({
         </div>
       )}
 
+      {tab === "secrets" && <SecretsPanel />}
+
+      {tab === "history" && <HistoryPanel />}
+
+      {tab === "recordings" && <RecordingsPanel />}
+
       {tab === "logs" && (
         <div className="space-y-4">
           {/* Log File Path */}
@@ -506,28 +570,63 @@ export default function SettingsModal({
             </div>
           </div>
 
-          {/* Log Viewer */}
+          {/* Retention */}
           <div>
-            <div className="flex items-center justify-between mb-2">
-              <h3 className="text-sm font-medium text-gray-300">
-                Recent Log Entries
-              </h3>
-              <button
-                onClick={handleLoadLog}
-                disabled={logLoading}
-                className="flex items-center gap-1 text-xs text-amber-400 hover:text-amber-300 transition-colors"
-              >
-                <RefreshCw
-                  size={12}
-                  className={logLoading ? "animate-spin" : ""}
-                />
-                Refresh
-              </button>
-            </div>
-            <pre className="bg-gray-950 border border-gray-700 rounded-lg p-3 text-xs text-gray-300 font-mono overflow-auto max-h-64 whitespace-pre-wrap">
-              {logContent || "Click Refresh to load log entries."}
-            </pre>
+            <label className="block text-sm font-medium text-gray-300 mb-1">
+              Keep Old Logs For
+            </label>
+            <select
+              value={settings.logRetentionDays ?? 14}
+              onChange={(e) => onUpdateSettings({ logRetentionDays: Number(e.target.value) })}
+              className="bg-gray-900 border border-gray-600 rounded-lg px-3 py-2 text-sm text-white
+                         focus:outline-none focus:border-amber-500 focus:ring-1 focus:ring-amber-500"
+            >
+              {[3, 7, 14, 30, 90].map((d) => (
+                <option key={d} value={d}>
+                  {d} days
+                </option>
+              ))}
+            </select>
+            <p className="text-xs text-gray-500 mt-1">
+              The log starts a new file at 5 MB or once a day; older files are
+              compressed and deleted after this long.
+            </p>
           </div>
+
+          {/* Redaction */}
+          <div>
+            <h3 className="text-sm font-medium text-gray-300 mb-2">
+              Redaction
+            </h3>
+            <p className="text-xs text-gray-500 mb-3">
+              API keys, bearer tokens and <span className="font-mono">--config=</span>{" "}
+              values are always removed before anything is written to the log.
+              Add your own patterns (one regex per line) below.
+            </p>
+            <label className="flex items-center gap-2 text-sm text-gray-300 mb-2">
+              <input
+                type="checkbox"
+                checked={settings.logRedactHome ?? false}
+                onChange={(e) => applyRedaction(e.target.checked, redactPatternsText)}
+                className="accent-amber-500"
+              />
+              Replace home directory with <span className="font-mono">~</span>
+            </label>
+            <textarea
+              value={redactPatternsText}
+              onChange={(e) => setRedactPatternsText(e.target.value)}
+              onBlur={() => applyRedaction(settings.logRedactHome ?? false, redactPatternsText)}
+              placeholder="e.g. acme-[0-9]+"
+              rows={2}
+              spellCheck={false}
+              className="w-full bg-gray-900 border border-gray-600 rounded-lg px-3 py-2 text-sm text-white font-mono
+                         placeholder-gray-500 focus:outline-none focus:border-amber-500 focus:ring-1 focus:ring-amber-500 resize-y"
+            />
+            {redactError && <p className="text-xs text-red-400 mt-1">{redactError}</p>}
+          </div>
+
+          {/* Log Viewer */}
+          <LogQueryPanel />
         </div>
       )}
     </Modal>
@@ -1,7 +1,15 @@
 import { useState, useEffect, useCallback, useRef } from "react";
 import { listen } from "@tauri-apps/api/event";
-import type { Project, GlobalSettings, Session, SessionStatus } from "../types";
-import { resolveSessionFlags } from "../services/ide";
+import type {
+  Project,
+  GlobalSettings,
+  PtySessionInfo,
+  Session,
+  SessionEvent,
+  SessionEventPayload,
+  SessionStatus,
+} from "../types";
+import { listPtySessions, resolveSessionFlags } from "../services/ide";
 
 interface SessionStatePayload {
   sessionId: string;
@@ -12,18 +20,80 @@ interface ExitPayload {
   code: number;
 }
 
+function samePath(a: string, b: string): boolean {
+  const norm = (p: string) => p.replace(/[/\\]+$/, "").replace(/\\/g, "/").toLowerCase();
+  return norm(a) === norm(b);
+}
+
+/** The rail's "doing right now" line for a hook event; undefined clears it. */
+function activityOf(event: SessionEvent): string | undefined {
+  switch (event.kind) {
+    case "toolStarted":
+      return event.tool ? `Running ${event.tool}` : "Running a tool";
+    case "compactionStarted":
+      return "Compacting…";
+    case "subagentStopped":
+      return "Subagent finished";
+    default:
+      return undefined;
+  }
+}
+
+/** A session card for a PTY the backend has but this list doesn't. */
+function sessionFromInfo(info: PtySessionInfo, projects: Project[]): Session {
+  const project = projects.find((p) => samePath(p.path, info.cwd));
+  const startedAt = Date.parse(info.startedAt) || Date.now();
+  return {
+    id: info.sessionId,
+    projectId: project?.id ?? "",
+    title: project
+      ? project.tabTitle?.trim() || project.name
+      : info.cwd.split(/[/\\]/).filter(Boolean).pop() ?? info.cwd,
+    cwd: info.cwd,
+    model: project?.model,
+    color: project?.color,
+    flags: info.flags,
+    status: "idle",
+    startedAt,
+    lastActivityAt: Date.parse(info.lastOutputAt ?? "") || startedAt,
+    unseen: false,
+    reattach: true,
+  };
+}
+
 /**
  * Runtime-only session state for IDE Mode. Owns the session list, the active
  * selection, and the listeners that turn backend events (hook attention pings,
- * PTY exits) into blink/banner state.
+ * PTY exits) into blink/banner state. The list itself is the backend's: on
+ * mount it adopts every PTY still running (a reload keeps them alive), and
+ * `pty-spawned` adds any it somehow missed.
  */
-export function useSessions() {
+export function useSessions(projects: Project[]) {
   const [sessions, setSessions] = useState<Session[]>([]);
   const [activeId, setActiveId] = useState<string | null>(null);
   const activeRef = useRef<string | null>(null);
   activeRef.current = activeId;
   // Last time each session produced PTY output — drives Working vs Idle.
   const lastOutputRef = useRef<Record<string, number>>({});
+  const projectsRef = useRef(projects);
+  projectsRef.current = projects;
+
+  /** Add cards for backend PTYs not already in the list, to be reattached. */
+  const adoptSessions = useCallback((live: PtySessionInfo[]) => {
+    setSessions((prev) => {
+      const known = new Set(prev.map((s) => s.id));
+      const adopted = live
+        .filter((info) => !known.has(info.sessionId))
+        .map((info) => sessionFromInfo(info, projectsRef.current));
+      if (adopted.length === 0) return prev;
+      if (activeRef.current === null) setActiveId(adopted[0].id);
+      return [...prev, ...adopted];
+    });
+  }, []);
+
+  useEffect(() => {
+    listPtySessions().then(adoptSessions).catch(() => {});
+  }, [adoptSessions]);
 
   const createSession = useCallback(
     (project: Project, settings: GlobalSettings): string => {
@@ -185,6 +255,35 @@ export function useSessions() {
       );
     }).then((u) => unlisteners.push(u));
 
+    // Finer-grained hooks: what the agent is doing mid-turn. A prompt or a tool
+    // call is also proof a turn is under way, so an idle card (e.g. one whose
+    // prompt came from a reattached terminal) is promoted to working. Turn ends
+    // still arrive through session-state. The hook's details carry the
+    // Notification's text for the waiting banner, and the agent's transcript.
+    listen<SessionEventPayload>("session-event", (e) => {
+      const { sessionId, details } = e.payload;
+      const event: SessionEvent = e.payload;
+      const midTurn = event.kind === "toolStarted" || event.kind === "promptSubmitted";
+      if (midTurn) lastOutputRef.current[sessionId] = Date.now();
+      setSessions((prev) =>
+        prev.map((s) =>
+          s.id === sessionId
+            ? {
+                ...s,
+                activity: activityOf(event),
+                status: midTurn && s.status === "idle" ? ("working" as const) : s.status,
+                attention: event.kind === "notification" ? details?.message : undefined,
+                transcriptPath: details?.transcriptPath ?? s.transcriptPath,
+              }
+            : s
+        )
+      );
+    }).then((u) => unlisteners.push(u));
+
+    listen<PtySessionInfo>("pty-spawned", (e) => {
+      adoptSessions([e.payload]);
+    }).then((u) => unlisteners.push(u));
+
     listen<ExitPayload>("pty-exit", (e) => {
       const { sessionId, code } = e.payload;
       // Show "Exited" briefly, then auto-remove the card (no lingering dead state).
@@ -195,6 +294,7 @@ export function useSessions() {
                 ...s,
                 status: "exited",
                 exitCode: code,
+                activity: undefined,
                 unseen: activeRef.current !== sessionId,
               }
             : s
@@ -212,7 +312,7 @@ export function useSessions() {
     }).then((u) => unlisteners.push(u));
 
     return () => unlisteners.forEach((u) => u());
-  }, []);
+  }, [adoptSessions]);
 
   return {
     sessions,
@@ -2,6 +2,7 @@ import { useState, useEffect, useCallback } from "react";
 import type { GlobalSettings, AgentId } from "../types";
 import { loadAppData, saveSettings } from "../services/store";
 import { detectAgentPath } from "../services/launcher";
+import { setLogRedaction, setLogRetention } from "../services/log";
 import { ALL_AGENTS } from "../agents/registry";
 import { agentGlobalFlags, agentCustomFlags } from "../utils/flags";
 
@@ -35,6 +36,25 @@ export function useSettings() {
     })();
   }, []);
 
+  // The backend starts with only its built-in redaction patterns; hand it the
+  // user's additions once settings are loaded and whenever they change.
+  const logRedactHome = settings?.logRedactHome ?? false;
+  const logRedactPatterns = settings?.logRedactPatterns;
+  useEffect(() => {
+    if (!settings) return;
+    setLogRedaction(logRedactHome, logRedactPatterns ?? []).catch(() => {
+      /* an invalid pattern is reported where it is edited, in Settings */
+    });
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [settings === null, logRedactHome, logRedactPatterns]);
+
+  const logRetentionDays = settings?.logRetentionDays;
+  useEffect(() => {
+    if (!settings || logRetentionDays === undefined) return;
+    setLogRetention(logRetentionDays).catch(() => {});
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [settings === null, logRetentionDays]);
+
   const updateSettings = useCallback(
     async (partial: Partial<GlobalSettings>) => {
       if (!settings) return;
@@ -0,0 +1,12 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { HistorySession, HistoryStats } from "../types";
+
+/** Past launches and IDE sessions, newest first. */
+export async function launchHistory(project?: string, limit?: number): Promise<HistorySession[]> {
+  return invoke<HistorySession[]>("launch_history", { project, limit });
+}
+
+/** Per-project and overall stats for launches started at or after `since` (RFC 3339). */
+export async function launchStats(since?: string): Promise<HistoryStats> {
+  return invoke<HistoryStats>("launch_stats", { since });
+}
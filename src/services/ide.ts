@@ -1,5 +1,5 @@
 import { invoke, Channel } from "@tauri-apps/api/core";
-import type { Project, GlobalSettings } from "../types";
+import type { Project, GlobalSettings, PtySessionInfo } from "../types";
 import { resolveAgentRequest } from "./launcher";
 
 /** Build the resolved flag list (incl. the model flag) for a project, as the wt path does. */
@@ -10,7 +10,12 @@ export function resolveSessionFlags(
   return resolveAgentRequest(project, settings).flags;
 }
 
-/** Spawn an embedded PTY running the project's agent. Output streams via `onOutput`. */
+/**
+ * Spawn an embedded PTY running the project's agent. Output streams via
+ * `onOutput`. `profile` overrides the project's default launch profile (null
+ * for none). Recorded when `settings.ideRecord` is on. Rejects with a
+ * `LaunchError`.
+ */
 export async function spawnPty(
   sessionId: string,
   project: Project,
@@ -18,7 +23,8 @@ export async function spawnPty(
   flags: string[],
   cols: number,
   rows: number,
-  onOutput: Channel<number[]>
+  onOutput: Channel<number[]>,
+  profile?: string | null
 ): Promise<void> {
   const { agentPath, subcommand, claudeFeatures, notifyHook } =
     resolveAgentRequest(project, settings);
@@ -27,6 +33,7 @@ export async function spawnPty(
     cols,
     rows,
     onOutput,
+    recording: settings.ideRecord ? { input: settings.ideRecordInput ?? false } : null,
     request: {
       agentPath,
       projectPath: project.path,
@@ -40,10 +47,22 @@ export async function spawnPty(
       tabTitle: project.tabTitle?.trim() || project.name,
       dynamicTitle: project.dynamicTitle ?? false,
       ideRenderer: project.ideRenderer ?? settings.ideRenderer ?? "fullscreen",
+      launchProfile: profile === undefined ? project.launchProfile ?? null : profile,
+      env: project.env ?? {},
+      secretEnv: project.secretEnv ?? {},
     },
   });
 }
 
+/**
+ * Attach `onOutput` to a session that is already running, e.g. after a
+ * reload: its scrollback arrives first, then live output. Rejects if the
+ * session has exited.
+ */
+export function attachPty(sessionId: string, onOutput: Channel<number[]>): Promise<void> {
+  return invoke("attach_pty", { sessionId, onOutput });
+}
+
 /** Windows build number (0 if unreadable) — feeds xterm.js's `windowsPty` hint. */
 export function getOsBuild(): Promise<number> {
   return invoke<number>("get_os_build");
@@ -61,6 +80,11 @@ export function killPty(sessionId: string): Promise<void> {
   return invoke("kill_pty", { sessionId });
 }
 
+/** Every PTY session the backend has running, oldest first. */
+export function listPtySessions(): Promise<PtySessionInfo[]> {
+  return invoke<PtySessionInfo[]>("list_pty_sessions");
+}
+
 /** Ensure the IDE attention hooks (Stop/Notification → app) are installed. */
 export function ensureIdeHooks(): Promise<string> {
   return invoke<string>("ensure_ide_hooks");
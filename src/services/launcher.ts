@@ -1,5 +1,14 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { Project, GlobalSettings, LaunchResult, AgentId } from "../types";
+import type {
+  Project,
+  GlobalSettings,
+  LaunchError,
+  LaunchResult,
+  LaunchPreview,
+  LaunchProfile,
+  AgentId,
+  TmuxWindow,
+} from "../types";
 import { resolveFlags, agentPath } from "../utils/flags";
 import { getAgent } from "../agents/registry";
 
@@ -30,31 +39,122 @@ export function resolveAgentRequest(project: Project, settings: GlobalSettings)
   };
 }
 
-export async function launchProject(
+/**
+ * The `LaunchRequest` the backend receives for a Launcher-Mode launch.
+ * `profile` overrides the project's default launch profile for this launch;
+ * pass null to launch with none.
+ */
+function buildLaunchRequest(
   project: Project,
-  settings: GlobalSettings
-): Promise<LaunchResult> {
+  settings: GlobalSettings,
+  profile?: string | null
+) {
   const { flags, agentPath, subcommand, claudeFeatures, notifyHook } =
     resolveAgentRequest(project, settings);
 
-  const result = await invoke<LaunchResult>("launch_agent", {
-    request: {
-      agentPath,
-      projectPath: project.path,
-      terminalProfile: settings.terminalProfile,
-      flags,
-      subcommand,
-      claudeFeatures,
-      notifyHook,
-      preLaunchCommand: project.preLaunchCommand ?? null,
-      tabColor: project.color ?? null,
-      tabTitle: project.tabTitle?.trim() || project.name,
-      dynamicTitle: project.dynamicTitle ?? false,
-      modelInTitle: project.modelInTitle ?? false,
-    },
+  return {
+    agentPath,
+    projectPath: project.path,
+    terminalProfile: settings.terminalProfile,
+    flags,
+    subcommand,
+    claudeFeatures,
+    notifyHook,
+    preLaunchCommand: project.preLaunchCommand ?? null,
+    tabColor: project.color ?? null,
+    tabTitle: project.tabTitle?.trim() || project.name,
+    dynamicTitle: project.dynamicTitle ?? false,
+    modelInTitle: project.modelInTitle ?? false,
+    terminalOrder: settings.terminalOrder ?? [],
+    multiplexer: project.multiplexer ?? null,
+    multiplexerSession: project.multiplexerSession ?? null,
+    launchProfile: profile === undefined ? project.launchProfile ?? null : profile,
+    env: project.env ?? {},
+    secretEnv: project.secretEnv ?? {},
+  };
+}
+
+export async function launchProject(
+  project: Project,
+  settings: GlobalSettings,
+  profile?: string | null
+): Promise<LaunchResult> {
+  return invoke<LaunchResult>("launch_agent", {
+    request: buildLaunchRequest(project, settings, profile),
   });
+}
 
-  return result;
+/**
+ * Dry run of `launchProject`: the backend validates the request exactly as a
+ * launch would and returns what it would run and write, without doing either.
+ * Rejects with the launch's own `LaunchError` when validation fails.
+ */
+export async function previewLaunch(
+  project: Project,
+  settings: GlobalSettings,
+  profile?: string | null
+): Promise<LaunchPreview> {
+  return invoke<LaunchPreview>("preview_launch", {
+    request: buildLaunchRequest(project, settings, profile),
+  });
+}
+
+/** The backend's built-in launch profiles ("review", "yolo", "offline"). */
+export async function listLaunchProfiles(): Promise<LaunchProfile[]> {
+  return invoke<LaunchProfile[]>("list_launch_profiles");
+}
+
+/** One-line description of a launch failure, worded as the backend logs it. */
+export function describeLaunchError(error: LaunchError): string {
+  switch (error.kind) {
+    case "validation":
+      return error.reason;
+    case "missingPath":
+      if (error.field === "projectPath") return `Project directory does not exist: ${error.path}`;
+      if (error.field === "agentPath") return `Agent executable not found: ${error.path}`;
+      return `${error.field} does not exist: ${error.path}`;
+    case "unsupported":
+      return `${error.field} is not supported on ${error.platform}`;
+    case "missingSecret":
+      return `Secret not found: ${error.name}`;
+    case "spawn":
+      return `${error.program} spawn failed: ${error.message}`;
+    case "exited":
+      return `${error.program} exited with code ${error.code ?? "none"}${error.stderr ? `: ${error.stderr}` : ""}`;
+    case "io":
+      return `${error.context}: ${error.message}`;
+    case "fallbackExhausted": {
+      const tried = error.attempts.map((a) => a.backend).join(", ");
+      const last = error.attempts[error.attempts.length - 1];
+      return `No launch method succeeded (tried: ${tried})${last ? `; last error: ${describeLaunchError(last.error)}` : ""}`;
+    }
+  }
+}
+
+/**
+ * True when the failure points at the configured agent executable — it is
+ * missing, or the OS could not find it at spawn time — so re-running agent
+ * detection is the likely fix.
+ */
+export function isAgentPathError(error: LaunchError, agentPath: string): boolean {
+  if (error.kind === "missingPath") return error.field === "agentPath";
+  if (error.kind === "validation") return error.field === "agentPath";
+  if (error.kind === "spawn") return error.program === agentPath && error.osErrorKind === "NotFound";
+  return false;
+}
+
+/** Names of the secrets in the backend store. Values are never returned. */
+export async function listSecrets(): Promise<string[]> {
+  return invoke<string[]>("list_secrets");
+}
+
+/** Store (or replace) a secret in the OS keyring, or the encrypted fallback. */
+export async function setSecret(name: string, value: string): Promise<void> {
+  return invoke("set_secret", { name, value });
+}
+
+export async function deleteSecret(name: string): Promise<void> {
+  return invoke("delete_secret", { name });
 }
 
 export async function detectAgentPath(agentId: AgentId): Promise<string> {
@@ -65,3 +165,13 @@ export async function detectAgentPath(agentId: AgentId): Promise<string> {
 export async function launchShell(shell: "cmd" | "pwsh"): Promise<LaunchResult> {
   return invoke<LaunchResult>("launch_shell", { shell });
 }
+
+/** Live tmux windows started by the launcher (empty when tmux isn't running). */
+export async function listTmuxWindows(): Promise<TmuxWindow[]> {
+  return invoke<TmuxWindow[]>("list_tmux_windows");
+}
+
+/** Make a launcher-owned tmux window current in its session. */
+export async function focusTmuxWindow(windowId: string): Promise<void> {
+  return invoke("focus_tmux_window", { windowId });
+}
@@ -1,4 +1,5 @@
-import { invoke } from "@tauri-apps/api/core";
+import { Channel, invoke } from "@tauri-apps/api/core";
+import type { LogEntry, LogPage, LogQuery } from "../types";
 
 export async function getLogPath(): Promise<string> {
   return invoke<string>("get_log_path");
@@ -8,6 +9,40 @@ export async function readLog(tailLines?: number): Promise<string> {
   return invoke<string>("read_log", { tailLines });
 }
 
+/** Structured log entries matching `query`, newest first. */
+export async function queryLog(query: LogQuery): Promise<LogPage> {
+  return invoke<LogPage>("query_log", { query });
+}
+
+/**
+ * Call `onEntry` with each log entry as it is written, at `minLevel` or above
+ * when given. Resolves to a function that ends the subscription.
+ */
+export async function subscribeLog(
+  onEntry: (entry: LogEntry) => void,
+  minLevel?: string
+): Promise<() => void> {
+  const channel = new Channel<LogEntry>();
+  channel.onmessage = onEntry;
+  const subscription = await invoke<number>("subscribe_log", { minLevel, onEntry: channel });
+  return () => {
+    invoke("unsubscribe_log", { subscription }).catch(() => {});
+  };
+}
+
 export async function openLogFolder(): Promise<void> {
   await invoke("open_log_folder");
 }
+
+/**
+ * Apply the user's log redaction settings in the backend. Rejects with the
+ * backend's message when a pattern is not a valid regex.
+ */
+export async function setLogRedaction(redactHome: boolean, patterns: string[]): Promise<void> {
+  await invoke("set_log_redaction", { config: { redactHome, patterns } });
+}
+
+/** Keep rotated log segments for `days` days (1–365). */
+export async function setLogRetention(days: number): Promise<void> {
+  await invoke("set_log_retention", { days });
+}
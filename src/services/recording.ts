@@ -0,0 +1,54 @@
+import { Channel, invoke } from "@tauri-apps/api/core";
+import type { RecordingInfo, ReplayControl } from "../types";
+
+/** Stored session recordings, newest first. */
+export async function listRecordings(): Promise<RecordingInfo[]> {
+  return invoke<RecordingInfo[]>("list_recordings");
+}
+
+/** Copy a recording's `.cast` file to `destination`. */
+export async function exportRecording(id: string, destination: string): Promise<void> {
+  return invoke("export_recording", { id, destination });
+}
+
+/**
+ * Write a plain-text or Markdown transcript of a session to `destination`:
+ * from its recording when there is one, else from a live session's
+ * scrollback.
+ */
+export async function exportTranscript(
+  id: string,
+  format: "text" | "markdown",
+  destination: string
+): Promise<void> {
+  return invoke("export_transcript", { id, format, destination });
+}
+
+/** Rejects while the session is still recording. */
+export async function deleteRecording(id: string): Promise<void> {
+  return invoke("delete_recording", { id });
+}
+
+export interface ReplayInfo {
+  replayId: number;
+  duration: number;
+  cols: number;
+  rows: number;
+}
+
+/**
+ * Play a recording into `onOutput`, shaped like a live session's output.
+ * Idle gaps longer than `idleLimit` seconds are shortened to it. Progress
+ * arrives as `replay-state` events.
+ */
+export async function startReplay(
+  id: string,
+  onOutput: Channel<number[]>,
+  options: { speed?: number; idleLimit?: number } = {}
+): Promise<ReplayInfo> {
+  return invoke<ReplayInfo>("start_replay", { id, options, onOutput });
+}
+
+export async function controlReplay(replayId: number, control: ReplayControl): Promise<void> {
+  return invoke("control_replay", { replayId, control });
+}
@@ -41,6 +41,54 @@ export interface Project {
    * scrollback renderer (CLAUDE_CODE_DISABLE_ALTERNATE_SCREEN).
    */
   ideRenderer?: IdeRenderer;
+  /**
+   * Launch into a tmux/screen session window instead of a terminal tab, so
+   * the agent survives disconnects. Unset uses the terminal.
+   */
+  multiplexer?: Multiplexer;
+  /** Multiplexer session to create or reuse. Defaults to "claude-launcher". */
+  multiplexerSession?: string;
+  /**
+   * Name of the built-in launch profile (see `listLaunchProfiles`) applied
+   * by default when this project launches. Unset launches with none.
+   */
+  launchProfile?: string;
+  /**
+   * Extra environment for this project's agent (API base URLs, model, proxy
+   * settings). Validated by the backend; secret-looking values are redacted
+   * from the launch log.
+   */
+  env?: Record<string, string>;
+  /**
+   * Env vars filled from the backend secret store at launch: env var name →
+   * secret name (see `listSecrets`). Values never leave the backend.
+   */
+  secretEnv?: Record<string, string>;
+}
+
+/** A backend-defined bundle of flags, env vars, pre-launch steps and renderer. */
+export interface LaunchProfile {
+  name: string;
+  description: string;
+  flags: string[];
+  env: Record<string, string>;
+  preLaunch: string[];
+  renderer: IdeRenderer | null;
+  /** Rejected by the backend for agents other than Claude Code. */
+  claudeOnly: boolean;
+}
+
+export type Multiplexer = "tmux" | "screen";
+
+/** A live launcher-owned tmux window, as reported by `list_tmux_windows`. */
+export interface TmuxWindow {
+  session: string;
+  windowId: string;
+  name: string;
+  cwd: string;
+  active: boolean;
+  /** The CLAUDE_LAUNCHER_SESSION id the window's agent was started with. */
+  launcherSession: string;
 }
 
 /** Which Claude Code renderer an embedded IDE-mode session runs with. */
@@ -91,6 +139,12 @@ export interface GlobalSettings {
   remoteControl: boolean;
 
   terminalProfile: string;
+  /**
+   * Non-Windows only: terminal emulators to try, in order (e.g.
+   * ["kitty", "xterm"]). Unset or empty uses the backend's built-in order:
+   * gnome-terminal, konsole, kitty, wezterm, xterm.
+   */
+  terminalOrder?: string[];
   /** Which top-level UI is shown. Defaults to "launcher". */
   uiMode: UiMode;
   /**
@@ -112,6 +166,13 @@ export interface GlobalSettings {
    * Defaults to IDE_FONT_SIZE_DEFAULT.
    */
   ideFontSize?: number;
+  /**
+   * Record IDE sessions as asciicast v2 files (see recording.rs), listed in
+   * Settings → Recordings. Applies to newly opened sessions. Defaults to false.
+   */
+  ideRecord?: boolean;
+  /** Also record what is typed, including at password prompts. Defaults to false. */
+  ideRecordInput?: boolean;
   /**
    * Install a `notify` turn-completion callback for agents that support one
    * (currently Codex), in both Windows Terminal tabs and IDE sessions. Gives a
@@ -121,6 +182,19 @@ export interface GlobalSettings {
    * only symptom is silence. See CODEX_NOTIFY_TEMPLATE in lib.rs.
    */
   agentNotifyHook?: boolean;
+  /**
+   * Replace the home directory with `~` in the launch log. Built-in secret
+   * patterns (API keys, bearer tokens, `--config=` values) are always
+   * redacted; this and `logRedactPatterns` add to them.
+   */
+  logRedactHome?: boolean;
+  /** Extra regexes redacted from the launch log before it is written. */
+  logRedactPatterns?: string[];
+  /**
+   * Days to keep rotated launch log segments (1–365). The log rotates at
+   * 5 MB or daily, whichever comes first. Defaults to 14.
+   */
+  logRetentionDays?: number;
 }
 
 /** Bounds for `GlobalSettings.ideFontSize`. */
@@ -163,6 +237,58 @@ export interface Session {
   startedAt: number; // epoch ms
   lastActivityAt: number; // epoch ms — drives the idle timer
   unseen: boolean; // true while blinking; cleared on focus
+  /** What the agent is doing right now, from its hooks (e.g. "Running Bash"). */
+  activity?: string;
+  /** The last Notification's message ("Claude needs your permission to use Bash"). */
+  attention?: string;
+  /** The agent's own JSONL transcript, as its hooks last reported it. */
+  transcriptPath?: string;
+  /** Already running in the backend (e.g. from before a reload): attach to it rather than spawn. */
+  reattach?: boolean;
+}
+
+/** A Claude Code hook firing in a session, as `session-event` reports it. */
+export type SessionEvent =
+  | { kind: "stopped" }
+  | { kind: "notification" }
+  | { kind: "toolStarted"; tool?: string | null }
+  | { kind: "toolFinished"; tool?: string | null }
+  | { kind: "promptSubmitted" }
+  | { kind: "sessionStarted" }
+  | { kind: "sessionEnded" }
+  | { kind: "subagentStopped" }
+  | { kind: "compactionStarted" };
+
+/** The checked subset of a hook's own payload; any field may be missing. */
+export interface HookDetails {
+  transcriptPath?: string;
+  /** Claude Code's id for the conversation; changes on /clear. */
+  agentSessionId?: string;
+  message?: string;
+  toolName?: string;
+  /** The tool call's command, file, pattern or URL, clipped. */
+  toolTarget?: string;
+}
+
+export type SessionEventPayload = SessionEvent & {
+  sessionId: string;
+  /** RFC 3339. */
+  at: string;
+  details?: HookDetails;
+};
+
+/** A live backend PTY, as `list_pty_sessions` and `pty-spawned` report it. */
+export interface PtySessionInfo {
+  sessionId: string;
+  pid: number | null;
+  cwd: string;
+  agentPath: string;
+  flags: string[];
+  /** RFC 3339. */
+  startedAt: string;
+  cols: number;
+  rows: number;
+  lastOutputAt: string | null;
 }
 
 export interface AppData {
@@ -178,8 +304,171 @@ export interface SortConfig {
   direction: SortDirection;
 }
 
+/**
+ * Why a launch failed, as reported by `launch_agent`, `launch_shell`,
+ * `preview_launch` and `spawn_pty`. `field` names the offending
+ * `LaunchRequest` field (camelCase, e.g. "agentPath").
+ */
+export type LaunchError =
+  | { kind: "validation"; field: string; reason: string }
+  | { kind: "missingPath"; field: string; path: string }
+  | { kind: "unsupported"; field: string; platform: string }
+  | { kind: "missingSecret"; name: string }
+  | { kind: "spawn"; program: string; osErrorKind: string; message: string }
+  | { kind: "exited"; program: string; code: number | null; stderr: string }
+  | { kind: "io"; context: string; message: string }
+  | { kind: "fallbackExhausted"; attempts: { backend: string; error: LaunchError }[] };
+
 export interface LaunchResult {
   success: boolean;
   command: string;
+  error: LaunchError | null;
+}
+
+/** One spawn the backend would make, as resolved by `preview_launch`. */
+export interface LaunchPlan {
+  program: string;
+  args: string[];
+  /** Env vars set on the child. */
+  env: Record<string, string>;
+  /** Env vars stripped from the inherited environment. */
+  envRemove: string[];
+  cwd: string | null;
+  /** A script written before the spawn (the wt pre-launch script). */
+  script: { path: string; contents: string } | null;
+}
+
+/** A write a launch makes besides the spawn itself. */
+export type LaunchSideEffect =
+  | { kind: "userEnvVar"; name: string; value: string }
+  | { kind: "tabNameMap"; path: string; key: string; name: string }
+  | { kind: "notifyScript"; path: string };
+
+export interface LaunchPreview {
+  /** Terminal launches: tried in order until one starts. Multiplexer: run in order for a new session. */
+  plans: LaunchPlan[];
+  /** Multiplexer launches only: run instead of `plans` when the session already exists. */
+  reusePlans: LaunchPlan[];
+  sideEffects: LaunchSideEffect[];
+}
+
+/** What a structured log entry records (see launch_log.rs). */
+export type LogEventKind =
+  | "message"
+  | "launch"
+  | "ptySpawn"
+  | "ptyExit"
+  | "sessionState"
+  | "rejectedPing";
+
+/** One entry of the structured launch log. */
+export interface LogEntry {
+  /** RFC 3339 with the machine's local offset. */
+  timestamp: string;
+  level: string;
+  event: LogEventKind;
+  message: string;
+  project?: string;
+  sessionId?: string;
+  /** Agent executable name, e.g. "claude". */
+  agent?: string;
+  durationMs?: number;
+  outcome?: "success" | "failure";
+}
+
+/** Filters for `queryLog`. Empty lists and unset fields match everything. */
+export interface LogQuery {
+  levels?: string[];
+  events?: LogEventKind[];
+  project?: string;
+  /** RFC 3339; inclusive. */
+  since?: string;
+  /** RFC 3339; exclusive. */
+  until?: string;
+  offset?: number;
+  limit?: number;
+}
+
+/** A page of `queryLog` results, newest first; `total` counts all matches. */
+export interface LogPage {
+  entries: LogEntry[];
+  total: number;
+}
+
+/** Where a launch ran (see history.rs). */
+export type LaunchMode = "terminal" | "multiplexer" | "ide";
+
+/** One past launch or IDE session from the launch history. */
+export interface HistorySession {
+  id: string;
+  project: string;
+  /** Agent executable name, e.g. "claude". */
+  agent: string;
+  flags: string[];
+  mode: LaunchMode;
+  startedAt: string;
+  /** IDE sessions and failed launches only. */
+  endedAt: string | null;
+  exitCode: number | null;
   error: string | null;
+  /** Ended by closing the session rather than exiting on its own. */
+  killed: boolean;
+  completions: number;
+  waits: number;
+}
+
+/** Launch history totals for one project (or, as `total`, all of them). */
+export interface ProjectStats {
+  project: string;
+  launches: number;
+  failures: number;
+  /** 0–1. */
+  failureRate: number;
+  /** IDE sessions only: terminal launches are not tracked past the launch. */
+  agentHours: number;
+  completions: number;
+  waits: number;
+  lastLaunchedAt: string | null;
 }
+
+export interface HistoryStats {
+  /** Most launched first. */
+  projects: ProjectStats[];
+  total: ProjectStats;
+}
+
+/** A stored session recording (asciicast v2). */
+export interface RecordingInfo {
+  /** The session id. */
+  id: string;
+  title: string | null;
+  /** RFC 3339. */
+  startedAt: string | null;
+  /** Seconds. */
+  duration: number;
+  width: number;
+  height: number;
+  sizeBytes: number;
+  /** The session is still running and recording. */
+  live: boolean;
+}
+
+/** Where a replay is, from `replay-state` events. */
+export interface ReplayState {
+  replayId: number;
+  /** Seconds into the replay (idle gaps compressed). */
+  time: number;
+  duration: number;
+  speed: number;
+  paused: boolean;
+  finished: boolean;
+  cols: number;
+  rows: number;
+}
+
+export type ReplayControl =
+  | { action: "pause" }
+  | { action: "resume" }
+  | { action: "seek"; time: number }
+  | { action: "speed"; speed: number }
+  | { action: "stop" };